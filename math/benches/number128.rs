@@ -0,0 +1,33 @@
+//! Number128 multiplication and division, the operations that the
+//! `binary-number128` feature speeds up. Compare the two scalings with
+//!
+//! ```sh
+//! cargo bench --bench number128
+//! cargo bench --bench number128 --features binary-number128
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use jet_proto_math::Number128;
+
+fn mul_div(c: &mut Criterion) {
+    let price = Number128::from_decimal(1_234_567, -4);
+    let amount = Number128::from_decimal(987_654_321, -6);
+    let rate = Number128::from_decimal(105, -2);
+
+    c.bench_function("number128 mul", |b| {
+        b.iter(|| black_box(price) * black_box(amount))
+    });
+    c.bench_function("number128 div", |b| {
+        b.iter(|| black_box(amount) / black_box(price))
+    });
+    c.bench_function("number128 mul chain", |b| {
+        b.iter(|| {
+            (0..10).fold(black_box(amount), |acc, _| {
+                acc * black_box(rate) / black_box(rate)
+            })
+        })
+    });
+}
+
+criterion_group!(benches, mul_div);
+criterion_main!(benches);
@@ -0,0 +1,471 @@
+//! Fuzzes the conversions between the number types and their other
+//! representations, checking that every result stays within the bounds set
+//! by its rounding and that exact conversions round trip.
+//!
+//! Usage: cargo run --release --example fuzz_conversions --features test-vectors -- [--seed n] [--iterations n]
+//!
+//! Without `--iterations` this runs until interrupted. Each iteration `i`
+//! draws its values from seed `seed + i`, so a failure is reproduced by
+//! running one iteration with the seed printed on failure.
+
+use std::fmt::Debug;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use jet_proto_math::bps::{bps_to_number, number_to_bps};
+use jet_proto_math::conversions::{
+    f64_to_number, f64_to_number128, number128_to_number, number128_to_token_amount,
+    number_to_number128, token_amount_to_number, token_amount_to_number128,
+};
+use jet_proto_math::q64::{from_q64_64, to_q64_64};
+use jet_proto_math::test_vectors::Rng;
+use jet_proto_math::{
+    DecimalValue, Number, Number128, NumberP, PodNumber, PodNumber128, Rounding, U192,
+};
+
+const ROUNDINGS: [Rounding; 4] = [
+    Rounding::Down,
+    Rounding::Up,
+    Rounding::HalfUp,
+    Rounding::HalfEven,
+];
+
+const PROGRESS_INTERVAL: u64 = 100_000;
+
+fn main() {
+    let mut seed = None;
+    let mut iterations = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().expect("missing flag value");
+        match arg.as_str() {
+            "--seed" => seed = Some(value.parse::<u64>().expect("invalid seed")),
+            "--iterations" => iterations = Some(value.parse::<u64>().expect("invalid iterations")),
+            _ => panic!("unknown flag {arg}, expected --seed or --iterations"),
+        }
+    }
+
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    });
+    println!("fuzzing conversions with --seed {seed}");
+
+    let start = Instant::now();
+    let mut i = 0u64;
+    let mut next_progress = PROGRESS_INTERVAL;
+    while Some(i) != iterations {
+        let iteration_seed = seed.wrapping_add(i);
+        if std::panic::catch_unwind(|| fuzz(iteration_seed)).is_err() {
+            eprintln!("failed, reproduce with --seed {iteration_seed} --iterations 1");
+            std::process::exit(1);
+        }
+
+        i += 1;
+        if i == next_progress {
+            println!("{i} iterations in {:.1?}", start.elapsed());
+            next_progress += PROGRESS_INTERVAL;
+        }
+    }
+
+    println!("{i} iterations passed in {:.1?}", start.elapsed());
+}
+
+/// Run every check once with values drawn from `seed`
+fn fuzz(seed: u64) {
+    let mut rng = Rng::new(seed);
+
+    number_and_number128(&mut rng);
+    token_amounts(&mut rng);
+    floats(&mut rng);
+    basis_points(&mut rng);
+    q64(&mut rng);
+    integers(&mut rng);
+    precision(&mut rng);
+    significant_figures(&mut rng);
+    representations(&mut rng);
+}
+
+fn rounding(rng: &mut Rng) -> Rounding {
+    ROUNDINGS[(rng.next_u64() % ROUNDINGS.len() as u64) as usize]
+}
+
+/// A value in `[-range, range]` with a uniformly chosen bit length
+fn small_i64(rng: &mut Rng, range: u64) -> i64 {
+    let magnitude = (rng.next_u64() >> (rng.next_u64() % 64)) % (range + 1);
+
+    match rng.next_u64() % 2 {
+        0 => magnitude as i64,
+        _ => -(magnitude as i64),
+    }
+}
+
+/// An `f64` that is usually in the range of the number types, but can be
+/// any bit pattern, including infinities and NaNs
+fn float(rng: &mut Rng) -> f64 {
+    match rng.next_u64() % 4 {
+        0 => f64::from_bits(rng.next_u64()),
+        _ => rng.number_128().as_f64() * (rng.next_u64() as f64 / u64::MAX as f64),
+    }
+}
+
+/// Assert that `value` is between the two bounds, in either order
+fn assert_between<T: PartialOrd + Debug>(value: T, a: T, b: T, input: impl Debug) {
+    let (low, high) = match a <= b {
+        true => (a, b),
+        false => (b, a),
+    };
+
+    assert!(
+        low <= value && value <= high,
+        "{input:?}: {value:?} is not between {low:?} and {high:?}"
+    );
+}
+
+/// Assert that the two results of rounding down and up are at most one raw
+/// unit apart
+fn assert_adjacent(down: i128, up: i128, input: impl Debug) {
+    assert!(
+        down.abs_diff(up) <= 1,
+        "{input:?}: {down} and {up} are more than one unit apart"
+    );
+}
+
+/// Like `assert_adjacent`, for the results of rounding a `Number` down
+/// and up
+fn assert_adjacent_numbers(down: Number, up: Number, input: impl Debug) {
+    assert!(
+        down <= up && up.to_u192() - down.to_u192() <= U192::one(),
+        "{input:?}: {down} and {up} are more than one unit apart"
+    );
+}
+
+fn number_and_number128(rng: &mut Rng) {
+    let n = rng.number();
+    let down = number_to_number128(n, Rounding::Down);
+    let up = number_to_number128(n, Rounding::Up);
+
+    match (down, up) {
+        (Ok(down), Ok(up)) => {
+            assert_between(number_to_number128(n, rounding(rng)).unwrap(), down, up, n);
+            assert_adjacent(down.to_i128(), up.to_i128(), n);
+            assert!(number128_to_number(down, Rounding::Down).unwrap() <= n);
+            assert!(number128_to_number(up, Rounding::Up).unwrap() >= n);
+        }
+        (Ok(down), Err(_)) => assert_eq!(Number128::MAX, down, "{n}"),
+        (Err(_), up) => {
+            assert!(up.is_err(), "{n}: only rounding down overflowed");
+            assert!(n > number128_to_number(Number128::MAX, Rounding::Down).unwrap());
+        }
+    }
+
+    let x = rng.number_128();
+    if x < Number128::ZERO {
+        assert!(number128_to_number(x, rounding(rng)).is_err(), "{x}");
+        return;
+    }
+
+    let down = number128_to_number(x, Rounding::Down).unwrap();
+    let up = number128_to_number(x, Rounding::Up).unwrap();
+    assert_between(number128_to_number(x, rounding(rng)).unwrap(), down, up, x);
+    assert_adjacent_numbers(down, up, x);
+    assert!(number_to_number128(down, Rounding::Down).unwrap() <= x);
+    if let Ok(back) = number_to_number128(up, Rounding::Up) {
+        assert!(back >= x, "{x}: {up} converts back to {back}");
+    }
+}
+
+fn token_amounts(rng: &mut Rng) {
+    let amount = rng.next_u64() >> (rng.next_u64() % 64);
+    let decimals = (rng.next_u64() % 20) as u8;
+    let rounding = rounding(rng);
+    let input = (amount, decimals);
+
+    let down = token_amount_to_number(amount, decimals, Rounding::Down);
+    let up = token_amount_to_number(amount, decimals, Rounding::Up);
+    let n = token_amount_to_number(amount, decimals, rounding);
+    assert_between(n, down, up, input);
+    assert_adjacent_numbers(down, up, input);
+    assert_eq!(Number::from_token_amount(amount, decimals), down);
+    assert!(down.to_token_amount(decimals, Rounding::Down).unwrap() <= amount);
+    if let Ok(back) = up.to_token_amount(decimals, Rounding::Up) {
+        assert!(back >= amount, "{input:?}: {up} converts back to {back}");
+    }
+    if down == up {
+        assert_eq!(Ok(amount), n.to_token_amount(decimals, rounding), "{n}");
+    }
+
+    let down = token_amount_to_number128(amount, decimals, Rounding::Down);
+    let up = token_amount_to_number128(amount, decimals, Rounding::Up);
+    let x = token_amount_to_number128(amount, decimals, rounding);
+    assert_between(x, down, up, input);
+    assert_adjacent(down.to_i128(), up.to_i128(), input);
+    assert!(number128_to_token_amount(down, decimals, Rounding::Down).unwrap() <= amount);
+    if let Ok(back) = number128_to_token_amount(up, decimals, Rounding::Up) {
+        assert!(back >= amount, "{input:?}: {up} converts back to {back}");
+    }
+    if down == up {
+        assert_eq!(
+            Ok(amount),
+            number128_to_token_amount(x, decimals, rounding),
+            "{x}"
+        );
+    }
+}
+
+fn floats(rng: &mut Rng) {
+    let value = float(rng);
+    let rounding = rounding(rng);
+    if !value.is_finite() {
+        assert!(f64_to_number(value, rounding).is_err(), "{value}");
+        assert!(f64_to_number128(value, rounding).is_err(), "{value}");
+        return;
+    }
+
+    // the error of `as_f64` relative to the exact value, plus one raw unit
+    let tolerance = |one: f64| value.abs() * 4.0 * f64::EPSILON + 1.0 / one;
+
+    match (
+        f64_to_number(value, Rounding::Down),
+        f64_to_number(value, Rounding::Up),
+    ) {
+        (Ok(down), Ok(up)) => {
+            assert!(value >= 0.0, "{value}: negative value converted");
+            assert_between(f64_to_number(value, rounding).unwrap(), down, up, value);
+            assert_adjacent_numbers(down, up, value);
+            let one = Number::ONE.as_f64();
+            assert!(
+                (down.as_f64() - value).abs() <= tolerance(one),
+                "{value}: {down}"
+            );
+        }
+        // negative values that round down to zero are allowed
+        (Ok(down), Err(_)) => assert!(
+            down == Number::MAX || (value < 0.0 && down == Number::ZERO),
+            "{value}: {down}"
+        ),
+        (Err(_), up) => {
+            assert!(up.is_err(), "{value}: only rounding down failed");
+            assert!(value < 0.0 || value > Number::MAX.as_f64() / 2.0, "{value}");
+        }
+    }
+
+    match (
+        f64_to_number128(value, Rounding::Down),
+        f64_to_number128(value, Rounding::Up),
+    ) {
+        (Ok(down), Ok(up)) => {
+            assert_between(f64_to_number128(value, rounding).unwrap(), down, up, value);
+            assert_adjacent(down.to_i128(), up.to_i128(), value);
+            assert!(down.to_i128().unsigned_abs() <= up.to_i128().unsigned_abs());
+            let one = Number128::ONE.to_i128() as f64;
+            assert!(
+                (down.as_f64() - value).abs() <= tolerance(one),
+                "{value}: {down}"
+            );
+        }
+        (Ok(down), Err(_)) => assert!(down == Number128::MAX || down == Number128::MIN),
+        (Err(_), up) => {
+            assert!(up.is_err(), "{value}: only rounding down failed");
+            assert!(value.abs() > Number128::MAX.as_f64() / 2.0, "{value}");
+        }
+    }
+}
+
+fn basis_points(rng: &mut Rng) {
+    let bps = rng.next_u64() as u16;
+    assert_eq!(Ok(bps), number_to_bps(bps_to_number(bps), rounding(rng)));
+
+    // values up to 10, so some overflow a u16 of basis points
+    let n = Number::from_decimal(small_i64(rng, 10_000_000_000_000_000).unsigned_abs(), -15);
+    match (
+        number_to_bps(n, Rounding::Down),
+        number_to_bps(n, Rounding::Up),
+    ) {
+        (Ok(down), Ok(up)) => {
+            assert_between(number_to_bps(n, rounding(rng)).unwrap(), down, up, n);
+            assert!(up - down <= 1, "{n}: {down} and {up} bps");
+            assert!(bps_to_number(down) <= n && n <= bps_to_number(up), "{n}");
+        }
+        (Ok(down), Err(_)) => assert_eq!(u16::MAX, down, "{n}"),
+        (Err(_), up) => assert!(up.is_err(), "{n}: only rounding down overflowed"),
+    }
+}
+
+fn q64(rng: &mut Rng) {
+    let price = rng.number_128();
+    if price < Number128::ZERO {
+        assert!(to_q64_64(price, rounding(rng)).is_err(), "{price}");
+    } else {
+        match (
+            to_q64_64(price, Rounding::Down),
+            to_q64_64(price, Rounding::Up),
+        ) {
+            (Ok(down), Ok(up)) => {
+                assert_between(to_q64_64(price, rounding(rng)).unwrap(), down, up, price);
+                assert!(up - down <= 1, "{price}: {down} and {up}");
+                assert!(from_q64_64(down, Rounding::Down).unwrap() <= price);
+                assert!(from_q64_64(up, Rounding::Up).unwrap() >= price);
+            }
+            (Ok(down), Err(_)) => assert_eq!(u128::MAX, down, "{price}"),
+            // Q64.64 has 64 integer bits
+            (Err(_), up) => {
+                assert!(up.is_err(), "{price}: only rounding down overflowed");
+                assert!(price.try_as_u128(0).unwrap() >> 64 > 0, "{price}");
+            }
+        }
+    }
+
+    let value = rng.next_u128() >> (rng.next_u64() % 128);
+    let down = from_q64_64(value, Rounding::Down).unwrap();
+    let up = from_q64_64(value, Rounding::Up).unwrap();
+    assert_between(from_q64_64(value, rounding(rng)).unwrap(), down, up, value);
+    assert_adjacent(down.to_i128(), up.to_i128(), value);
+    assert!(to_q64_64(down, Rounding::Down).unwrap() <= value);
+    assert!(to_q64_64(up, Rounding::Up).unwrap() >= value);
+}
+
+fn integers(rng: &mut Rng) {
+    let n = rng.number();
+    let exponent = small_i64(rng, 20).max(-15) as i32;
+    let input = (n, exponent);
+
+    match n.try_as_u128(exponent) {
+        Ok(value) => {
+            assert_eq!(i64::try_from(value).ok(), n.try_as_i64(exponent).ok());
+            assert_eq!(i128::try_from(value).ok(), n.try_as_i128(exponent).ok());
+            assert!(
+                Number::from_u128(value, exponent).unwrap() <= n,
+                "{input:?}"
+            );
+            if let Some(Ok(next)) = value
+                .checked_add(1)
+                .map(|next| Number::from_u128(next, exponent))
+            {
+                assert!(next > n, "{input:?}: {value} was not truncated");
+            }
+        }
+        Err(_) => {
+            assert!(n.try_as_i64(exponent).is_err(), "{input:?}");
+            assert!(n.try_as_i128(exponent).is_err(), "{input:?}");
+        }
+    }
+
+    let x = rng.number_128();
+    let exponent = small_i64(rng, 20) as i32;
+    let input = (x, exponent);
+
+    match x.try_as_i128(exponent) {
+        Ok(value) => {
+            assert_eq!(i64::try_from(value).ok(), x.try_as_i64(exponent).ok());
+            assert_eq!(u128::try_from(value).ok(), x.try_as_u128(exponent).ok());
+            if let Ok(back) = Number128::try_from_decimal(value, exponent) {
+                assert!(
+                    back.to_i128().unsigned_abs() <= x.to_i128().unsigned_abs(),
+                    "{input:?}: {value} was rounded up"
+                );
+            }
+        }
+        Err(_) => {
+            assert!(x.try_as_i64(exponent).is_err(), "{input:?}");
+            assert!(x.try_as_u128(exponent).is_err(), "{input:?}");
+        }
+    }
+}
+
+fn precision(rng: &mut Rng) {
+    let n = rng.number();
+
+    let down: NumberP<6> = n.to_precision(Rounding::Down).unwrap();
+    let up: NumberP<6> = n.to_precision(Rounding::Up).unwrap();
+    let rounded: NumberP<6> = n.to_precision(rounding(rng)).unwrap();
+    assert_between(rounded, down, up, n);
+    assert!(down.to_precision::<15>(Rounding::Down).unwrap() <= n);
+    if let Ok(back) = up.to_precision::<15>(Rounding::Up) {
+        assert!(back >= n, "{n}: {up} converts back to {back}");
+    }
+
+    if let Ok(wide) = n.to_precision::<20>(rounding(rng)) {
+        assert_eq!(Ok(n), wide.to_precision::<15>(rounding(rng)));
+    }
+}
+
+fn significant_figures(rng: &mut Rng) {
+    let figures = (rng.next_u64() % 45) as u32;
+
+    let n = rng.number();
+    match (
+        n.round_to_sig_figs(figures, Rounding::Down),
+        n.round_to_sig_figs(figures, Rounding::Up),
+    ) {
+        (Ok(down), Ok(up)) => {
+            assert!(down <= n && n <= up, "{n}: {down} and {up}");
+            let rounded = n.round_to_sig_figs(figures, rounding(rng)).unwrap();
+            assert_between(rounded, down, up, n);
+        }
+        (_, up) => assert!(figures == 0 || up.is_err(), "{n} to {figures} figures"),
+    }
+
+    let x = rng.number_128();
+    match (
+        x.round_to_sig_figs(figures, Rounding::Down),
+        x.round_to_sig_figs(figures, Rounding::Up),
+    ) {
+        (Ok(down), Ok(up)) => {
+            assert_between(x, down, up, (x, figures));
+            assert!(
+                down.to_i128().unsigned_abs() <= x.to_i128().unsigned_abs(),
+                "{x}: rounding down grew to {down}"
+            );
+            let rounded = x.round_to_sig_figs(figures, rounding(rng)).unwrap();
+            assert_between(rounded, down, up, x);
+        }
+        (_, up) => assert!(figures == 0 || up.is_err(), "{x} to {figures} figures"),
+    }
+}
+
+/// Conversions that must reproduce the value exactly whenever they succeed
+fn representations(rng: &mut Rng) {
+    let n = rng.number();
+    assert_eq!(Ok(n), n.to_string().parse(), "{n}");
+    assert_eq!(Ok(n), n.to_exact_string().parse(), "{n}");
+    assert_eq!(n, Number::from_le_bytes(n.to_le_bytes()));
+    assert_eq!(n, Number::from_be_bytes(n.to_be_bytes()));
+    assert_eq!(n, Number::from(PodNumber::from(n)));
+    if let Ok(decimal) = DecimalValue::try_from(n) {
+        assert_eq!(Ok(n), Number::try_from(decimal), "{n}");
+    }
+
+    let x = rng.number_128();
+    assert_eq!(Ok(x), x.to_exact_string().parse(), "{x}");
+    assert_eq!(x, Number128::from_le_bytes(x.to_le_bytes()));
+    assert_eq!(x, Number128::from_be_bytes(x.to_be_bytes()));
+    assert_eq!(x, Number128::from(PodNumber128::from(x)));
+    if let Ok(decimal) = DecimalValue::try_from(x) {
+        assert_eq!(Ok(x), Number128::try_from(decimal), "{x}");
+    }
+
+    #[cfg(feature = "decimal")]
+    {
+        use rust_decimal::Decimal;
+
+        if let Ok(decimal) = Decimal::try_from(n) {
+            assert_eq!(Ok(n), Number::try_from_rust_decimal(decimal), "{n}");
+        }
+        if let Ok(decimal) = Decimal::try_from(x) {
+            assert_eq!(Ok(x), Number128::try_from_rust_decimal(decimal), "{x}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    {
+        use jet_proto_math::json::number128;
+
+        let mut json = Vec::new();
+        number128::serialize(&x, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let parsed = number128::deserialize(&mut serde_json::Deserializer::from_slice(&json));
+        assert_eq!(x, parsed.unwrap(), "{x}");
+    }
+}
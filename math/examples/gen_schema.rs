@@ -0,0 +1,22 @@
+//! Writes the JSON Schema and TypeScript definitions of the number types.
+//!
+//! Usage: cargo run --example gen_schema --features schema -- [dir]
+
+use std::path::PathBuf;
+
+use jet_proto_math::schema::{json_schema, typescript};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(
+        std::env::args()
+            .nth(1)
+            .unwrap_or_else(|| "schema".to_string()),
+    );
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("numbers.schema.json"), json_schema())?;
+    std::fs::write(dir.join("numbers.d.ts"), typescript())?;
+
+    println!("wrote schema to {}", dir.display());
+    Ok(())
+}
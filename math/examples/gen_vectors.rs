@@ -0,0 +1,34 @@
+//! Writes deterministic test vectors for the number types as JSON.
+//!
+//! Usage: cargo run --example gen_vectors --features test-vectors -- [dir] [seed] [count]
+
+use std::path::PathBuf;
+
+use jet_proto_math::test_vectors::{bits_vectors, number_128_vectors, number_vectors, to_json};
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let dir = PathBuf::from(args.next().unwrap_or_else(|| "test-vectors".to_string()));
+    let seed = args
+        .next()
+        .map(|s| s.parse().expect("invalid seed"))
+        .unwrap_or(0);
+    let count = args
+        .next()
+        .map(|s| s.parse().expect("invalid count"))
+        .unwrap_or(1_000);
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(
+        dir.join("number.json"),
+        to_json(&number_vectors(seed, count)),
+    )?;
+    std::fs::write(
+        dir.join("number_128.json"),
+        to_json(&number_128_vectors(seed, count)),
+    )?;
+    std::fs::write(dir.join("bits.json"), to_json(&bits_vectors(seed, count)))?;
+
+    println!("wrote test vectors to {}", dir.display());
+    Ok(())
+}
@@ -0,0 +1,11 @@
+/** A non-negative decimal string with up to 15 fractional digits, at most 6277101735386680763835789423207666416102355.444464034512895 */
+export type Number = string;
+
+/** A decimal string with up to 10 fractional digits, from -17014118346046923173168730371.5884105728 to 17014118346046923173168730371.5884105727 */
+export type Number128 = string;
+
+/** The value mantissa * 10^exponent */
+export type DecimalValue = { mantissa: number | bigint; exponent: number };
+
+/** A Number128 with the value mantissa * 10^exponent, as written by json::number128, which also reads a Number128 string */
+export type Number128Json = { mantissa: string; exponent: number };
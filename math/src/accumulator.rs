@@ -0,0 +1,97 @@
+use std::iter::FromIterator;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::bigint::{U192, U256};
+use crate::number::*;
+
+/// Sums a large number of `Number` values in a 256-bit total, so that the
+/// running sum never overflows even when the final result does not fit in
+/// a `Number`.
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct NumberAccumulator(U256);
+
+impl NumberAccumulator {
+    pub const ZERO: Self = Self(U256([0, 0, 0, 0]));
+
+    pub fn new() -> Self {
+        Self::ZERO
+    }
+
+    /// Add a number to the running total
+    pub fn add(&mut self, n: Number) {
+        self.0 = self
+            .0
+            .checked_add(U256::from(n.to_u192()))
+            .expect("accumulator overflow");
+    }
+
+    /// Check if the running total is too large to fit in a `Number`
+    pub fn is_overflowing(&self) -> bool {
+        U192::try_from(self.0).is_err()
+    }
+
+    /// Get the running total, or an error if it does not fit in a `Number`
+    pub fn try_total(&self) -> Result<Number, Error> {
+        U192::try_from(self.0)
+            .map(Number::from_u192)
+            .map_err(|_| Error::Overflow(Number::MAX))
+    }
+
+    /// Get the running total
+    ///
+    /// Panics if the total does not fit in a `Number`.
+    pub fn total(&self) -> Number {
+        self.try_total()
+            .expect("accumulated total overflows a Number")
+    }
+}
+
+impl Extend<Number> for NumberAccumulator {
+    fn extend<I: IntoIterator<Item = Number>>(&mut self, iter: I) {
+        for n in iter {
+            self.add(n);
+        }
+    }
+}
+
+impl FromIterator<Number> for NumberAccumulator {
+    fn from_iter<I: IntoIterator<Item = Number>>(iter: I) -> Self {
+        let mut acc = Self::new();
+        acc.extend(iter);
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_small_values_exactly() {
+        let tiny = Number::from_decimal(1, -15);
+        let acc: NumberAccumulator = std::iter::repeat_n(tiny, 10_000).collect();
+
+        assert_eq!(Number::from_decimal(1, -11), acc.total());
+    }
+
+    #[test]
+    fn recovers_from_intermediate_overflow() {
+        let mut acc = NumberAccumulator::new();
+        acc.add(Number::MAX);
+        acc.add(Number::MAX);
+        assert!(acc.is_overflowing());
+        assert_eq!(Err(Error::Overflow(Number::MAX)), acc.try_total());
+
+        let mut acc = NumberAccumulator::new();
+        acc.extend([Number::MAX, Number::ONE]);
+        assert!(acc.is_overflowing());
+    }
+
+    #[test]
+    fn empty_total_is_zero() {
+        assert_eq!(Number::ZERO, NumberAccumulator::new().total());
+        assert_eq!(NumberAccumulator::ZERO, NumberAccumulator::default());
+    }
+}
@@ -0,0 +1,93 @@
+//! Zero-copy `rkyv` serialization.
+//!
+//! Numbers are archived as `PodNumber` and `PodNumber128`, so an archived
+//! value has the same little-endian layout on every platform, and can be
+//! read in place from a memory-mapped snapshot with `From`.
+
+use rkyv::{Archive, Deserialize, Fallible, Serialize};
+
+use crate::{Number, Number128, PodNumber, PodNumber128};
+
+impl Archive for Number {
+    type Archived = PodNumber;
+    type Resolver = ();
+
+    unsafe fn resolve(&self, _pos: usize, _resolver: (), out: *mut PodNumber) {
+        out.write(PodNumber::from(*self));
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Number {
+    fn serialize(&self, _serializer: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<Number, D> for PodNumber {
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Number, D::Error> {
+        Ok(Number::from(*self))
+    }
+}
+
+impl Archive for Number128 {
+    type Archived = PodNumber128;
+    type Resolver = ();
+
+    unsafe fn resolve(&self, _pos: usize, _resolver: (), out: *mut PodNumber128) {
+        out.write(PodNumber128::from(*self));
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Number128 {
+    fn serialize(&self, _serializer: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<Number128, D> for PodNumber128 {
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Number128, D::Error> {
+        Ok(Number128::from(*self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    struct Snapshot {
+        deposits: Number,
+        rate: Number128,
+        slot: u64,
+    }
+
+    #[test]
+    fn archived_layout() {
+        let number = Number::from_decimal(1242, -3);
+        let number_128 = Number128::from_decimal(-1242, -3);
+
+        let bytes = rkyv::to_bytes::<_, 64>(&number).unwrap();
+        assert_eq!(&number.to_le_bytes(), bytes.as_slice());
+
+        let bytes = rkyv::to_bytes::<_, 64>(&number_128).unwrap();
+        assert_eq!(&number_128.to_le_bytes(), bytes.as_slice());
+    }
+
+    #[test]
+    fn read_in_place() {
+        let snapshot = Snapshot {
+            deposits: Number::from_decimal(1_000_000u64, 0),
+            rate: Number128::from_bps(250),
+            slot: 42,
+        };
+
+        let bytes = rkyv::to_bytes::<_, 256>(&snapshot).unwrap();
+        let archived = unsafe { rkyv::archived_root::<Snapshot>(&bytes) };
+
+        assert_eq!(snapshot.deposits, Number::from(archived.deposits));
+        assert_eq!(snapshot.rate, Number128::from(archived.rate));
+
+        let deserialized: Snapshot = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        assert_eq!(snapshot, deserialized);
+    }
+}
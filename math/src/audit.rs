@@ -0,0 +1,112 @@
+//! Tracing of arithmetic operations, for audits.
+//!
+//! With the `audit` feature enabled, every arithmetic operation on the
+//! number types is recorded while a trace is running on the current thread,
+//! including whether the result was rounded or saturated. Without the
+//! feature none of this is compiled in.
+//!
+//! ```ignore
+//! audit::start();
+//! run_scenario();
+//! for event in audit::finish().iter().filter(|e| e.rounded || e.saturated) {
+//!     println!("{event:?}");
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+
+/// A single recorded operation
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEvent {
+    /// The name of the operation, e.g. `Number128::checked_mul`
+    pub op: &'static str,
+    pub lhs: String,
+    pub rhs: String,
+    /// The result, or `None` if the operation failed
+    pub result: Option<String>,
+    /// Whether the exact result was rounded to fit the precision
+    pub rounded: bool,
+    /// Whether the result was clamped to the representable range
+    pub saturated: bool,
+}
+
+thread_local! {
+    static TRACE: RefCell<Option<Vec<AuditEvent>>> = const { RefCell::new(None) };
+}
+
+/// Start recording operations on the current thread, discarding any
+/// unfinished trace.
+pub fn start() {
+    TRACE.with(|trace| *trace.borrow_mut() = Some(vec![]));
+}
+
+/// Stop recording, returning the operations recorded since `start`
+pub fn finish() -> Vec<AuditEvent> {
+    TRACE.with(|trace| trace.borrow_mut().take().unwrap_or_default())
+}
+
+pub(crate) fn record<L: Debug, R: Debug, T: Debug>(
+    op: &'static str,
+    lhs: &L,
+    rhs: &R,
+    result: Option<&T>,
+    rounded: bool,
+    saturated: bool,
+) {
+    TRACE.with(|trace| {
+        if let Some(events) = trace.borrow_mut().as_mut() {
+            events.push(AuditEvent {
+                op,
+                lhs: format!("{lhs:#?}"),
+                rhs: format!("{rhs:#?}"),
+                result: result.map(|r| format!("{r:#?}")),
+                rounded,
+                saturated,
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Number, Number128};
+
+    #[test]
+    fn records_only_while_tracing() {
+        let _ = Number128::ONE + Number128::ONE;
+
+        start();
+        let third = Number128::ONE / Number128::from_decimal(3, 0);
+        let _ = Number::MAX.saturating_add(Number::ONE);
+        let events = finish();
+
+        let _ = third * 3;
+
+        assert_eq!(2, events.len());
+        assert_eq!("Number128::checked_div", events[0].op);
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Some("Number128(0.3333333333; raw=3333333333)".to_owned()),
+            events[0].result
+        );
+        assert!(events[0].rounded);
+        assert_eq!("Number::saturating_add", events[1].op);
+        assert!(events[1].saturated);
+        assert!(finish().is_empty());
+    }
+
+    #[test]
+    fn exact_operations_are_not_lossy() {
+        start();
+        let _ = Number::from_decimal(15, -1) * Number::from_decimal(2, 0);
+        let _ = Number128::from_decimal(15, -1) - Number128::ONE;
+        let _ = Number128::MAX.checked_add(&Number128::ONE);
+        let events = finish();
+
+        assert_eq!(3, events.len());
+        assert!(events.iter().all(|e| !e.rounded && !e.saturated));
+        assert_eq!(None, events[2].result);
+    }
+}
@@ -0,0 +1,508 @@
+//! Wide integer types used as the backing representation and for
+//! intermediate values in precision-critical calculations.
+//!
+//! These are the same types the crate uses internally, so downstream code
+//! can build custom routines against them without depending on `uint`
+//! directly.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use bytemuck::{Pod, Zeroable};
+use uint::construct_uint;
+
+construct_uint! {
+    /// A 192-bit unsigned integer
+    #[derive(Pod, Zeroable)]
+    pub struct U192(3);
+}
+
+construct_uint! {
+    /// A 256-bit unsigned integer
+    #[derive(Pod, Zeroable)]
+    pub struct U256(4);
+}
+
+construct_uint! {
+    pub(crate) struct U384(6);
+}
+
+impl U192 {
+    /// Multiply without overflow, returning the high and low halves
+    /// of the 384-bit product as `(hi, lo)`.
+    pub fn full_mul(self, rhs: U192) -> (U192, U192) {
+        let product = U384::from(self) * U384::from(rhs);
+        let U384(limbs) = product;
+
+        (
+            U192([limbs[3], limbs[4], limbs[5]]),
+            U192([limbs[0], limbs[1], limbs[2]]),
+        )
+    }
+
+    /// Divide, returning both the quotient and the remainder.
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_rem(self, rhs: U192) -> (U192, U192) {
+        self.div_mod(rhs)
+    }
+
+    /// Compute `self * mul / div` with a 384-bit intermediate product,
+    /// returning `None` if `div` is zero or the result overflows.
+    pub fn checked_mul_div(self, mul: U192, div: U192) -> Option<U192> {
+        if div.is_zero() {
+            return None;
+        }
+
+        (U384::from(self) * U384::from(mul) / U384::from(div)).checked_as_u192()
+    }
+}
+
+impl U256 {
+    /// Multiply without overflow, returning the high and low halves
+    /// of the 512-bit product as `(hi, lo)`.
+    pub fn full_mul(self, rhs: U256) -> (U256, U256) {
+        let mut limbs = [0u64; 8];
+
+        for (i, a) in self.0.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, b) in rhs.0.iter().enumerate() {
+                let sum = *a as u128 * *b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = sum as u64;
+                carry = sum >> 64;
+            }
+            limbs[i + 4] = carry as u64;
+        }
+
+        (
+            U256([limbs[4], limbs[5], limbs[6], limbs[7]]),
+            U256([limbs[0], limbs[1], limbs[2], limbs[3]]),
+        )
+    }
+
+    /// Divide, returning both the quotient and the remainder.
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_rem(self, rhs: U256) -> (U256, U256) {
+        self.div_mod(rhs)
+    }
+}
+
+impl From<U192> for U256 {
+    fn from(n: U192) -> Self {
+        U256([n.0[0], n.0[1], n.0[2], 0])
+    }
+}
+
+impl TryFrom<U256> for U192 {
+    type Error = &'static str;
+
+    fn try_from(n: U256) -> Result<Self, Self::Error> {
+        if n.0[3] != 0 {
+            return Err("integer overflow when casting to U192");
+        }
+
+        Ok(U192([n.0[0], n.0[1], n.0[2]]))
+    }
+}
+
+impl From<U192> for U384 {
+    fn from(n: U192) -> Self {
+        U384([n.0[0], n.0[1], n.0[2], 0, 0, 0])
+    }
+}
+
+impl From<U256> for U384 {
+    fn from(n: U256) -> Self {
+        U384([n.0[0], n.0[1], n.0[2], n.0[3], 0, 0])
+    }
+}
+
+impl U384 {
+    /// Narrow to a `U192`, returning `None` if the value doesn't fit
+    pub(crate) fn checked_as_u192(self) -> Option<U192> {
+        match self.0[3..].iter().all(|l| *l == 0) {
+            true => Some(U192([self.0[0], self.0[1], self.0[2]])),
+            false => None,
+        }
+    }
+
+    /// Narrow to a `U256`, returning `None` if the value doesn't fit
+    pub(crate) fn checked_as_u256(self) -> Option<U256> {
+        match self.0[4..].iter().all(|l| *l == 0) {
+            true => Some(U256([self.0[0], self.0[1], self.0[2], self.0[3]])),
+            false => None,
+        }
+    }
+
+    /// The greatest common divisor, which is zero only if both are zero
+    pub(crate) fn gcd(self, other: U384) -> U384 {
+        let (mut a, mut b) = (self, other);
+        while !b.is_zero() {
+            (a, b) = (b, a % b);
+        }
+
+        a
+    }
+}
+
+const SIGN_BIT: U192 = U192([0, 0, 1 << 63]);
+
+/// A 192-bit signed integer, stored in two's complement
+#[derive(Pod, Zeroable, Default, Clone, Copy, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct I192(U192);
+
+impl I192 {
+    pub const ZERO: Self = Self(U192([0, 0, 0]));
+    pub const ONE: Self = Self(U192([1, 0, 0]));
+    pub const MAX: Self = Self(U192([u64::MAX, u64::MAX, u64::MAX >> 1]));
+    pub const MIN: Self = Self(SIGN_BIT);
+    pub const BITS: u32 = 192;
+
+    /// Create from an unsigned magnitude, if it fits
+    pub fn from_unsigned(value: U192) -> Option<Self> {
+        match value & SIGN_BIT == U192::zero() {
+            true => Some(Self(value)),
+            false => None,
+        }
+    }
+
+    /// Get the raw two's complement representation
+    pub fn to_bits(self) -> U192 {
+        self.0
+    }
+
+    /// Create from a raw two's complement representation
+    pub fn from_bits(bits: U192) -> Self {
+        Self(bits)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 & SIGN_BIT != U192::zero()
+    }
+
+    /// The absolute value as an unsigned integer, which cannot overflow
+    pub fn unsigned_abs(self) -> U192 {
+        match self.is_negative() {
+            true => (!self.0).overflowing_add(U192::one()).0,
+            false => self.0,
+        }
+    }
+
+    /// Create a value with the given sign from an unsigned magnitude,
+    /// returning `None` if it is out of range.
+    fn from_sign_magnitude(negative: bool, magnitude: U192) -> Option<Self> {
+        if !negative {
+            return Self::from_unsigned(magnitude);
+        }
+
+        match magnitude.cmp(&SIGN_BIT) {
+            Ordering::Greater => None,
+            _ => Some(Self((!magnitude).overflowing_add(U192::one()).0)),
+        }
+    }
+
+    pub fn overflowing_neg(self) -> (Self, bool) {
+        let negated = Self((!self.0).overflowing_add(U192::one()).0);
+        (negated, self == Self::MIN)
+    }
+
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let sum = Self(self.0.overflowing_add(rhs.0).0);
+        let overflow =
+            self.is_negative() == rhs.is_negative() && sum.is_negative() != self.is_negative();
+
+        (sum, overflow)
+    }
+
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let difference = Self(self.0.overflowing_sub(rhs.0).0);
+        let overflow = self.is_negative() != rhs.is_negative()
+            && difference.is_negative() != self.is_negative();
+
+        (difference, overflow)
+    }
+
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let negative = self.is_negative() != rhs.is_negative();
+        let (magnitude, overflow) = self.unsigned_abs().overflowing_mul(rhs.unsigned_abs());
+
+        match Self::from_sign_magnitude(negative, magnitude) {
+            Some(product) => (product, overflow),
+            None => (Self(self.0.overflowing_mul(rhs.0).0), true),
+        }
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        match self.overflowing_neg() {
+            (n, false) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (n, false) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (n, false) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (n, false) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Divide, rounding the quotient towards zero. The remainder has the
+    /// same sign as `self`.
+    ///
+    /// Returns `None` when dividing by zero, or for `MIN / -1`.
+    pub fn checked_div_rem(self, rhs: Self) -> Option<(Self, Self)> {
+        if rhs == Self::ZERO {
+            return None;
+        }
+
+        let (quotient, remainder) = self.unsigned_abs().div_rem(rhs.unsigned_abs());
+        let quotient =
+            Self::from_sign_magnitude(self.is_negative() != rhs.is_negative(), quotient)?;
+        let remainder = Self::from_sign_magnitude(self.is_negative(), remainder)?;
+
+        Some((quotient, remainder))
+    }
+
+    /// Divide, returning both the quotient and the remainder.
+    ///
+    /// Panics when dividing by zero, or on overflow.
+    pub fn div_rem(self, rhs: Self) -> (Self, Self) {
+        self.checked_div_rem(rhs)
+            .expect("attempt to divide by zero or with overflow")
+    }
+}
+
+impl From<i128> for I192 {
+    fn from(n: i128) -> Self {
+        let fill = if n < 0 { u64::MAX } else { 0 };
+        Self(U192([n as u64, (n >> 64) as u64, fill]))
+    }
+}
+
+impl From<i64> for I192 {
+    fn from(n: i64) -> Self {
+        Self::from(n as i128)
+    }
+}
+
+impl From<u64> for I192 {
+    fn from(n: u64) -> Self {
+        Self(U192::from(n))
+    }
+}
+
+impl TryFrom<I192> for i128 {
+    type Error = &'static str;
+
+    fn try_from(n: I192) -> Result<Self, Self::Error> {
+        let fill = if n.is_negative() { u64::MAX } else { 0 };
+        let low = n.0 .0[0] as u128 | (n.0 .0[1] as u128) << 64;
+
+        if n.0 .0[2] != fill || (low as i128).is_negative() != n.is_negative() {
+            return Err("integer overflow when casting to i128");
+        }
+
+        Ok(low as i128)
+    }
+}
+
+impl Ord for I192 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0 ^ SIGN_BIT).cmp(&(other.0 ^ SIGN_BIT))
+    }
+}
+
+impl PartialOrd for I192 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for I192 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("arithmetic operation overflow")
+    }
+}
+
+impl Sub for I192 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("arithmetic operation overflow")
+    }
+}
+
+impl Mul for I192 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("arithmetic operation overflow")
+    }
+}
+
+impl Div for I192 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for I192 {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Neg for I192 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("arithmetic operation overflow")
+    }
+}
+
+impl Display for I192 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.unsigned_abs())
+    }
+}
+
+impl std::fmt::Debug for I192 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u192_full_mul() {
+        let (hi, lo) = U192::MAX.full_mul(U192::MAX);
+        assert_eq!(U192::MAX - 1, hi);
+        assert_eq!(U192::one(), lo);
+
+        let (hi, lo) = U192::from(3u64).full_mul(U192::from(5u64));
+        assert_eq!(U192::zero(), hi);
+        assert_eq!(U192::from(15u64), lo);
+    }
+
+    #[test]
+    fn u192_checked_mul_div() {
+        assert_eq!(
+            Some(U192::MAX),
+            U192::MAX.checked_mul_div(U192::MAX, U192::MAX)
+        );
+        assert_eq!(
+            Some(U192::from(5u64)),
+            U192::from(10u64).checked_mul_div(U192::one(), U192::from(2u64))
+        );
+        assert_eq!(
+            None,
+            U192::MAX.checked_mul_div(U192::from(2u64), U192::one())
+        );
+        assert_eq!(None, U192::one().checked_mul_div(U192::one(), U192::zero()));
+    }
+
+    #[test]
+    fn u256_full_mul() {
+        let (hi, lo) = U256::MAX.full_mul(U256::MAX);
+        assert_eq!(U256::MAX - 1, hi);
+        assert_eq!(U256::one(), lo);
+
+        let (hi, lo) = U256::from(u128::MAX).full_mul(U256::from(u128::MAX));
+        assert_eq!(U256::zero(), hi);
+        assert_eq!(U256::from(u128::MAX) * U256::from(u128::MAX), lo);
+    }
+
+    #[test]
+    fn div_rem() {
+        assert_eq!(
+            (U192::from(3u64), U192::from(1u64)),
+            U192::from(10u64).div_rem(U192::from(3u64))
+        );
+        assert_eq!(
+            (I192::from(-3i64), I192::from(-1i64)),
+            I192::from(-10i64).div_rem(I192::from(3i64))
+        );
+        assert_eq!(None, I192::ONE.checked_div_rem(I192::ZERO));
+        assert_eq!(None, I192::MIN.checked_div_rem(I192::from(-1i64)));
+    }
+
+    #[test]
+    fn i192_round_trip_i128() {
+        for n in [i128::MIN, -1, 0, 1, i128::MAX] {
+            assert_eq!(Ok(n), i128::try_from(I192::from(n)));
+        }
+
+        assert!(i128::try_from(I192::MAX).is_err());
+        assert!(i128::try_from(I192::MIN).is_err());
+        assert!(i128::try_from(I192::from(i128::MAX) + I192::ONE).is_err());
+    }
+
+    #[test]
+    fn i192_arithmetic() {
+        let a = I192::from(-7i64);
+        let b = I192::from(2i64);
+
+        assert_eq!(I192::from(-5i64), a + b);
+        assert_eq!(I192::from(-9i64), a - b);
+        assert_eq!(I192::from(-14i64), a * b);
+        assert_eq!(I192::from(49i64), a * a);
+        assert_eq!(I192::from(7i64), -a);
+    }
+
+    #[test]
+    fn i192_overflow() {
+        assert_eq!((I192::MIN, true), I192::MAX.overflowing_add(I192::ONE));
+        assert_eq!((I192::MAX, true), I192::MIN.overflowing_sub(I192::ONE));
+        assert_eq!((I192::MIN, true), I192::MIN.overflowing_neg());
+        assert!(I192::MAX.checked_mul(I192::from(2i64)).is_none());
+        assert_eq!(
+            Some(I192::MIN),
+            I192::from_bits(SIGN_BIT >> 1).checked_mul(I192::from(-2i64))
+        );
+    }
+
+    #[test]
+    fn i192_ordering() {
+        assert!(I192::MIN < I192::from(-1i64));
+        assert!(I192::from(-1i64) < I192::ZERO);
+        assert!(I192::ZERO < I192::MAX);
+    }
+
+    #[test]
+    fn i192_display() {
+        assert_eq!("-42", I192::from(-42i64).to_string());
+        assert_eq!("42", I192::from(42u64).to_string());
+    }
+}
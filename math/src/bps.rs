@@ -0,0 +1,196 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Mul;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{div_rounded, Error, Number, Number128, Rounding, BPS_EXPONENT};
+
+const BPS_PER_UNIT: u16 = 10_000;
+
+/// A ratio in basis points, e.g. a fee rate or a collateral weight.
+///
+/// Values above 100% are allowed, for ratios such as leverage multipliers,
+/// so use `checked_fraction` or `validate_fraction` where a value must be
+/// at most 100%. Multiplying an amount by a `Bps` truncates the result.
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct Bps(pub u16);
+
+impl Bps {
+    pub const ZERO: Self = Self(0);
+    pub const HUNDRED_PERCENT: Self = Self(BPS_PER_UNIT);
+
+    pub const fn new(basis_points: u16) -> Self {
+        Self(basis_points)
+    }
+
+    /// Create a value that must be at most 100%
+    pub fn checked_fraction(basis_points: u16) -> Result<Self, Error> {
+        Self(basis_points).validate_fraction()
+    }
+
+    /// Convert a whole percentage, returning an error if it is too large
+    pub fn from_percent(percent: u16) -> Result<Self, Error> {
+        percent.checked_mul(100).map(Self).ok_or(Error::OutOfRange)
+    }
+
+    pub fn is_fraction(&self) -> bool {
+        self.0 <= BPS_PER_UNIT
+    }
+
+    /// Return the value if it is at most 100%, or an error otherwise
+    pub fn validate_fraction(self) -> Result<Self, Error> {
+        match self.is_fraction() {
+            true => Ok(self),
+            false => Err(Error::InvalidInput("basis points exceed 10000")),
+        }
+    }
+
+    /// The remaining fraction, `100% - self`
+    pub fn complement(&self) -> Result<Self, Error> {
+        self.validate_fraction()
+            .map(|bps| Self(BPS_PER_UNIT - bps.0))
+    }
+
+    pub fn to_number(&self) -> Number {
+        Number::from_bps(self.0)
+    }
+
+    pub fn to_number128(&self) -> Number128 {
+        Number128::from_bps(self.0)
+    }
+}
+
+impl From<Bps> for Number {
+    fn from(bps: Bps) -> Self {
+        bps.to_number()
+    }
+}
+
+impl From<Bps> for Number128 {
+    fn from(bps: Bps) -> Self {
+        bps.to_number128()
+    }
+}
+
+impl Mul<Bps> for Number {
+    type Output = Number;
+
+    fn mul(self, rhs: Bps) -> Self::Output {
+        self * rhs.to_number()
+    }
+}
+
+impl Mul<Bps> for Number128 {
+    type Output = Number128;
+
+    fn mul(self, rhs: Bps) -> Self::Output {
+        self * rhs.to_number128()
+    }
+}
+
+/// Convert basis points to a `Number`, e.g. 250 to 0.025
+pub fn bps_to_number(bps: u16) -> Number {
+    Number::from_bps(bps)
+}
+
+/// Convert a `Number` to basis points, rounding as specified, or return an
+/// error if the result doesn't fit in a `u16`
+pub fn number_to_bps(number: Number, rounding: Rounding) -> Result<u16, Error> {
+    number
+        .checked_as_u64_rounded(BPS_EXPONENT, rounding)
+        .and_then(|bps| u16::try_from(bps).ok())
+        .ok_or(Error::OutOfRange)
+}
+
+/// Multiply an amount by some basis points, rounding as specified, or
+/// return `None` if the result doesn't fit in a `u64`
+///
+/// The product is computed exactly in integers, without converting either
+/// value to a fixed-point number.
+pub fn bps_mul(amount: u64, bps: u16, rounding: Rounding) -> Option<u64> {
+    let product = amount as u128 * bps as u128;
+
+    u64::try_from(div_rounded(product, BPS_PER_UNIT as u128, rounding)).ok()
+}
+
+/// Displays as a percentage, e.g. `12.5%`
+impl Display for Bps {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (whole, frac) = (self.0 / 100, self.0 % 100);
+
+        match frac {
+            0 => write!(f, "{}%", whole),
+            _ if frac % 10 == 0 => write!(f, "{}.{}%", whole, frac / 10),
+            _ => write!(f, "{}.{:02}%", whole, frac),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiply_amounts() {
+        let amount = Number::from(1_000u64);
+
+        assert_eq!(Number::from(25u64), amount * Bps(250));
+        assert_eq!(amount * Number::from_bps(250), amount * Bps(250));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Number128::from_decimal(-25, 0),
+            Number128::from_decimal(-1_000, 0) * Bps(250)
+        );
+        assert_eq!(
+            Number::from_decimal(2, -4),
+            Number::from_decimal(1, -2) * Bps(200)
+        );
+    }
+
+    #[test]
+    fn fractions() {
+        assert_eq!(Ok(Bps(9_750)), Bps(250).complement());
+        assert_eq!(Ok(Bps::ZERO), Bps::HUNDRED_PERCENT.complement());
+        assert!(Bps(10_001).complement().is_err());
+        assert!(Bps::checked_fraction(10_001).is_err());
+        assert!(Bps(20_000).validate_fraction().is_err());
+        assert_eq!(Ok(Bps(5_000)), Bps::from_percent(50));
+        assert!(Bps::from_percent(1_000).is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Number::from_decimal(25, -3), bps_to_number(250));
+        assert_eq!(Ok(250), number_to_bps(bps_to_number(250), Rounding::Down));
+
+        let third = Number::ONE / Number::from(3u64);
+        assert_eq!(Ok(3_333), number_to_bps(third, Rounding::Down));
+        assert_eq!(Ok(3_334), number_to_bps(third, Rounding::Up));
+        assert_eq!(Ok(3_333), number_to_bps(third, Rounding::HalfUp));
+        assert_eq!(
+            Err(Error::OutOfRange),
+            number_to_bps(Number::from(7u64), Rounding::Down)
+        );
+    }
+
+    #[test]
+    fn multiply_integers() {
+        assert_eq!(Some(25), bps_mul(1_000, 250, Rounding::Down));
+        assert_eq!(Some(0), bps_mul(1, 30, Rounding::Down));
+        assert_eq!(Some(1), bps_mul(1, 30, Rounding::Up));
+        assert_eq!(Some(1), bps_mul(1, 5_000, Rounding::HalfUp));
+        assert_eq!(Some(0), bps_mul(1, 5_000, Rounding::HalfEven));
+        assert_eq!(Some(2), bps_mul(3, 5_000, Rounding::HalfEven));
+        assert_eq!(Some(u64::MAX), bps_mul(u64::MAX, 10_000, Rounding::Up));
+        assert_eq!(None, bps_mul(u64::MAX, 10_001, Rounding::Down));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("12.5%", Bps(1_250).to_string());
+        assert_eq!("0.01%", Bps(1).to_string());
+        assert_eq!("100%", Bps::HUNDRED_PERCENT.to_string());
+        assert_eq!("655.35%", Bps(u16::MAX).to_string());
+    }
+}
@@ -0,0 +1,437 @@
+//! Conversions between the number types and the plain types they are
+//! usually exchanged as.
+//!
+//! Each conversion has one canonical function, which rounds as specified
+//! when the target can't represent a value exactly:
+//!
+//! - `Number` and `Number128`: [`number_to_number128`] and
+//!   [`number128_to_number`]
+//! - raw token amounts with some decimals: [`token_amount_to_number`] and
+//!   `Number::to_token_amount`, [`token_amount_to_number128`] and
+//!   [`number128_to_token_amount`]
+//! - `f64`: [`f64_to_number`] and `Number::as_f64`, [`f64_to_number128`]
+//!   and `Number128::as_f64`
+//! - strings: `FromStr` and `Display`, which round trip exactly
+
+use crate::bigint::U384;
+use crate::{div_rounded, Error, Number, Number128, Rounding, U192};
+
+/// Convert a `Number` to a `Number128`, rounding as specified if it has
+/// more precision than a `Number128` can store
+pub fn number_to_number128(n: Number, rounding: Rounding) -> Result<Number128, Error> {
+    let magnitude = scale(
+        U384::from(n.to_u192()),
+        one_128(),
+        U384::from(Number::ONE.to_u192()),
+        rounding,
+    );
+
+    to_number128(false, magnitude)
+}
+
+/// Convert a `Number128` to a `Number`, rounding as specified if it has
+/// more precision than a `Number` can store. Negative values are an error.
+pub fn number128_to_number(n: Number128, rounding: Rounding) -> Result<Number, Error> {
+    if n < Number128::ZERO {
+        return Err(Error::InvalidInput("negative value"));
+    }
+
+    let magnitude = scale(
+        U384::from(n.to_i128() as u128),
+        U384::from(Number::ONE.to_u192()),
+        one_128(),
+        rounding,
+    );
+
+    to_number(magnitude)
+}
+
+/// Convert a raw token amount with the given decimals into a `Number` of
+/// whole tokens, rounding as specified if there are more than 15 decimals
+pub fn token_amount_to_number(amount: u64, decimals: u8, rounding: Rounding) -> Number {
+    let magnitude = scale(
+        U384::from(amount),
+        U384::from(Number::ONE.to_u192()),
+        pow10_or_max(decimals),
+        rounding,
+    );
+
+    // the amount is scaled up by at most 10^15
+    to_number(magnitude).unwrap()
+}
+
+/// Convert a raw token amount with the given decimals into a `Number128`
+/// of whole tokens, rounding as specified if there are more decimals than
+/// a `Number128` can store
+pub fn token_amount_to_number128(amount: u64, decimals: u8, rounding: Rounding) -> Number128 {
+    let magnitude = scale(
+        U384::from(amount),
+        one_128(),
+        pow10_or_max(decimals),
+        rounding,
+    );
+
+    // a u64 scaled up by `ONE` always fits in an i128
+    to_number128(false, magnitude).unwrap()
+}
+
+/// Convert a `Number128` of whole tokens into a raw token amount with the
+/// given decimals, rounding as specified. Negative values are an error.
+pub fn number128_to_token_amount(
+    n: Number128,
+    decimals: u8,
+    rounding: Rounding,
+) -> Result<u64, Error> {
+    if n < Number128::ZERO {
+        return Err(Error::InvalidInput("negative value"));
+    }
+
+    if n == Number128::ZERO {
+        return Ok(0);
+    }
+
+    // any other value is out of range long before the product overflows
+    let magnitude = pow10(decimals)
+        .and_then(|pow| U384::from(n.to_i128() as u128).checked_mul(pow))
+        .map(|product| div_rounded(product, one_128(), rounding))
+        .ok_or(Error::OutOfRange)?;
+
+    match magnitude.bits() <= 64 {
+        true => Ok(magnitude.low_u64()),
+        false => Err(Error::OutOfRange),
+    }
+}
+
+/// Convert an `f64` to a `Number`, rounding its exact value as specified.
+/// Negative and non-finite values are an error.
+pub fn f64_to_number(value: f64, rounding: Rounding) -> Result<Number, Error> {
+    let (negative, magnitude) = decompose_f64(value, Number::ONE.to_u192(), rounding)?;
+
+    match negative && !magnitude.is_zero() {
+        true => Err(Error::InvalidInput("negative value")),
+        false => to_number(magnitude),
+    }
+}
+
+/// Convert an `f64` to a `Number128`, rounding the magnitude of its exact
+/// value as specified. Non-finite values are an error.
+pub fn f64_to_number128(value: f64, rounding: Rounding) -> Result<Number128, Error> {
+    let one = U192::from(Number128::ONE.to_i128() as u128);
+    let (negative, magnitude) = decompose_f64(value, one, rounding)?;
+
+    to_number128(negative, magnitude)
+}
+
+/// The sign of `value`, and its magnitude multiplied by `one` and rounded
+fn decompose_f64(value: f64, one: U192, rounding: Rounding) -> Result<(bool, U384), Error> {
+    if !value.is_finite() {
+        return Err(Error::InvalidInput("not a finite number"));
+    }
+
+    // value = significand * 2^exponent, exactly
+    let bits = value.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (significand, exponent) = match biased_exponent {
+        0 => (fraction, -1074),
+        _ => (fraction | (1 << 52), biased_exponent - 1075),
+    };
+
+    let product = U384::from(significand) * U384::from(one);
+    let magnitude = match exponent {
+        // anything shifted further is far out of range of every type
+        193.. => U384::MAX,
+        0.. => product << exponent as usize,
+        // the product has fewer than 128 bits, so it's less than half of
+        // one unit when divided by a larger power of two
+        ..=-128 => match rounding == Rounding::Up && !product.is_zero() {
+            true => U384::one(),
+            false => U384::zero(),
+        },
+        _ => scale(
+            product,
+            U384::one(),
+            U384::one() << (-exponent) as usize,
+            rounding,
+        ),
+    };
+
+    Ok((value.is_sign_negative(), magnitude))
+}
+
+/// Compute `value * mul / div`, rounding as specified
+fn scale(value: U384, mul: U384, div: U384, rounding: Rounding) -> U384 {
+    div_rounded(value * mul, div, rounding)
+}
+
+/// `10^decimals`, or `None` for more than 115 decimals
+fn pow10(decimals: u8) -> Option<U384> {
+    U384::from(10).checked_pow(U384::from(decimals))
+}
+
+/// `10^decimals`, saturating to `U384::MAX`. Dividing an amount scaled by
+/// at most `Number::ONE` rounds the same way by either, since both are more
+/// than twice the amount.
+fn pow10_or_max(decimals: u8) -> U384 {
+    pow10(decimals).unwrap_or(U384::MAX)
+}
+
+fn one_128() -> U384 {
+    U384::from(Number128::ONE.to_i128() as u128)
+}
+
+fn to_number(magnitude: U384) -> Result<Number, Error> {
+    magnitude
+        .checked_as_u192()
+        .map(Number::from_u192)
+        .ok_or(Error::OutOfRange)
+}
+
+fn to_number128(negative: bool, magnitude: U384) -> Result<Number128, Error> {
+    if magnitude.bits() > 128 {
+        return Err(Error::OutOfRange);
+    }
+
+    let magnitude = magnitude.low_u128();
+    let raw = match negative {
+        true => 0i128.checked_sub_unsigned(magnitude),
+        false => i128::try_from(magnitude).ok(),
+    };
+
+    raw.map(Number128::from_i128).ok_or(Error::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUNDINGS: [Rounding; 4] = [
+        Rounding::Down,
+        Rounding::Up,
+        Rounding::HalfUp,
+        Rounding::HalfEven,
+    ];
+
+    /// Deterministic pseudo-random values, biased towards small ones so
+    /// that both the fraction and the integer part are covered
+    fn samples(count: usize) -> impl Iterator<Item = u128> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+
+        (0..count).map(move |_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            let value = (state as u128) << 64 | state.rotate_left(29) as u128;
+            value >> (state % 128)
+        })
+    }
+
+    /// Generate a test that converting each value there and back is exact
+    /// when the value can be represented after the first conversion, and
+    /// otherwise brackets the value between rounding down and up
+    macro_rules! round_trip {
+        ($name:ident: $values:expr, $to:expr, $from:expr) => {
+            #[test]
+            fn $name() {
+                for value in $values {
+                    let (Ok(lo), Ok(hi)) = ($to(value, Rounding::Down), $to(value, Rounding::Up))
+                    else {
+                        continue;
+                    };
+                    let (Ok(down), Ok(up)) = ($from(lo, Rounding::Down), $from(hi, Rounding::Up))
+                    else {
+                        continue;
+                    };
+
+                    assert!(down <= value && value <= up, "{value:?}");
+                    if lo == hi {
+                        assert_eq!((value, value), (down, up));
+                    }
+                }
+            }
+        };
+    }
+
+    fn numbers() -> impl Iterator<Item = Number> {
+        samples(1_000).map(|n| Number::from_u192(U192::from(n)))
+    }
+
+    fn numbers_128() -> impl Iterator<Item = Number128> {
+        samples(1_000).map(|n| match n & 1 {
+            0 => Number128::from_i128((n >> 1) as i128),
+            _ => Number128::from_i128(-((n >> 1) as i128)),
+        })
+    }
+
+    round_trip!(number_via_number128: numbers(), number_to_number128, number128_to_number);
+    round_trip!(number128_via_number: numbers_128(), number128_to_number, number_to_number128);
+    round_trip!(
+        number128_via_token_amount: numbers_128(),
+        |n, rounding| number128_to_token_amount(n, 6, rounding),
+        |amount, rounding| Ok::<_, Error>(token_amount_to_number128(amount, 6, rounding))
+    );
+
+    #[test]
+    fn exact_round_trips() {
+        // a binary fraction generally has more digits than a `Number`
+        #[cfg(not(feature = "binary-number128"))]
+        for n in numbers_128().filter(|n| *n >= Number128::ZERO) {
+            for rounding in ROUNDINGS {
+                assert_eq!(
+                    Ok(n),
+                    number128_to_number(n, Rounding::Down)
+                        .and_then(|n| number_to_number128(n, rounding))
+                );
+            }
+        }
+
+        #[cfg(not(feature = "binary-number128"))]
+        for n in numbers_128() {
+            assert_eq!(Ok(n), n.to_string().parse());
+        }
+        for n in numbers() {
+            assert_eq!(Ok(n), n.to_string().parse());
+        }
+
+        for amount in samples(1_000).map(|n| n as u64) {
+            for rounding in ROUNDINGS {
+                let n = token_amount_to_number(amount, 9, rounding);
+                assert_eq!(Ok(amount), n.to_token_amount(9, Rounding::Down));
+
+                #[cfg(not(feature = "binary-number128"))]
+                assert_eq!(
+                    Ok(amount),
+                    number128_to_token_amount(
+                        token_amount_to_number128(amount, 9, rounding),
+                        9,
+                        Rounding::Down
+                    )
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rounding() {
+        #[cfg(not(feature = "binary-number128"))]
+        let n = Number::from_decimal(15, -11);
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(Number128::from_decimal(1, -10)),
+            number_to_number128(n, Rounding::Down)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(Number128::from_decimal(2, -10)),
+            number_to_number128(n, Rounding::HalfEven)
+        );
+        assert_eq!(
+            Number::from_decimal(1, -15),
+            token_amount_to_number(5, 16, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Number::ZERO,
+            token_amount_to_number(5, 16, Rounding::HalfEven)
+        );
+        assert_eq!(
+            Ok(1),
+            number128_to_token_amount(Number128::from_decimal(1, -10), 0, Rounding::Up)
+        );
+    }
+
+    #[test]
+    fn many_decimals() {
+        let smallest = Number::from_decimal(1, -15);
+        assert_eq!(
+            smallest,
+            token_amount_to_number(u64::MAX, 200, Rounding::Up)
+        );
+        assert_eq!(Number::ZERO, token_amount_to_number(1, 200, Rounding::Down));
+        assert_eq!(
+            Number::ZERO,
+            token_amount_to_number(1, 200, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Number::ZERO,
+            token_amount_to_number(0, u8::MAX, Rounding::Up)
+        );
+
+        assert_eq!(
+            Number128::from_i128(1),
+            token_amount_to_number128(1, 200, Rounding::Up)
+        );
+        assert_eq!(
+            Number128::ZERO,
+            token_amount_to_number128(u64::MAX, u8::MAX, Rounding::HalfEven)
+        );
+
+        assert_eq!(
+            Ok(0),
+            number128_to_token_amount(Number128::ZERO, 200, Rounding::Up)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            number128_to_token_amount(Number128::from_i128(1), 200, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            number128_to_token_amount(Number128::MAX, 115, Rounding::Down)
+        );
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(
+            Ok(Number::from_decimal(153_125u64, -5)),
+            f64_to_number(1.53125, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(-153_125, -5)),
+            f64_to_number128(-1.53125, Rounding::Down)
+        );
+
+        // 0.1 is slightly above 1/10 as an f64
+        assert_eq!(
+            Ok(Number::from_decimal(1, -1)),
+            f64_to_number(0.1, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(100_000_000_000_001u64, -15)),
+            f64_to_number(0.1, Rounding::Up)
+        );
+
+        assert_eq!(
+            Ok(Number::ZERO),
+            f64_to_number(f64::MIN_POSITIVE, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(1, -15)),
+            f64_to_number(f64::MIN_POSITIVE, Rounding::Up)
+        );
+        assert_eq!(Ok(Number::ZERO), f64_to_number(-0.0, Rounding::Down));
+
+        assert_eq!(Err(Error::OutOfRange), f64_to_number(1e60, Rounding::Down));
+        assert_eq!(
+            Err(Error::OutOfRange),
+            f64_to_number128(1e30, Rounding::Down)
+        );
+        assert!(f64_to_number(-1.0, Rounding::Down).is_err());
+        assert!(f64_to_number(f64::NAN, Rounding::Down).is_err());
+        assert!(f64_to_number128(f64::INFINITY, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Err(Error::OutOfRange),
+            number_to_number128(Number::MAX, Rounding::Down)
+        );
+        assert!(number128_to_number(-Number128::ONE, Rounding::Down).is_err());
+        assert!(number128_to_token_amount(-Number128::ONE, 0, Rounding::Down).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            number128_to_token_amount(Number128::MAX, 9, Rounding::Down)
+        );
+    }
+}
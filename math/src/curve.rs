@@ -0,0 +1,199 @@
+//! Piecewise-linear interest rate curves, and fitting them to observed
+//! rates.
+//!
+//! Fitting solves a least squares problem whose intermediate values can be
+//! negative, so curves are defined over `Number128`.
+
+use crate::{inverse_lerp, lerp, Error, Number128};
+
+/// A piecewise-linear function from utilization to interest rate, defined
+/// by `(utilization, rate)` points sorted by utilization.
+///
+/// Utilizations outside of the first and last points saturate to the rate
+/// at the nearest end.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestRateCurve {
+    points: Vec<(Number128, Number128)>,
+}
+
+impl InterestRateCurve {
+    /// Create a curve from at least two points, strictly increasing in
+    /// utilization
+    pub fn new(points: Vec<(Number128, Number128)>) -> Result<Self, Error> {
+        validate_knots(points.iter().map(|(x, _)| *x))?;
+
+        Ok(Self { points })
+    }
+
+    pub fn points(&self) -> &[(Number128, Number128)] {
+        &self.points
+    }
+
+    /// The interest rate at a utilization
+    pub fn rate(&self, utilization: Number128) -> Number128 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+
+        if utilization <= first.0 {
+            return first.1;
+        }
+        if utilization >= last.0 {
+            return last.1;
+        }
+
+        let index = self.points.partition_point(|(x, _)| *x <= utilization);
+        let ((x0, y0), (x1, y1)) = (self.points[index - 1], self.points[index]);
+
+        lerp(y0, y1, inverse_lerp(x0, x1, utilization))
+    }
+}
+
+/// Fit a curve with points at the given utilizations to observed
+/// `(utilization, rate)` pairs, by least squares.
+///
+/// The fitted curve is continuous, and minimizes the sum of the squared
+/// differences between the observed rates and the curve. Observations
+/// outside of the knots are treated as being at the nearest end. Returns an
+/// error if some segment of the curve has no observations to fit it to.
+pub fn curve_fit(
+    observations: &[(Number128, Number128)],
+    knots: &[Number128],
+) -> Result<InterestRateCurve, Error> {
+    validate_knots(knots.iter().copied())?;
+
+    // the normal equations for the weight of each knot are tridiagonal
+    let n = knots.len();
+    let mut diagonal = vec![Number128::ZERO; n];
+    let mut off_diagonal = vec![Number128::ZERO; n - 1];
+    let mut rhs = vec![Number128::ZERO; n];
+
+    for (x, y) in observations {
+        let x = (*x).clamp(knots[0], knots[n - 1]);
+        let index = knots.partition_point(|k| *k <= x).clamp(1, n - 1);
+        let t = inverse_lerp(knots[index - 1], knots[index], x);
+        let (left, right) = (index - 1, index);
+        let (w_left, w_right) = (Number128::ONE - t, t);
+
+        diagonal[left] += w_left * w_left;
+        diagonal[right] += w_right * w_right;
+        off_diagonal[left] += w_left * w_right;
+        rhs[left] += w_left * *y;
+        rhs[right] += w_right * *y;
+    }
+
+    // forward elimination, then back substitution
+    for i in 1..n {
+        let pivot = diagonal[i - 1];
+        if pivot <= Number128::ZERO {
+            return Err(Error::InvalidInput("not enough observations to fit"));
+        }
+
+        let factor = off_diagonal[i - 1] / pivot;
+        diagonal[i] -= factor * off_diagonal[i - 1];
+        let eliminated = factor * rhs[i - 1];
+        rhs[i] -= eliminated;
+    }
+    if diagonal[n - 1] <= Number128::ZERO {
+        return Err(Error::InvalidInput("not enough observations to fit"));
+    }
+
+    let mut rates = vec![Number128::ZERO; n];
+    rates[n - 1] = rhs[n - 1] / diagonal[n - 1];
+    for i in (0..n - 1).rev() {
+        rates[i] = (rhs[i] - off_diagonal[i] * rates[i + 1]) / diagonal[i];
+    }
+
+    InterestRateCurve::new(knots.iter().copied().zip(rates).collect())
+}
+
+fn validate_knots(knots: impl Iterator<Item = Number128> + Clone) -> Result<(), Error> {
+    if knots.clone().count() < 2 {
+        return Err(Error::InvalidInput("curve needs at least two points"));
+    }
+    if knots.clone().zip(knots.skip(1)).any(|(a, b)| a >= b) {
+        return Err(Error::InvalidInput("curve points are not sorted"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bps(bps: u16) -> Number128 {
+        Number128::from_bps(bps)
+    }
+
+    fn assert_close(expected: Number128, actual: Number128) {
+        let difference = expected - actual;
+        assert!(
+            difference.branchless_max(-difference) <= Number128::from_decimal(1, -8),
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn curve() -> InterestRateCurve {
+        InterestRateCurve::new(vec![
+            (bps(0), bps(200)),
+            (bps(8000), bps(1000)),
+            (bps(10000), bps(10000)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn evaluates_curve() {
+        let curve = curve();
+
+        assert_eq!(bps(200), curve.rate(bps(0)));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(bps(600), curve.rate(bps(4000)));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(bps(5500), curve.rate(bps(9000)));
+        assert_eq!(bps(10000), curve.rate(bps(12000)));
+        assert_eq!(bps(200), curve.rate(-bps(1)));
+    }
+
+    #[test]
+    fn recovers_exact_curve() {
+        let expected = curve();
+        let knots: Vec<_> = expected.points().iter().map(|(x, _)| *x).collect();
+        let observations: Vec<_> = [0, 2000, 4000, 6000, 8000, 9000, 10000]
+            .into_iter()
+            .map(|u| (bps(u), expected.rate(bps(u))))
+            .collect();
+
+        let curve = curve_fit(&observations, &knots).unwrap();
+
+        for ((_, rate), (_, expected)) in curve.points().iter().zip(expected.points()) {
+            assert_close(*expected, *rate);
+        }
+    }
+
+    #[test]
+    fn averages_noise() {
+        let knots = [bps(0), bps(10000)];
+        let observations = [
+            (bps(0), bps(100)),
+            (bps(0), bps(300)),
+            (bps(10000), bps(900)),
+            (bps(10000), bps(1100)),
+        ];
+
+        let curve = curve_fit(&observations, &knots).unwrap();
+
+        assert_close(bps(200), curve.rate(bps(0)));
+        assert_close(bps(1000), curve.rate(bps(10000)));
+        assert_close(bps(600), curve.rate(bps(5000)));
+    }
+
+    #[test]
+    fn invalid_fits() {
+        let knots = [bps(0), bps(5000), bps(10000)];
+
+        assert!(curve_fit(&[(bps(1000), bps(1))], &knots).is_err());
+        assert!(curve_fit(&[], &knots).is_err());
+        assert!(curve_fit(&[(bps(1), bps(1))], &[bps(0)]).is_err());
+        assert!(curve_fit(&[(bps(1), bps(1))], &[bps(1), bps(0)]).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Exact conversions to and from `rust_decimal::Decimal`.
+//!
+//! Every conversion either preserves the value exactly or fails, so that
+//! amounts can be reconciled between the on-chain types and off-chain
+//! services without drift. A `Decimal` holds at most 28 decimal places and
+//! a 96-bit mantissa, so large or very precise values may not convert.
+//!
+//! The blanket `From` impls on the number types rule out `TryFrom<Decimal>`,
+//! so conversions from a `Decimal` are inherent methods instead.
+
+use rust_decimal::Decimal;
+
+use crate::{Error, Number, Number128};
+
+const MAX_DECIMAL_SCALE: usize = 28;
+
+fn to_decimal(exact: &str) -> Result<Decimal, Error> {
+    let (_, _, frac) = crate::display::split_decimal(exact)?;
+
+    if frac.len() > MAX_DECIMAL_SCALE {
+        return Err(Error::InvalidInput("too many decimal places"));
+    }
+
+    Decimal::from_str_exact(exact).map_err(|_| Error::OutOfRange)
+}
+
+impl TryFrom<Number> for Decimal {
+    type Error = Error;
+
+    fn try_from(n: Number) -> Result<Self, Self::Error> {
+        to_decimal(&n.to_string())
+    }
+}
+
+impl Number {
+    /// Convert a `Decimal`, returning an error if it is negative or can't
+    /// be represented exactly
+    pub fn try_from_rust_decimal(d: Decimal) -> Result<Self, Error> {
+        d.to_string().parse()
+    }
+}
+
+impl TryFrom<Number128> for Decimal {
+    type Error = Error;
+
+    fn try_from(n: Number128) -> Result<Self, Self::Error> {
+        to_decimal(&n.to_exact_string())
+    }
+}
+
+impl Number128 {
+    /// Convert a `Decimal`, returning an error if it can't be represented
+    /// exactly
+    pub fn try_from_rust_decimal(d: Decimal) -> Result<Self, Error> {
+        d.to_string().parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let decimal = Decimal::new(-12_345, 3);
+        let number_128 = Number128::from_decimal(-12_345, -3);
+
+        assert_eq!(Ok(number_128), Number128::try_from_rust_decimal(decimal));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(decimal), Decimal::try_from(number_128));
+        assert_eq!(
+            Ok(Number::from_decimal(12_345u64, -3)),
+            Number::try_from_rust_decimal(decimal.abs())
+        );
+        assert_eq!(
+            Ok(decimal.abs()),
+            Decimal::try_from(Number::from_decimal(12_345u64, -3))
+        );
+        assert_eq!(Ok(Decimal::ZERO), Decimal::try_from(Number::ZERO));
+    }
+
+    #[test]
+    fn precision_loss_is_rejected() {
+        let precise = Decimal::new(1, 16);
+
+        assert!(Number::try_from_rust_decimal(precise).is_err());
+        assert!(Number128::try_from_rust_decimal(precise).is_err());
+        assert!(Number::try_from_rust_decimal(Decimal::NEGATIVE_ONE).is_err());
+        assert_eq!(Err(Error::OutOfRange), Decimal::try_from(Number::MAX));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Err(Error::OutOfRange), Decimal::try_from(Number128::MAX));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::bigint::U192;
+use crate::{Error, Number, Number128};
+
+const NUMBER_PRECISION: i32 = 15;
+const NUMBER128_PRECISION: i32 = 10;
+
+/// A compact decimal, `mantissa * 10^exponent`, for storing values in
+/// accounts or passing them between programs.
+///
+/// Conversions to and from the number types are lossless, and fail rather
+/// than round when a value can't be represented exactly.
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecimalValue {
+    pub mantissa: i64,
+    pub exponent: i32,
+}
+
+impl DecimalValue {
+    pub fn new(mantissa: i64, exponent: i32) -> Self {
+        Self { mantissa, exponent }
+    }
+
+    /// Remove trailing zeros from the mantissa, so that equal values have
+    /// the same representation. Zero is normalized to `0 * 10^0`.
+    pub fn normalize(self) -> Self {
+        let (mantissa, exponent) = strip_zeros(self.mantissa as i128, self.exponent);
+
+        Self::new(mantissa as i64, exponent)
+    }
+}
+
+fn strip_zeros(mut mantissa: i128, mut exponent: i32) -> (i128, i32) {
+    if mantissa == 0 {
+        return (0, 0);
+    }
+
+    while mantissa % 10 == 0 && exponent < i32::MAX {
+        mantissa /= 10;
+        exponent += 1;
+    }
+
+    (mantissa, exponent)
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+impl TryFrom<DecimalValue> for Number {
+    type Error = Error;
+
+    fn try_from(d: DecimalValue) -> Result<Self, Self::Error> {
+        let d = d.normalize();
+
+        if d.mantissa < 0 {
+            return Err(Error::InvalidInput("negative value"));
+        }
+
+        let shift = NUMBER_PRECISION
+            .checked_add(d.exponent)
+            .ok_or(Error::ExponentOutOfRange(d.exponent))?;
+        if shift < 0 {
+            return Err(Error::InvalidInput("too many decimal places"));
+        }
+
+        U192::from(10)
+            .checked_pow(U192::from(shift))
+            .and_then(|scale| scale.checked_mul(U192::from(d.mantissa)))
+            .map(Number::from_u192)
+            .ok_or(Error::OutOfRange)
+    }
+}
+
+impl TryFrom<Number> for DecimalValue {
+    type Error = Error;
+
+    fn try_from(n: Number) -> Result<Self, Self::Error> {
+        let ten = U192::from(10);
+        let mut mantissa = n.to_u192();
+        let mut exponent = -NUMBER_PRECISION;
+
+        if mantissa.is_zero() {
+            return Ok(Self::default());
+        }
+
+        loop {
+            let (quotient, remainder) = mantissa.div_mod(ten);
+            if !remainder.is_zero() {
+                break;
+            }
+            mantissa = quotient;
+            exponent += 1;
+        }
+
+        if mantissa > U192::from(i64::MAX) {
+            return Err(Error::OutOfRange);
+        }
+
+        Ok(Self::new(mantissa.low_u64() as i64, exponent))
+    }
+}
+
+impl TryFrom<DecimalValue> for Number128 {
+    type Error = Error;
+
+    fn try_from(d: DecimalValue) -> Result<Self, Self::Error> {
+        let d = d.normalize();
+        let n = Number128::try_from_decimal(d.mantissa, d.exponent)?;
+
+        match DecimalValue::try_from(n) {
+            Ok(exact) if exact == d => Ok(n),
+            _ => Err(Error::InvalidInput("value can't be represented exactly")),
+        }
+    }
+}
+
+impl TryFrom<Number128> for DecimalValue {
+    type Error = Error;
+
+    fn try_from(n: Number128) -> Result<Self, Self::Error> {
+        // value = raw / one = raw * (decimal_one / one) / decimal_one
+        let decimal_one = 10i128.pow(NUMBER128_PRECISION as u32);
+        let one = Number128::ONE.to_i128();
+        let divisor = gcd(one, decimal_one);
+        let (denominator, numerator) = (one / divisor, decimal_one / divisor);
+
+        let raw = n.to_i128();
+        if raw % denominator != 0 {
+            return Err(Error::InvalidInput("value has no short decimal form"));
+        }
+
+        let mantissa = (raw / denominator)
+            .checked_mul(numerator)
+            .ok_or(Error::OutOfRange)?;
+        let (mantissa, exponent) = strip_zeros(mantissa, -NUMBER128_PRECISION);
+
+        i64::try_from(mantissa)
+            .map(|mantissa| Self::new(mantissa, exponent))
+            .map_err(|_| Error::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(
+            DecimalValue::new(15, -1),
+            DecimalValue::new(1500, -3).normalize()
+        );
+        assert_eq!(
+            DecimalValue::new(-2, 3),
+            DecimalValue::new(-200, 1).normalize()
+        );
+        assert_eq!(
+            DecimalValue::default(),
+            DecimalValue::new(0, -8).normalize()
+        );
+    }
+
+    #[test]
+    fn number_round_trip() {
+        let cases = [
+            (
+                DecimalValue::new(12_345, -3),
+                Number::from_decimal(12_345u64, -3),
+            ),
+            (DecimalValue::new(1, -15), Number::from_decimal(1u64, -15)),
+            (
+                DecimalValue::new(25, 6),
+                Number::from_decimal(25_000_000u64, 0),
+            ),
+            (DecimalValue::default(), Number::ZERO),
+        ];
+
+        for (decimal, number) in cases {
+            assert_eq!(Ok(number), Number::try_from(decimal));
+            assert_eq!(Ok(decimal), DecimalValue::try_from(number));
+        }
+    }
+
+    #[test]
+    fn number_inexact() {
+        assert!(Number::try_from(DecimalValue::new(1, -16)).is_err());
+        assert!(Number::try_from(DecimalValue::new(-1, 0)).is_err());
+        assert!(Number::try_from(DecimalValue::new(1, 60)).is_err());
+        assert!(Number::try_from(DecimalValue::new(1, i32::MAX)).is_err());
+        assert_eq!(Err(Error::OutOfRange), DecimalValue::try_from(Number::MAX));
+    }
+}
+
+#[cfg(test)]
+mod compat_tests {
+    use super::*;
+
+    #[test]
+    fn number_128_round_trip() {
+        let cases = [
+            (
+                DecimalValue::new(-125, -3),
+                Number128::from_decimal(-125, -3),
+            ),
+            (DecimalValue::new(3, 4), Number128::from_decimal(30_000, 0)),
+            (DecimalValue::default(), Number128::ZERO),
+        ];
+
+        for (decimal, number) in cases {
+            assert_eq!(Ok(number), Number128::try_from(decimal));
+            assert_eq!(Ok(decimal), DecimalValue::try_from(number));
+        }
+        assert_eq!(
+            Ok(Number128::from_decimal(15, -1)),
+            Number128::try_from(DecimalValue::new(1500, -3))
+        );
+    }
+
+    #[test]
+    fn number_128_inexact() {
+        assert!(Number128::try_from(DecimalValue::new(1, -11)).is_err());
+        assert!(Number128::try_from(DecimalValue::new(1, 40)).is_err());
+        assert!(DecimalValue::try_from(Number128::MAX).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Types that tag a number with what it measures, so that dimensionally
+//! meaningless arithmetic fails to compile.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+macro_rules! dimension {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+        #[repr(transparent)]
+        pub struct $name<N>(N);
+
+        impl<N> $name<N> {
+            pub const fn new(value: N) -> Self {
+                Self(value)
+            }
+
+            /// The raw number, without the dimension
+            pub fn into_inner(self) -> N {
+                self.0
+            }
+        }
+
+        impl<N> From<N> for $name<N> {
+            fn from(value: N) -> Self {
+                Self(value)
+            }
+        }
+    };
+}
+
+macro_rules! dimension_op {
+    ($lhs:ident $op:ident $method:ident $rhs:ident = $output:ident) => {
+        impl<N: $op<Output = N>> $op<$rhs<N>> for $lhs<N> {
+            type Output = $output<N>;
+
+            fn $method(self, rhs: $rhs<N>) -> Self::Output {
+                $output($op::$method(self.0, rhs.0))
+            }
+        }
+    };
+}
+
+dimension! {
+    /// A quantity of something, e.g. a token balance or a value in USD.
+    ///
+    /// `Amount`, `UnitRate` and `Factor` tag a number with what it measures,
+    /// so that only operations which make sense dimensionally compile:
+    ///
+    /// ```
+    /// # use jet_proto_math::{dimension::{Amount, UnitRate}, Number};
+    /// let balance = Amount::new(Number::from(1_000u64));
+    /// let rate = UnitRate::new(Number::from_bps(500));
+    ///
+    /// let interest: Amount<Number> = balance * rate;
+    /// assert_eq!(Number::from(1_050u64), (balance + interest).into_inner());
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use jet_proto_math::{dimension::{Amount, UnitRate}, Number};
+    /// let balance = Amount::new(Number::from(1_000u64));
+    /// let rate = UnitRate::new(Number::from_bps(500));
+    ///
+    /// let nonsense = balance + rate;
+    /// ```
+    Amount
+}
+
+dimension! {
+    /// A rate per unit of an amount, e.g. an interest rate or a fee rate.
+    ///
+    /// Not to be confused with [`crate::rate::Rate`], which is the price of
+    /// one asset in another.
+    UnitRate
+}
+
+dimension! {
+    /// A dimensionless scale, e.g. a ratio of two amounts or a collateral
+    /// weight
+    Factor
+}
+
+dimension_op!(Amount Add add Amount = Amount);
+dimension_op!(Amount Sub sub Amount = Amount);
+dimension_op!(Amount Mul mul UnitRate = Amount);
+dimension_op!(UnitRate Mul mul Amount = Amount);
+dimension_op!(Amount Mul mul Factor = Amount);
+dimension_op!(Factor Mul mul Amount = Amount);
+dimension_op!(Amount Div div Factor = Amount);
+dimension_op!(Amount Div div Amount = Factor);
+
+dimension_op!(UnitRate Add add UnitRate = UnitRate);
+dimension_op!(UnitRate Sub sub UnitRate = UnitRate);
+dimension_op!(UnitRate Mul mul UnitRate = UnitRate);
+dimension_op!(UnitRate Mul mul Factor = UnitRate);
+dimension_op!(Factor Mul mul UnitRate = UnitRate);
+dimension_op!(UnitRate Div div Factor = UnitRate);
+dimension_op!(UnitRate Div div UnitRate = Factor);
+
+dimension_op!(Factor Add add Factor = Factor);
+dimension_op!(Factor Sub sub Factor = Factor);
+dimension_op!(Factor Mul mul Factor = Factor);
+dimension_op!(Factor Div div Factor = Factor);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Number128;
+
+    #[test]
+    fn dimensional_operators() {
+        let collateral = Amount::new(Number128::from_decimal(2_000, 0));
+        let debt = Amount::new(Number128::from_decimal(1_000, 0));
+        let weight = Factor::new(Number128::from_bps(8_000));
+        let rate = UnitRate::new(Number128::from_bps(1_000));
+
+        let ratio: Factor<Number128> = collateral * weight / (debt + debt * rate);
+        #[cfg(not(feature = "binary-number128"))]
+        let compounded: UnitRate<Number128> = rate * rate + rate;
+
+        assert_eq!(
+            Number128::from_decimal(16, 0) / Number128::from_decimal(11, 0),
+            ratio.into_inner()
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Number128::from_bps(1_100), compounded.into_inner());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Amount::from(Number128::from_decimal(900, 0)),
+            debt - debt * rate
+        );
+    }
+}
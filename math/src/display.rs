@@ -0,0 +1,211 @@
+use std::fmt::{Display, Formatter};
+
+use crate::Error;
+
+/// Renders a number rounded to a fixed number of decimal places.
+///
+/// The plain `Display` impls always print the exact stored value, which can
+/// be misleading when only a few decimals are meaningful. Wrapping a value
+/// in `DisplayRounded` rounds it half away from zero and pads the result to
+/// exactly the requested number of decimals.
+///
+/// ```
+/// # use jet_proto_math::{DisplayRounded, Number};
+/// let n = Number::from_decimal(123456, -5);
+/// assert_eq!("1.235", DisplayRounded(n, 3).to_string());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayRounded<T>(pub T, pub u32);
+
+impl<T: Display> Display for DisplayRounded<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let exact = self.0.to_string();
+        let decimals = self.1 as usize;
+
+        let (negative, unsigned) = match exact.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, exact.as_str()),
+        };
+        let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let (int, frac) = round_half_up(int, frac, decimals);
+        let is_zero = int.bytes().chain(frac.bytes()).all(|d| d == b'0');
+
+        if negative && !is_zero {
+            write!(f, "-")?;
+        }
+        if decimals == 0 {
+            write!(f, "{}", int)
+        } else {
+            write!(f, "{}.{}", int, frac)
+        }
+    }
+}
+
+/// Round a decimal, given as its integer and fraction digits, half away
+/// from zero to exactly `decimals` places, returning the integer and
+/// fraction digits of the result.
+fn round_half_up(int: &str, frac: &str, decimals: usize) -> (String, String) {
+    let mut digits: Vec<u8> = int.bytes().chain(frac.bytes()).collect();
+    let point = int.len();
+
+    if frac.len() > decimals {
+        let round_up = digits[point + decimals] >= b'5';
+        digits.truncate(point + decimals);
+
+        if round_up {
+            let mut carry = true;
+            for digit in digits.iter_mut().rev() {
+                if *digit == b'9' {
+                    *digit = b'0';
+                } else {
+                    *digit += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                digits.insert(0, b'1');
+            }
+        }
+    } else {
+        digits.resize(point + decimals, b'0');
+    }
+
+    let point = digits.len() - decimals;
+    let int = std::str::from_utf8(&digits[..point]).unwrap();
+    let frac = std::str::from_utf8(&digits[point..]).unwrap();
+
+    (int.to_owned(), frac.to_owned())
+}
+
+const SI_SUFFIXES: [&str; 5] = ["", "K", "M", "B", "T"];
+
+/// Format an exact decimal string with a thousands suffix, e.g. `1.24M`,
+/// rounded half away from zero to at least `significant` digits.
+pub(crate) fn format_si(exact: &str, significant: usize) -> String {
+    let (negative, int, frac) = split_decimal(exact).unwrap();
+    let int = int.trim_start_matches('0');
+    let significant = significant.max(1);
+
+    let group = (int.len().saturating_sub(1) / 3).min(SI_SUFFIXES.len() - 1);
+    let (int, shifted) = int.split_at(int.len() - 3 * group);
+    let frac = shifted.to_owned() + frac;
+
+    let decimals = match int.is_empty() {
+        true => frac.len() - frac.trim_start_matches('0').len() + significant,
+        false => significant.saturating_sub(int.len()),
+    };
+    let (int, frac) = round_half_up(int, &frac, decimals);
+    let frac = frac.trim_end_matches('0');
+
+    let (int, frac, suffix) = match int.len() > 3 && group + 1 < SI_SUFFIXES.len() {
+        // rounded up to the next suffix
+        true => ("1", "", SI_SUFFIXES[group + 1]),
+        false => (int.as_str(), frac, SI_SUFFIXES[group]),
+    };
+    let int = match int.is_empty() {
+        true => "0",
+        false => int,
+    };
+    let sign = match negative && (int != "0" || !frac.is_empty()) {
+        true => "-",
+        false => "",
+    };
+
+    match frac.is_empty() {
+        true => format!("{sign}{int}{suffix}"),
+        false => format!("{sign}{int}.{frac}{suffix}"),
+    }
+}
+
+/// Split a decimal string like `-12.340` into its sign, integer digits and
+/// fraction digits, with trailing zeros removed from the fraction.
+pub(crate) fn split_decimal(s: &str) -> Result<(bool, &str, &str), Error> {
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    if int.is_empty() || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(Error::InvalidInput("not a decimal number"));
+    }
+
+    Ok((negative, int, frac.trim_end_matches('0')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Number, Number128};
+
+    #[test]
+    fn rounds_half_up() {
+        assert_eq!(
+            "1.24",
+            DisplayRounded(Number::from_decimal(1235, -3), 2).to_string()
+        );
+        assert_eq!(
+            "1.23",
+            DisplayRounded(Number::from_decimal(12349, -4), 2).to_string()
+        );
+    }
+
+    #[test]
+    fn pads_to_decimals() {
+        assert_eq!(
+            "1.5000",
+            DisplayRounded(Number::from_bps(15000), 4).to_string()
+        );
+        assert_eq!("1000", DisplayRounded(Number::from(1000u64), 0).to_string());
+    }
+
+    #[test]
+    fn carries_into_integer() {
+        assert_eq!(
+            "10.00",
+            DisplayRounded(Number::from_decimal(9999, -3), 2).to_string()
+        );
+        assert_eq!(
+            "1",
+            DisplayRounded(Number::from_decimal(5, -1), 0).to_string()
+        );
+    }
+
+    #[test]
+    fn negative_values() {
+        // -1.235 is not exact with binary scaling
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            "-1.24",
+            DisplayRounded(Number128::from_decimal(-1235, -3), 2).to_string()
+        );
+        assert_eq!(
+            "-1.13",
+            DisplayRounded(Number128::from_decimal(-1125, -3), 2).to_string()
+        );
+        assert_eq!(
+            "0.00",
+            DisplayRounded(Number128::from_decimal(-1, -3), 2).to_string()
+        );
+    }
+
+    #[test]
+    fn si_suffixes() {
+        assert_eq!("1.24M", format_si("1235000.5", 3));
+        assert_eq!("987.2K", format_si("987210", 4));
+        assert_eq!("987K", format_si("987210", 2));
+        assert_eq!("12.5", format_si("12.5", 3));
+        assert_eq!("0.00123", format_si("0.0012345", 3));
+        assert_eq!("0", format_si("0.0", 3));
+        assert_eq!("-1.5B", format_si("-1500000000", 3));
+        assert_eq!("1234T", format_si("1234000000000000", 3));
+    }
+
+    #[test]
+    fn si_rounds_into_next_suffix() {
+        assert_eq!("1M", format_si("999960", 4));
+        assert_eq!("1K", format_si("999.96", 4));
+        assert_eq!("-1M", format_si("-999999.9", 3));
+    }
+}
@@ -0,0 +1,99 @@
+//! Splitting an amount between recipients in proportion to their weights.
+//!
+//! Rounding each share down on its own leaves some of the amount
+//! undistributed, and rounding each share to nearest can distribute more
+//! than the amount. `distribute` uses the largest remainder method instead,
+//! so the shares always add up to exactly the amount being split.
+
+use std::cmp::Reverse;
+
+use crate::bigint::U256;
+use crate::{Error, Number};
+
+/// Split `total` in proportion to `weights`, with the shares summing to
+/// exactly `total`
+///
+/// Each share is first rounded down, and the units left over go to the
+/// shares with the largest remainders, the earliest first on ties. Returns
+/// an error if the weights sum to zero.
+pub fn distribute(total: u64, weights: &[Number]) -> Result<Vec<u64>, Error> {
+    // a weight has 192 bits, so the sum can't overflow 256 bits
+    let total_weight = weights
+        .iter()
+        .fold(U256::zero(), |sum, w| sum + U256::from(w.to_u192()));
+    if total_weight.is_zero() {
+        return Err(Error::InvalidInput("weights sum to zero"));
+    }
+
+    let (mut shares, remainders): (Vec<u64>, Vec<U256>) = weights
+        .iter()
+        .map(|w| {
+            let (share, remainder) =
+                (U256::from(total) * U256::from(w.to_u192())).div_mod(total_weight);
+            // a share is at most the total
+            (share.low_u64(), remainder)
+        })
+        .unzip();
+
+    // the leftover is the sum of the remainders, which is less than one
+    // unit per share
+    let leftover = total - shares.iter().sum::<u64>();
+
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by_key(|&i| Reverse(remainders[i]));
+    for &i in order.iter().take(leftover as usize) {
+        shares[i] += 1;
+    }
+
+    Ok(shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weights(values: &[u64]) -> Vec<Number> {
+        values.iter().map(|&v| Number::from(v)).collect()
+    }
+
+    #[test]
+    fn shares_sum_to_total() {
+        assert_eq!(Ok(vec![34, 33, 33]), distribute(100, &weights(&[1, 1, 1])));
+        assert_eq!(Ok(vec![1, 0, 0]), distribute(1, &weights(&[1, 1, 1])));
+        assert_eq!(Ok(vec![25, 75]), distribute(100, &weights(&[1, 3])));
+        assert_eq!(Ok(vec![0, 7, 0]), distribute(7, &weights(&[0, 5, 0])));
+        assert_eq!(Ok(vec![0, 0]), distribute(0, &weights(&[1, 2])));
+
+        let thirds = [Number::from_decimal(1u64, -15), Number::ONE, Number::MAX];
+        for total in [0, 1, 999, u64::MAX] {
+            let shares = distribute(total, &thirds).unwrap();
+            assert_eq!(total as u128, shares.iter().map(|&s| s as u128).sum());
+        }
+    }
+
+    #[test]
+    fn leftover_goes_to_largest_remainders() {
+        // exact shares of 1.4, 2.8 and 5.8
+        assert_eq!(Ok(vec![1, 3, 6]), distribute(10, &weights(&[7, 14, 29])));
+        // exact shares of 2.5 each, so the tie goes to the first
+        assert_eq!(Ok(vec![3, 2]), distribute(5, &weights(&[1, 1])));
+    }
+
+    #[test]
+    fn extreme_values() {
+        assert_eq!(
+            Ok(vec![u64::MAX / 2 + 1, u64::MAX / 2]),
+            distribute(u64::MAX, &[Number::MAX, Number::MAX])
+        );
+        assert_eq!(
+            Ok(vec![u64::MAX]),
+            distribute(u64::MAX, &[Number::from_decimal(1u64, -15)])
+        );
+    }
+
+    #[test]
+    fn zero_weights() {
+        assert!(distribute(10, &[]).is_err());
+        assert!(distribute(10, &weights(&[0, 0])).is_err());
+    }
+}
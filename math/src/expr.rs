@@ -0,0 +1,184 @@
+use std::ops::{Div, Mul};
+
+use crate::bigint::{U192, U384};
+use crate::{div_rounded, Error, Number, Rounding};
+
+/// A chain of multiplications and divisions on `Number`, evaluated with a
+/// single rounding step.
+///
+/// Chaining the operators rounds after every step, so for example
+/// `ONE / 3 * 3` is slightly less than one. An expression keeps the exact
+/// result as a 384-bit fraction instead, and only rounds in `eval`.
+///
+/// The fraction is reduced when it would no longer fit in 384 bits, so
+/// long chains only fail if the reduced fraction is still too large.
+///
+/// ```
+/// # use std::ops::{Div, Mul};
+/// # use jet_proto_math::{Number, NumberExpr, Rounding};
+/// let three = Number::from(3u64);
+/// let expr = NumberExpr::from(Number::ONE).div(three).mul(three);
+///
+/// assert_eq!(Ok(Number::ONE), expr.round(Rounding::Down).eval());
+/// assert!(Number::ONE / three * three < Number::ONE);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NumberExpr {
+    /// The exact value as a fraction of underlying values, or `None` if an
+    /// intermediate value overflowed
+    fraction: Option<(U384, U384)>,
+    rounding: Rounding,
+}
+
+impl NumberExpr {
+    /// Set how the result is rounded in `eval`, which is `Rounding::Down`
+    /// by default
+    pub fn round(self, rounding: Rounding) -> Self {
+        Self { rounding, ..self }
+    }
+
+    /// Compute the result, rounding once
+    ///
+    /// Returns an error if the result or any intermediate value overflows,
+    /// or if the expression divides by zero.
+    pub fn eval(&self) -> Result<Number, Error> {
+        let (numerator, denominator) = self.fraction.ok_or(Error::OutOfRange)?;
+
+        if denominator.is_zero() {
+            return Err(Error::DivideByZero);
+        }
+
+        div_rounded(numerator, denominator, self.rounding)
+            .checked_as_u192()
+            .map(Number::from_u192)
+            .ok_or(Error::OutOfRange)
+    }
+
+    fn scale(self, numerator: U192, denominator: U192) -> Self {
+        let (a, b) = (U384::from(numerator), U384::from(denominator));
+        let fraction = self.fraction.and_then(|(n, d)| {
+            multiply(n, a, d, b).or_else(|| {
+                // reducing is much more expensive than multiplying, so only
+                // do it once the fraction gets too large
+                let (n, d) = reduce(n, d);
+                let (n, b) = reduce(n, b);
+                let (a, d) = reduce(a, d);
+                multiply(n, a, d, b)
+            })
+        });
+
+        Self { fraction, ..self }
+    }
+}
+
+/// Multiply the fraction `n / d` by `a / b`
+fn multiply(n: U384, a: U384, d: U384, b: U384) -> Option<(U384, U384)> {
+    Some((n.checked_mul(a)?, d.checked_mul(b)?))
+}
+
+/// Divide a numerator and a denominator by their greatest common divisor
+fn reduce(numerator: U384, denominator: U384) -> (U384, U384) {
+    match numerator.gcd(denominator) {
+        gcd if gcd.is_zero() => (numerator, denominator),
+        gcd => (numerator / gcd, denominator / gcd),
+    }
+}
+
+impl From<Number> for NumberExpr {
+    fn from(n: Number) -> Self {
+        Self {
+            fraction: Some((U384::from(n.to_u192()), U384::one())),
+            rounding: Rounding::Down,
+        }
+    }
+}
+
+impl Mul<Number> for NumberExpr {
+    type Output = NumberExpr;
+
+    fn mul(self, rhs: Number) -> Self::Output {
+        self.scale(rhs.to_u192(), Number::ONE.to_u192())
+    }
+}
+
+impl Div<Number> for NumberExpr {
+    type Output = NumberExpr;
+
+    fn div(self, rhs: Number) -> Self::Output {
+        self.scale(Number::ONE.to_u192(), rhs.to_u192())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_once() {
+        let third = NumberExpr::from(Number::ONE) / Number::from(3u64);
+
+        assert_eq!(
+            Ok(Number::from_decimal(333_333_333_333_333u64, -15)),
+            third.eval()
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(333_333_333_333_334u64, -15)),
+            third.round(Rounding::Up).eval()
+        );
+        assert_eq!(Ok(Number::ONE), (third * Number::from(3u64)).eval());
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let half = NumberExpr::from(Number::from_decimal(5u64, -15)) / Number::from(10u64);
+        let quarter = NumberExpr::from(Number::from_decimal(1u64, -15)) / Number::from(4u64);
+
+        assert_eq!(Ok(Number::ZERO), half.eval());
+        assert_eq!(
+            Ok(Number::from_decimal(1u64, -15)),
+            half.round(Rounding::HalfUp).eval()
+        );
+        assert_eq!(Ok(Number::ZERO), half.round(Rounding::HalfEven).eval());
+        assert_eq!(Ok(Number::ZERO), quarter.round(Rounding::HalfUp).eval());
+    }
+
+    #[test]
+    fn matches_checked_mul_div() {
+        let a = Number::from_decimal(123_456_789u64, -4);
+        let b = Number::from_decimal(987_654_321u64, -7);
+        let c = Number::from_decimal(42u64, -1);
+
+        assert_eq!(
+            Ok(a.checked_mul_div(&b, &c).unwrap()),
+            (NumberExpr::from(a) * b / c).eval()
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let max = NumberExpr::from(Number::MAX);
+
+        assert_eq!(Err(Error::DivideByZero), (max / Number::ZERO).eval());
+        assert_eq!(Err(Error::OutOfRange), (max * Number::from(2u64)).eval());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            (max * Number::MAX * Number::MAX / Number::MAX).eval()
+        );
+        assert_eq!(Ok(Number::MAX), (max * Number::ONE / Number::ONE).eval());
+    }
+
+    #[test]
+    fn long_chains_are_reduced() {
+        let two = Number::from(2u64);
+        let three = Number::from(3u64);
+
+        let ones = (0..20).fold(NumberExpr::from(two), |expr, _| expr * Number::ONE);
+        assert_eq!(Ok(two), ones.eval());
+
+        let thirds = (0..20).fold(NumberExpr::from(two), |expr, _| expr / three * three);
+        assert_eq!(Ok(two), thirds.eval());
+
+        let max = (0..4).fold(NumberExpr::from(Number::MAX), |expr, _| expr * Number::ONE);
+        assert_eq!(Ok(Number::MAX), max.eval());
+    }
+}
@@ -0,0 +1,152 @@
+//! Tiered fee schedules that can be stored in an account.
+//!
+//! A schedule is a list of tiers, each with a minimum notional and a fee in
+//! basis points. The rate of the highest tier whose threshold is reached
+//! applies to the whole amount. Fees are rounded up, and never exceed the
+//! amount, so `fee + net == amount` always holds.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::Error;
+
+/// The maximum number of tiers a `FeeSchedule` can hold
+pub const MAX_FEE_TIERS: usize = 8;
+
+const BPS_PER_UNIT: u64 = 10_000;
+
+/// A fee rate that applies from a minimum notional upwards
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct FeeTier {
+    /// The smallest amount this tier applies to
+    pub threshold: u64,
+    /// The fee rate in basis points
+    pub fee_bps: u16,
+    _reserved: [u8; 6],
+}
+
+impl FeeTier {
+    pub fn new(threshold: u64, fee_bps: u16) -> Self {
+        Self {
+            threshold,
+            fee_bps,
+            _reserved: [0; 6],
+        }
+    }
+}
+
+/// A tiered fee schedule, with a fixed size so it can be stored in an account
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct FeeSchedule {
+    tiers: [FeeTier; MAX_FEE_TIERS],
+    len: u64,
+}
+
+impl FeeSchedule {
+    /// Create a schedule from tiers sorted by increasing threshold
+    ///
+    /// Amounts below the first threshold are charged no fee.
+    pub fn new(tiers: &[FeeTier]) -> Result<Self, Error> {
+        if tiers.len() > MAX_FEE_TIERS {
+            return Err(Error::InvalidInput("too many fee tiers"));
+        }
+        if tiers.windows(2).any(|w| w[0].threshold >= w[1].threshold) {
+            return Err(Error::InvalidInput("fee tiers are not sorted"));
+        }
+        if tiers.iter().any(|t| t.fee_bps as u64 > BPS_PER_UNIT) {
+            return Err(Error::InvalidInput("fee exceeds 10000 bps"));
+        }
+
+        let mut schedule = Self::zeroed();
+        schedule.tiers[..tiers.len()].copy_from_slice(tiers);
+        schedule.len = tiers.len() as u64;
+
+        Ok(schedule)
+    }
+
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers[..(self.len as usize).min(MAX_FEE_TIERS)]
+    }
+
+    /// The fee rate in basis points that applies to an amount
+    pub fn fee_bps(&self, amount: u64) -> u16 {
+        let tiers = self.tiers();
+        let index = tiers.partition_point(|t| t.threshold <= amount);
+
+        match index {
+            0 => 0,
+            _ => tiers[index - 1].fee_bps,
+        }
+    }
+
+    /// Split an amount into the fee charged and the net amount remaining
+    pub fn apply(&self, amount: u64) -> (u64, u64) {
+        let bps = self.fee_bps(amount).min(BPS_PER_UNIT as u16) as u128;
+        let fee = (amount as u128 * bps).div_ceil(BPS_PER_UNIT as u128) as u64;
+
+        (fee, amount - fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> FeeSchedule {
+        FeeSchedule::new(&[
+            FeeTier::new(0, 30),
+            FeeTier::new(1_000_000, 20),
+            FeeTier::new(10_000_000, 10),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn selects_tier_by_amount() {
+        let schedule = schedule();
+
+        assert_eq!((300, 99_700), schedule.apply(100_000));
+        assert_eq!((2_000, 998_000), schedule.apply(1_000_000));
+        assert_eq!((10_000, 9_990_000), schedule.apply(10_000_000));
+        assert_eq!((0, 0), schedule.apply(0));
+    }
+
+    #[test]
+    fn fee_rounds_up() {
+        let schedule = schedule();
+
+        assert_eq!((1, 0), schedule.apply(1));
+        assert_eq!((1, 332), schedule.apply(333));
+    }
+
+    #[test]
+    fn fee_and_net_sum_to_amount() {
+        let full = FeeSchedule::new(&[FeeTier::new(0, 10_000)]).unwrap();
+
+        for amount in [0, 1, 17, u64::MAX] {
+            let (fee, net) = full.apply(amount);
+            assert_eq!(amount, fee + net);
+        }
+        for amount in [0, 1, 9_999, 12_345_678, u64::MAX] {
+            let (fee, net) = schedule().apply(amount);
+            assert_eq!(amount, fee + net);
+        }
+    }
+
+    #[test]
+    fn below_first_threshold_is_free() {
+        let schedule = FeeSchedule::new(&[FeeTier::new(100, 50)]).unwrap();
+
+        assert_eq!((0, 99), schedule.apply(99));
+        assert_eq!((1, 99), schedule.apply(100));
+        assert_eq!((0, 5), FeeSchedule::default().apply(5));
+    }
+
+    #[test]
+    fn rejects_invalid_tiers() {
+        assert!(FeeSchedule::new(&[FeeTier::new(10, 1), FeeTier::new(10, 2)]).is_err());
+        assert!(FeeSchedule::new(&[FeeTier::new(0, 10_001)]).is_err());
+        assert!(FeeSchedule::new(&[FeeTier::default(); MAX_FEE_TIERS + 1]).is_err());
+    }
+}
@@ -1,4 +1,8 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::bigint::U256;
 use crate::number::*;
+use crate::Number128;
 
 /// Computes the Taylor expansion of exp(x) - 1, using the
 /// indicated number of terms.
@@ -25,10 +29,603 @@ pub fn expm1_approx(x: Number, terms: usize) -> Number {
     acc
 }
 
+/// `2^(-2^-i)` for `i` in `1..=50`, as the raw values of a `Number`,
+/// rounded to the nearest
+const EXP2_FRACTIONS: [u64; 50] = [
+    707_106_781_186_548,
+    840_896_415_253_715,
+    917_004_043_204_671,
+    957_603_280_698_574,
+    978_572_062_087_700,
+    989_228_013_193_975,
+    994_599_423_483_633,
+    997_296_056_085_470,
+    998_647_112_890_970,
+    999_323_327_502_651,
+    999_661_606_496_244,
+    999_830_788_931_929,
+    999_915_390_886_613,
+    999_957_694_548_431,
+    999_978_847_050_492,
+    999_989_423_469_314,
+    999_994_711_720_674,
+    999_997_355_856_841,
+    999_998_677_927_547,
+    999_999_338_963_555,
+    999_999_669_481_723,
+    999_999_834_740_848,
+    999_999_917_370_420,
+    999_999_958_685_209,
+    999_999_979_342_604,
+    999_999_989_671_302,
+    999_999_994_835_651,
+    999_999_997_417_826,
+    999_999_998_708_913,
+    999_999_999_354_456,
+    999_999_999_677_228,
+    999_999_999_838_614,
+    999_999_999_919_307,
+    999_999_999_959_654,
+    999_999_999_979_827,
+    999_999_999_989_913,
+    999_999_999_994_957,
+    999_999_999_997_478,
+    999_999_999_998_739,
+    999_999_999_999_370,
+    999_999_999_999_685,
+    999_999_999_999_842,
+    999_999_999_999_921,
+    999_999_999_999_961,
+    999_999_999_999_980,
+    999_999_999_999_990,
+    999_999_999_999_995,
+    999_999_999_999_998,
+    999_999_999_999_999,
+    999_999_999_999_999,
+];
+
+/// Decays a value exponentially, halving it every `half_life_seconds`.
+///
+/// Whole half-lives are applied exactly, and the fraction of a half-life
+/// is approximated from its binary digits with a table of powers of two.
+/// The result never increases with the elapsed time, and a zero half-life
+/// decays the value to zero immediately.
+pub fn decay(initial: Number, half_life_seconds: u64, elapsed: u64) -> Number {
+    if elapsed == 0 {
+        return initial;
+    }
+    if half_life_seconds == 0 {
+        return Number::ZERO;
+    }
+
+    let halvings = elapsed / half_life_seconds;
+    if halvings >= Number::BITS as u64 {
+        return Number::ZERO;
+    }
+
+    let half_life = half_life_seconds as u128;
+    let mut remainder = (elapsed % half_life_seconds) as u128;
+    let mut factor = Number::ONE;
+
+    for fraction in EXP2_FRACTIONS {
+        if remainder == 0 {
+            break;
+        }
+
+        remainder *= 2;
+        if remainder >= half_life {
+            remainder -= half_life;
+            factor *= Number::from_decimal(fraction, -15);
+        }
+    }
+
+    Number::from_u192((initial * factor).to_u192() >> halvings as usize)
+}
+
+/// The extra precision carried while summing the series in `ln_1p` and
+/// `exp_m1`, as a factor of 10^20
+const SERIES_SCALE: i128 = 100_000_000_000_000_000_000;
+
+impl Number {
+    /// Computes `ln(1 + x)`, keeping full precision for small `x` such as
+    /// per-slot rates.
+    ///
+    /// The series is summed with 20 extra digits, so the result is within
+    /// one unit in the last place of the exact value, including for the
+    /// `x < 1e-4` of accrual math where a generic `ln` loses most of its
+    /// digits. Returns an error if `x > 1/2`, where the series converges
+    /// slowly.
+    pub fn ln_1p(&self) -> Result<Number, Error> {
+        self.series(ln_1p_series)
+    }
+
+    /// Computes `exp(x) - 1`, keeping full precision for small `x` such as
+    /// per-slot rates.
+    ///
+    /// The series is summed with 20 extra digits, so the result is within
+    /// one unit in the last place of the exact value. Returns an error if
+    /// `x > 1/2`.
+    pub fn exp_m1(&self) -> Result<Number, Error> {
+        self.series(exp_m1_series)
+    }
+
+    fn series(&self, series: fn(i128, i128) -> i128) -> Result<Number, Error> {
+        let one = Number::ONE.to_u192().as_u128() as i128;
+
+        match *self <= Number::ONE / 2u64 {
+            true => Ok(Number::from_u192(
+                (series(self.to_u192().as_u128() as i128, one) as u128).into(),
+            )),
+            false => Err(Error::InvalidInput("series argument is larger than 1/2")),
+        }
+    }
+}
+
+impl Number128 {
+    /// Computes `ln(1 + x)`, keeping full precision for small `x` such as
+    /// per-slot rates.
+    ///
+    /// The series is summed with 20 extra digits, so the result is within
+    /// one unit in the last place of the exact value. Returns an error if
+    /// `|x| > 1/2`, where the series converges slowly.
+    pub fn ln_1p(&self) -> Result<Number128, Error> {
+        self.series(ln_1p_series)
+    }
+
+    /// Computes `exp(x) - 1`, keeping full precision for small `x` such as
+    /// per-slot rates.
+    ///
+    /// The series is summed with 20 extra digits, so the result is within
+    /// one unit in the last place of the exact value. Returns an error if
+    /// `|x| > 1/2`.
+    pub fn exp_m1(&self) -> Result<Number128, Error> {
+        self.series(exp_m1_series)
+    }
+
+    fn series(&self, series: fn(i128, i128) -> i128) -> Result<Number128, Error> {
+        let one = Number128::ONE.to_i128();
+
+        match self.to_i128().unsigned_abs() <= one as u128 / 2 {
+            true => Ok(Number128::from_i128(series(self.to_i128(), one))),
+            false => Err(Error::InvalidInput("series argument is larger than 1/2")),
+        }
+    }
+}
+
+/// Sums `x - x^2 / 2 + x^3 / 3 - ...` for the raw value `x` of a number
+/// scaled by `one`, rounding to the nearest raw value
+fn ln_1p_series(x: i128, one: i128) -> i128 {
+    let (x, scale) = (x * SERIES_SCALE, one * SERIES_SCALE);
+    let (mut power, mut sum) = (x, x);
+
+    for k in 2.. {
+        power = mul_scaled(power, x, scale);
+        let term = power / k;
+        if term == 0 {
+            break;
+        }
+
+        match k % 2 {
+            0 => sum -= term,
+            _ => sum += term,
+        }
+    }
+
+    round_series(sum)
+}
+
+/// Sums `x + x^2 / 2! + x^3 / 3! + ...` for the raw value `x` of a number
+/// scaled by `one`, rounding to the nearest raw value
+fn exp_m1_series(x: i128, one: i128) -> i128 {
+    let (x, scale) = (x * SERIES_SCALE, one * SERIES_SCALE);
+    let (mut term, mut sum) = (x, x);
+
+    for k in 2.. {
+        term = mul_scaled(term, x, scale) / k;
+        if term == 0 {
+            break;
+        }
+
+        sum += term;
+    }
+
+    round_series(sum)
+}
+
+/// Computes `a * b / scale`, rounded toward zero
+fn mul_scaled(a: i128, b: i128, scale: i128) -> i128 {
+    let product = U256::from(a.unsigned_abs()) * U256::from(b.unsigned_abs());
+    let magnitude = (product / U256::from(scale)).as_u128() as i128;
+
+    match (a < 0) != (b < 0) {
+        true => -magnitude,
+        false => magnitude,
+    }
+}
+
+/// Removes the extra precision, rounding half away from zero
+fn round_series(sum: i128) -> i128 {
+    let half = SERIES_SCALE / 2;
+
+    match sum < 0 {
+        true => (sum - half) / SERIES_SCALE,
+        false => (sum + half) / SERIES_SCALE,
+    }
+}
+
+/// The logistic curve `1 / (1 + exp(-steepness * (x - midpoint)))`, which
+/// rises from 0 to 1, passing through 1/2 at the midpoint.
+///
+/// `exp` is computed from `exp_m1` by halving the exponent until the series
+/// applies and squaring the result back, so the result is accurate to about
+/// 1e-8. Returns an error if `steepness * (x - midpoint)` overflows.
+pub fn sigmoid(
+    x: Number128,
+    steepness: Number128,
+    midpoint: Number128,
+) -> Result<Number128, Error> {
+    let t = x
+        .checked_sub(&midpoint)
+        .and_then(|d| d.checked_mul(&steepness))
+        .and_then(|t| t.checked_neg().map(|neg| (t, neg)))
+        .ok_or(Error::OutOfRange)?;
+
+    // only take `exp` of negative values, which can't overflow
+    Ok(match t {
+        (t, _) if t >= Number128::ZERO => Number128::ONE / (Number128::ONE + exp_neg(t)),
+        (_, neg) => Number128::ONE - Number128::ONE / (Number128::ONE + exp_neg(neg)),
+    })
+}
+
+/// The inverse of `sigmoid`: the `x` at which the curve reaches `y`.
+///
+/// Returns an error if `y` is not strictly between 0 and 1, if the
+/// steepness is zero, or if the result overflows.
+pub fn inverse_sigmoid(
+    y: Number128,
+    steepness: Number128,
+    midpoint: Number128,
+) -> Result<Number128, Error> {
+    if y <= Number128::ZERO || y >= Number128::ONE {
+        return Err(Error::InvalidInput("sigmoid value is not between 0 and 1"));
+    }
+    if steepness == Number128::ZERO {
+        return Err(Error::DivideByZero);
+    }
+
+    let logit = ln(y) - ln(Number128::ONE - y);
+
+    logit
+        .checked_div(&steepness)
+        .and_then(|d| d.checked_add(&midpoint))
+        .ok_or(Error::OutOfRange)
+}
+
+/// Computes `exp(-t)` for `t >= 0`, by halving `t` until `exp_m1` applies
+/// and squaring the result back
+fn exp_neg(t: Number128) -> Number128 {
+    let half = Number128::ONE / 2i128;
+    let (mut reduced, mut squarings) = (t, 0);
+
+    while reduced > half {
+        reduced = reduced / 2i128;
+        squarings += 1;
+    }
+
+    let mut result = Number128::ONE + (-reduced).exp_m1().unwrap();
+    for _ in 0..squarings {
+        result = result * result;
+    }
+
+    result
+}
+
+/// Computes `ln(value)` for `value > 0`, by scaling it by a power of two
+/// until `ln_1p` applies
+pub(crate) fn ln(value: Number128) -> Number128 {
+    let half = Number128::ONE / 2i128;
+    let ln_2 = -(-half).ln_1p().unwrap();
+
+    let k = value.log2_floor().unwrap();
+    let mantissa = match k >= 0 {
+        true => Number128::from_i128(value.to_i128() >> k),
+        false => Number128::from_i128(value.to_i128() << -k),
+    };
+    let (k, mantissa) = match mantissa > Number128::ONE + half {
+        true => (k + 1, mantissa / 2i128),
+        false => (k, mantissa),
+    };
+
+    ln_2 * k + (mantissa - Number128::ONE).ln_1p().unwrap()
+}
+
+/// Computes the weighted average of a set of rates, such as the borrow
+/// rates of several pools weighted by their outstanding borrows.
+///
+/// The weights do not need to be normalized. Returns an error if the
+/// total weight is zero, or if any intermediate value overflows.
+pub fn blend_rates(
+    rates_and_weights: impl Iterator<Item = (Number, Number)>,
+) -> Result<Number, Error> {
+    let mut weighted_sum = Number::ZERO;
+    let mut total_weight = Number::ZERO;
+
+    for (rate, weight) in rates_and_weights {
+        let weighted = rate
+            .checked_mul(&weight)
+            .ok_or(Error::Overflow(weighted_sum))?;
+
+        weighted_sum = weighted_sum
+            .checked_add(&weighted)
+            .ok_or(Error::Overflow(weighted_sum))?;
+        total_weight = total_weight
+            .checked_add(&weight)
+            .ok_or(Error::Overflow(total_weight))?;
+    }
+
+    if total_weight == Number::ZERO {
+        return Err(Error::DivideByZero);
+    }
+
+    weighted_sum
+        .checked_div(&total_weight)
+        .ok_or(Error::Overflow(weighted_sum))
+}
+
+/// Linearly interpolates between `a` and `b`, where `t` is the fraction
+/// of the way from `a` to `b`.
+///
+/// Values of `t` outside of `[0, 1]` extrapolate, which for an unsigned
+/// `Number` panics if the result would be negative.
+pub fn lerp<T>(a: T, b: T, t: T) -> T
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    if b >= a {
+        a + (b - a) * t
+    } else {
+        a - (a - b) * t
+    }
+}
+
+/// Linearly interpolates between `a` and `b`, clamping the result to
+/// lie between them.
+pub fn lerp_clamped<T>(a: T, b: T, t: T) -> T
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    clamp(lerp(a, b, t), lo, hi)
+}
+
+/// The inverse of `lerp`: computes the fraction `t` of the way `value`
+/// is from `a` to `b`.
+///
+/// Panics if `a == b`, or if `value` lies outside of `[a, b]` and the
+/// result would be negative for an unsigned `Number`.
+pub fn inverse_lerp<T>(a: T, b: T, value: T) -> T
+where
+    T: Copy + PartialOrd + Sub<Output = T> + Div<Output = T>,
+{
+    if b >= a {
+        (value - a) / (b - a)
+    } else {
+        (a - value) / (a - b)
+    }
+}
+
+/// Computes the fraction `t` of the way `value` is from `a` to `b`,
+/// clamping `value` to lie between `a` and `b` first.
+pub fn inverse_lerp_clamped<T>(a: T, b: T, value: T) -> T
+where
+    T: Copy + PartialOrd + Sub<Output = T> + Div<Output = T>,
+{
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    inverse_lerp(a, b, clamp(value, lo, hi))
+}
+
+/// Maps a value from one range onto another
+pub fn remap<T>(range_in: (T, T), range_out: (T, T), value: T) -> T
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    let t = inverse_lerp(range_in.0, range_in.1, value);
+    lerp(range_out.0, range_out.1, t)
+}
+
+/// Maps a value from one range onto another, saturating at the ends of
+/// the output range for values outside of the input range.
+pub fn remap_clamped<T>(range_in: (T, T), range_out: (T, T), value: T) -> T
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    let t = inverse_lerp_clamped(range_in.0, range_in.1, value);
+    lerp_clamped(range_out.0, range_out.1, t)
+}
+
+fn clamp<T: PartialOrd>(value: T, lo: T, hi: T) -> T {
+    if value < lo {
+        lo
+    } else if value > hi {
+        hi
+    } else {
+        value
+    }
+}
+
+/// A piecewise constant function, defined by a set of `(x, y)`
+/// breakpoints sorted by `x`.
+///
+/// The function takes the `y` value of the last breakpoint at or before
+/// the input. Inputs before the first breakpoint saturate to its value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StepFunction<T> {
+    breakpoints: Vec<(T, T)>,
+}
+
+impl<T: Copy + PartialOrd> StepFunction<T> {
+    /// Create a step function from breakpoints, which must be non-empty
+    /// and strictly increasing in `x`
+    pub fn new(breakpoints: Vec<(T, T)>) -> Result<Self, Error> {
+        if breakpoints.is_empty() {
+            return Err(Error::InvalidInput("step function has no breakpoints"));
+        }
+        if breakpoints.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(Error::InvalidInput(
+                "step function breakpoints are not sorted",
+            ));
+        }
+
+        Ok(Self { breakpoints })
+    }
+
+    pub fn breakpoints(&self) -> &[(T, T)] {
+        &self.breakpoints
+    }
+
+    /// Evaluate the function at `x`
+    pub fn evaluate(&self, x: T) -> T {
+        let index = self.breakpoints.partition_point(|(bx, _)| *bx <= x);
+        self.breakpoints[index.saturating_sub(1)].1
+    }
+}
+
+/// Finds the input at which a monotonic function reaches `target`, by
+/// bisection over `[lo, hi]`.
+///
+/// The function may be increasing or decreasing. The exact solution lies
+/// within `tol` above the returned value, which is never greater than it.
+/// Returns an error if `lo > hi`, or if `target` is not between `f(lo)`
+/// and `f(hi)`.
+pub fn solve_monotonic(
+    f: impl Fn(Number) -> Number,
+    target: Number,
+    mut lo: Number,
+    mut hi: Number,
+    tol: Number,
+) -> Result<Number, Error> {
+    if lo > hi {
+        return Err(Error::InvalidInput("solver bounds are not sorted"));
+    }
+
+    let (f_lo, f_hi) = (f(lo), f(hi));
+    let increasing = f_lo <= f_hi;
+
+    if f_lo == target {
+        return Ok(lo);
+    }
+    if !(f_lo.min(f_hi) <= target && target <= f_lo.max(f_hi)) {
+        return Err(Error::InvalidInput("solver target is out of range"));
+    }
+
+    while hi - lo > tol {
+        let mid = lo + (hi - lo) / 2u64;
+        if mid == lo {
+            break;
+        }
+
+        let f_mid = f(mid);
+        if f_mid == target {
+            return Ok(mid);
+        }
+
+        if (f_mid < target) == increasing {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Ok(lo)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn series_near_zero() {
+        let n = |value: u64, exponent: i32| Number::from_decimal(value, exponent);
+
+        assert_eq!(Ok(n(100_005_000_167, -15)), n(1, -4).exp_m1());
+        assert_eq!(Ok(n(99_995_000_333, -15)), n(1, -4).ln_1p());
+        assert_eq!(Ok(n(1_234, -12)), n(1_234, -12).exp_m1());
+        assert_eq!(Ok(n(1_234, -12)), n(1_234, -12).ln_1p());
+        assert_eq!(Ok(n(10_050_167_084_168, -15)), n(1, -2).exp_m1());
+        assert_eq!(Ok(n(9_950_330_853_168, -15)), n(1, -2).ln_1p());
+        assert_eq!(Ok(n(648_721_270_700_128, -15)), n(5, -1).exp_m1());
+        assert_eq!(Ok(n(405_465_108_108_164, -15)), n(5, -1).ln_1p());
+        assert_eq!(Ok(Number::ZERO), Number::ZERO.ln_1p());
+        assert!(n(6, -1).exp_m1().is_err());
+        assert!(n(6, -1).ln_1p().is_err());
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn series_near_zero_128() {
+        let n = |value: i64, exponent: i32| Number128::from_decimal(value, exponent);
+
+        assert_eq!(Ok(n(1_000_050, -10)), n(1, -4).exp_m1());
+        assert_eq!(Ok(n(999_950, -10)), n(1, -4).ln_1p());
+        assert_eq!(Ok(n(-999_950, -10)), n(-1, -4).exp_m1());
+        assert_eq!(Ok(n(-1_000_050, -10)), n(-1, -4).ln_1p());
+        assert_eq!(Ok(n(-3_934_693_403, -10)), n(-5, -1).exp_m1());
+        assert_eq!(Ok(n(-6_931_471_806, -10)), n(-5, -1).ln_1p());
+        assert!(n(-6, -1).ln_1p().is_err());
+        assert!(n(6, -1).exp_m1().is_err());
+    }
+
+    #[test]
+    fn sigmoid_curve() {
+        let n = |value: i64, exponent: i32| Number128::from_decimal(value, exponent);
+        let (steepness, midpoint) = (n(20, 0), n(8, -1));
+
+        assert_eq!(Ok(n(5, -1)), sigmoid(midpoint, steepness, midpoint));
+
+        for x in [0.0f64, 0.3, 0.75, 0.79, 0.8001, 0.85, 1.0, 3.0] {
+            let y = sigmoid(n((x * 1e4) as i64, -4), steepness, midpoint).unwrap();
+            let expected = 1.0 / (1.0 + (-20.0 * (x - 0.8)).exp());
+            assert!((y.as_f64() - expected).abs() < 1e-8, "sigmoid({x})");
+
+            // near 0 and 1, the curve is too flat to invert precisely
+            if n(1, -3) < y && y < n(999, -3) {
+                let inverse = inverse_sigmoid(y, steepness, midpoint).unwrap();
+                assert!((inverse.as_f64() - x).abs() < 1e-6, "inverse_sigmoid({y})");
+            }
+        }
+
+        let low = sigmoid(n(7, -1), steepness, midpoint).unwrap();
+        let high = sigmoid(n(9, -1), steepness, midpoint).unwrap();
+        assert!((low + high - Number128::ONE).as_f64().abs() < 1e-9);
+        assert_eq!(Ok(Number128::ZERO), sigmoid(-n(1, 9), steepness, midpoint));
+    }
+
+    #[test]
+    fn sigmoid_errors() {
+        let n = |value: i64, exponent: i32| Number128::from_decimal(value, exponent);
+
+        assert!(sigmoid(Number128::MAX, n(2, 0), Number128::ZERO).is_err());
+        assert!(inverse_sigmoid(Number128::ONE, n(2, 0), Number128::ZERO).is_err());
+        assert!(inverse_sigmoid(Number128::ZERO, n(2, 0), Number128::ZERO).is_err());
+        assert_eq!(
+            Err(Error::DivideByZero),
+            inverse_sigmoid(n(5, -1), Number128::ZERO, Number128::ZERO)
+        );
+    }
+
+    #[test]
+    fn series_matches_f64() {
+        for x in [1e-9, 3.7e-8, 2.5e-6, 9.9e-5, 0.123, 0.49] {
+            let number = Number::from_decimal((x * 1e12) as u64, -12);
+            let x = number.as_f64();
+
+            let ln = number.ln_1p().unwrap().as_f64();
+            let exp = number.exp_m1().unwrap().as_f64();
+            assert!((ln - x.ln_1p()).abs() <= 1e-15, "ln_1p({x})");
+            assert!((exp - x.exp_m1()).abs() <= 1e-15, "exp_m1({x})");
+        }
+    }
+
     #[test]
     fn test_5() {
         assert_eq!(
@@ -36,4 +633,230 @@ mod tests {
             expm1_approx(Number::from_decimal(2, -1), 5)
         )
     }
+
+    #[test]
+    fn blend_two_rates() {
+        let blended = blend_rates(
+            [
+                (Number::from_bps(500), Number::from(300u64)),
+                (Number::from_bps(1000), Number::from(100u64)),
+            ]
+            .into_iter(),
+        );
+
+        assert_eq!(Ok(Number::from_bps(625)), blended);
+    }
+
+    #[test]
+    fn blend_single_rate_ignores_weight_scale() {
+        let rate = Number::from_decimal(123, -3);
+        let blended = blend_rates([(rate, Number::from_decimal(7, -9))].into_iter());
+
+        assert_eq!(Ok(rate), blended);
+    }
+
+    #[test]
+    fn blend_zero_weight() {
+        assert_eq!(
+            Err(Error::DivideByZero),
+            blend_rates([(Number::ONE, Number::ZERO)].into_iter())
+        );
+        assert_eq!(Err(Error::DivideByZero), blend_rates(std::iter::empty()));
+    }
+
+    #[test]
+    fn lerp_number() {
+        let a = Number::from(10u64);
+        let b = Number::from(20u64);
+        let quarter = Number::from_bps(2500);
+
+        assert_eq!(Number::from_decimal(125, -1), lerp(a, b, quarter));
+        assert_eq!(Number::from_decimal(175, -1), lerp(b, a, quarter));
+        assert_eq!(Number::from(30u64), lerp(a, b, Number::from(2u64)));
+        assert_eq!(b, lerp_clamped(a, b, Number::from(2u64)));
+    }
+
+    #[test]
+    fn lerp_number_128() {
+        let a = Number128::from_decimal(-10, 0);
+        let b = Number128::from_decimal(10, 0);
+
+        assert_eq!(Number128::ZERO, lerp(a, b, Number128::from_bps(5000)));
+        assert_eq!(
+            Number128::from_decimal(-30, 0),
+            lerp(a, b, Number128::from_decimal(-1, 0))
+        );
+        assert_eq!(a, lerp_clamped(a, b, Number128::from_decimal(-1, 0)));
+    }
+
+    #[test]
+    fn inverse_lerp_values() {
+        let a = Number::from(10u64);
+        let b = Number::from(20u64);
+
+        assert_eq!(
+            Number::from_bps(2500),
+            inverse_lerp(a, b, Number::from_decimal(125, -1))
+        );
+        assert_eq!(
+            Number::from_bps(2500),
+            inverse_lerp(b, a, Number::from_decimal(175, -1))
+        );
+        assert_eq!(Number::ONE, inverse_lerp_clamped(a, b, Number::from(50u64)));
+        assert_eq!(Number::ZERO, inverse_lerp_clamped(a, b, Number::ZERO));
+    }
+
+    #[test]
+    fn remap_ranges() {
+        let utilization = (Number128::from_bps(8000), Number128::ONE);
+        let rate = (Number128::from_bps(1000), Number128::from_bps(5000));
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Number128::from_bps(3000),
+            remap(utilization, rate, Number128::from_bps(9000))
+        );
+        assert_eq!(
+            Number128::from_bps(1000),
+            remap_clamped(utilization, rate, Number128::from_bps(5000))
+        );
+        assert_eq!(
+            Number128::from_bps(5000),
+            remap_clamped(utilization, rate, Number128::from_bps(15000))
+        );
+    }
+
+    #[test]
+    fn step_function() {
+        let steps = StepFunction::new(vec![
+            (Number::from(10u64), Number::from_bps(100)),
+            (Number::from(100u64), Number::from_bps(50)),
+            (Number::from(1000u64), Number::from_bps(10)),
+        ])
+        .unwrap();
+
+        assert_eq!(Number::from_bps(100), steps.evaluate(Number::ZERO));
+        assert_eq!(Number::from_bps(100), steps.evaluate(Number::from(10u64)));
+        assert_eq!(Number::from_bps(50), steps.evaluate(Number::from(999u64)));
+        assert_eq!(Number::from_bps(10), steps.evaluate(Number::MAX));
+    }
+
+    #[test]
+    fn step_function_invalid() {
+        assert!(StepFunction::<Number128>::new(vec![]).is_err());
+        assert!(StepFunction::new(vec![
+            (Number128::ONE, Number128::ZERO),
+            (Number128::ONE, Number128::ONE),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn solve_increasing() {
+        let tol = Number::from_decimal(1, -12);
+        let sqrt_2 = solve_monotonic(
+            |x| x * x,
+            Number::from(2u64),
+            Number::ZERO,
+            Number::from(2u64),
+            tol,
+        )
+        .unwrap();
+
+        let exact = Number::from_decimal(1_414_213_562_373_095u64, -15);
+        assert!(sqrt_2 <= exact && exact - sqrt_2 <= tol);
+    }
+
+    #[test]
+    fn solve_utilization() {
+        // the borrow amount that brings a pool with 1000 deposited to 80%
+        // utilization
+        let deposits = Number::from(1000u64);
+        let borrows = solve_monotonic(
+            |borrows| borrows / deposits,
+            Number::from_bps(8000),
+            Number::ZERO,
+            deposits,
+            Number::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(Number::from_bps(8000), borrows / deposits);
+        assert!(borrows - Number::from(800u64) < Number::from_decimal(1, -12));
+    }
+
+    #[test]
+    fn solve_decreasing() {
+        let ten = Number::from(10u64);
+        let x = solve_monotonic(
+            |x| ten - x * Number::from(3u64),
+            Number::from(4u64),
+            Number::ZERO,
+            Number::from(3u64),
+            Number::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(Number::from(2u64), x);
+    }
+
+    #[test]
+    fn solve_invalid() {
+        let f = |x: Number| x;
+        let one = Number::ONE;
+        let two = Number::from(2u64);
+
+        assert!(solve_monotonic(f, two, Number::ZERO, one, Number::ZERO).is_err());
+        assert!(solve_monotonic(f, one, two, Number::ZERO, Number::ZERO).is_err());
+        assert_eq!(Ok(one), solve_monotonic(f, one, one, one, Number::ZERO));
+    }
+
+    #[test]
+    fn decay_halves() {
+        let initial = Number::from(1_000u64);
+
+        assert_eq!(initial, decay(initial, 3_600, 0));
+        assert_eq!(Number::from(500u64), decay(initial, 3_600, 3_600));
+        assert_eq!(Number::from(125u64), decay(initial, 3_600, 10_800));
+        assert_eq!(Number::ZERO, decay(initial, 1, 192));
+        assert_eq!(Number::ZERO, decay(initial, 0, 1));
+        assert_eq!(
+            Number::from_decimal(707_106_781_186_548u64, -12),
+            decay(initial, 2, 1)
+        );
+    }
+
+    #[test]
+    fn decay_matches_f64() {
+        let initial = Number::from(1_000_000u64);
+        let half_life = 86_400 * 30;
+
+        for elapsed in (0..half_life * 3).step_by(7_919) {
+            let expected = 1_000_000f64 * 2f64.powf(-(elapsed as f64) / half_life as f64);
+            let actual = decay(initial, half_life, elapsed).as_f64();
+
+            assert!((expected - actual).abs() / expected < 1e-12);
+        }
+    }
+
+    #[test]
+    fn decay_is_monotonic() {
+        let initial = Number::from_decimal(123_456_789u64, -3);
+
+        for half_life in [1, 7, 3_600] {
+            let mut previous = initial;
+            for elapsed in 0..half_life * 4 {
+                let decayed = decay(initial, half_life, elapsed);
+                assert!(decayed <= previous);
+                previous = decayed;
+            }
+        }
+
+        let mut previous = initial;
+        for elapsed in (0..86_400 * 365).step_by(997) {
+            let decayed = decay(initial, 86_400 * 90, elapsed);
+            assert!(decayed <= previous);
+            previous = decayed;
+        }
+    }
 }
@@ -0,0 +1,153 @@
+//! Funding payments for perpetual futures.
+//!
+//! Positions are signed: longs are positive and shorts negative. A
+//! positive funding rate means longs pay shorts, and a negative rate means
+//! shorts pay longs, so a payment is positive when the position pays and
+//! negative when it receives.
+//!
+//! Payments are rounded towards positive infinity, so that a position never
+//! pays less or receives more than it's owed, and the sum of the payments
+//! from both sides can't leave the exchange short.
+
+use crate::{Error, Number128, Rounding};
+
+/// Limits applied to funding rates
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FundingConfig {
+    /// The largest funding rate per second, in either direction
+    pub max_rate: Number128,
+}
+
+impl FundingConfig {
+    /// Limit a rate to `[-max_rate, max_rate]`
+    pub fn clamp_rate(&self, rate: Number128) -> Result<Number128, Error> {
+        if self.max_rate < Number128::ZERO {
+            return Err(Error::InvalidInput("negative max funding rate"));
+        }
+
+        Ok(rate.clamp(-self.max_rate, self.max_rate))
+    }
+}
+
+/// The funding paid by a position over `elapsed` seconds at a rate per
+/// second, after clamping the rate to the configured limits
+///
+/// `position_size` is the signed notional value of the position.
+pub fn funding_payment(
+    position_size: Number128,
+    rate: Number128,
+    elapsed: u64,
+    config: &FundingConfig,
+) -> Result<Number128, Error> {
+    let rate = config.clamp_rate(rate)?;
+
+    // the rate times a whole number of seconds is exact, so the payment is
+    // only rounded once
+    let total_rate = rate
+        .to_i128()
+        .checked_mul(elapsed as i128)
+        .ok_or(Error::OutOfRange)?;
+
+    // rounding down truncates towards zero, which is towards positive
+    // infinity for a payment received
+    let receives = (position_size < Number128::ZERO) != (rate < Number128::ZERO);
+    let rounding = match receives {
+        true => Rounding::Down,
+        false => Rounding::Up,
+    };
+
+    position_size
+        .checked_mul_div_rounded(&Number128::from_i128(total_rate), &Number128::ONE, rounding)
+        .ok_or(Error::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: FundingConfig = FundingConfig {
+        max_rate: number128!(~0.0001),
+    };
+
+    #[test]
+    fn longs_and_shorts_pay_each_other() {
+        let size = Number128::from_decimal(10_000, 0);
+        let rate = Number128::from_decimal(1, -6);
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(number128!(0.6)),
+            funding_payment(size, rate, 60, &CONFIG)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(number128!(-0.6)),
+            funding_payment(-size, rate, 60, &CONFIG)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(number128!(-0.6)),
+            funding_payment(size, -rate, 60, &CONFIG)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(number128!(0.6)),
+            funding_payment(-size, -rate, 60, &CONFIG)
+        );
+        assert_eq!(Ok(Number128::ZERO), funding_payment(size, rate, 0, &CONFIG));
+    }
+
+    #[test]
+    fn rates_are_clamped() {
+        let size = Number128::from_decimal(100, 0);
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(number128!(1)),
+            funding_payment(size, Number128::ONE, 100, &CONFIG)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(number128!(1)),
+            funding_payment(-size, -Number128::ONE, 100, &CONFIG)
+        );
+        assert_eq!(Ok(-CONFIG.max_rate), CONFIG.clamp_rate(Number128::MIN));
+
+        let negative = FundingConfig {
+            max_rate: number128!(-1),
+        };
+        assert!(funding_payment(size, Number128::ONE, 1, &negative).is_err());
+    }
+
+    #[test]
+    fn rounds_against_the_position() {
+        let size = Number128::from_decimal(3, -10);
+        let rate = Number128::from_decimal(5, -5);
+
+        // the payment of 1.5e-14 is less than the smallest unit
+        assert_eq!(
+            Ok(Number128::from_i128(1)),
+            funding_payment(size, rate, 1, &CONFIG)
+        );
+        assert_eq!(
+            Ok(Number128::ZERO),
+            funding_payment(-size, rate, 1, &CONFIG)
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let config = FundingConfig {
+            max_rate: Number128::MAX,
+        };
+
+        assert_eq!(
+            Err(Error::OutOfRange),
+            funding_payment(Number128::ONE, Number128::MAX, 2, &config)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            funding_payment(Number128::MAX, Number128::ONE, 2, &config)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Sanity checks on oracle prices, e.g. rejecting an update that moves the
+//! price too far, or a price that is too uncertain to be used.
+//!
+//! Comparisons are made exactly on the underlying integers, so a price
+//! exactly at a limit is always accepted. With the `binary-number128`
+//! feature, a limit like 0.02 has no exact representation and is rounded
+//! when it is converted, so the limit is the stored value rather than the
+//! decimal one.
+
+use crate::bigint::U256;
+use crate::{Error, Number128};
+
+const BPS_PER_UNIT: u16 = 10_000;
+
+/// Whether `new` differs from `old` by more than `max_bps` of `old`
+pub fn max_deviation_exceeded(old: Number128, new: Number128, max_bps: u16) -> Result<bool, Error> {
+    let old = positive(old)?;
+    let deviation = old.to_i128().abs_diff(new.to_i128());
+
+    Ok(U256::from(deviation) * U256::from(BPS_PER_UNIT)
+        > U256::from(old.to_i128() as u128) * U256::from(max_bps))
+}
+
+/// Whether the confidence of a price is at most `max_ratio` of the price,
+/// e.g. 0.02 to reject prices that are uncertain by more than 2%
+pub fn within_confidence(
+    price: Number128,
+    confidence: Number128,
+    max_ratio: Number128,
+) -> Result<bool, Error> {
+    let price = positive(price)?;
+    if confidence < Number128::ZERO {
+        return Err(Error::InvalidInput("confidence is negative"));
+    }
+    if max_ratio < Number128::ZERO {
+        return Err(Error::InvalidInput("maximum ratio is negative"));
+    }
+
+    let raw = |n: Number128| U256::from(n.to_i128() as u128);
+
+    Ok(raw(confidence) * raw(Number128::ONE) <= raw(max_ratio) * raw(price))
+}
+
+/// Limit the change from `old` to `new` to at most `max_bps_per_update` of
+/// `old`, so that a single update can only move a price gradually
+///
+/// The allowed change is rounded down, so the result never moves further
+/// than the limit.
+pub fn clamp_change(
+    old: Number128,
+    new: Number128,
+    max_bps_per_update: u16,
+) -> Result<Number128, Error> {
+    let old = positive(old)?;
+    let max_change = old
+        .checked_mul_div(
+            &Number128::from_i128(max_bps_per_update as i128),
+            &Number128::from_i128(BPS_PER_UNIT as i128),
+        )
+        .ok_or(Error::OutOfRange)?;
+
+    // the limits can only overflow beyond the range of `new`
+    let lo = old.checked_sub(&max_change).unwrap_or(Number128::MIN);
+    let hi = old.checked_add(&max_change).unwrap_or(Number128::MAX);
+
+    Ok(new.clamp(lo, hi))
+}
+
+fn positive(price: Number128) -> Result<Number128, Error> {
+    match price > Number128::ZERO {
+        true => Ok(price),
+        false => Err(Error::InvalidInput("price is not positive")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(value: i128, exponent: i32) -> Number128 {
+        Number128::from_decimal(value, exponent)
+    }
+
+    #[test]
+    fn deviation() {
+        assert_eq!(Ok(false), max_deviation_exceeded(n(100, 0), n(105, 0), 500));
+        assert_eq!(Ok(false), max_deviation_exceeded(n(100, 0), n(95, 0), 500));
+        assert_eq!(Ok(true), max_deviation_exceeded(n(100, 0), n(105, 0), 499));
+        assert_eq!(
+            Ok(true),
+            max_deviation_exceeded(n(100, 0), n(9_499_999, -5), 500)
+        );
+        assert_eq!(Ok(true), max_deviation_exceeded(n(1, 0), Number128::MIN, 0));
+        assert_eq!(
+            Ok(false),
+            max_deviation_exceeded(Number128::MAX, Number128::MAX, 0)
+        );
+    }
+
+    #[test]
+    fn confidence() {
+        let max_ratio = n(2, -2);
+
+        // exactly at a limit that every scaling can represent
+        assert_eq!(
+            Ok(true),
+            within_confidence(n(64, 0), n(1, 0), n(15_625, -6))
+        );
+        assert_eq!(
+            Ok(false),
+            within_confidence(n(64, 0), n(1, 0), n(15_624, -6))
+        );
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(true), within_confidence(n(50, 0), n(1, 0), max_ratio));
+        assert_eq!(
+            Ok(false),
+            within_confidence(n(50, 0), n(101, -2), max_ratio)
+        );
+        assert_eq!(
+            Ok(true),
+            within_confidence(n(50, 0), Number128::ZERO, Number128::ZERO)
+        );
+        assert_eq!(
+            Ok(true),
+            within_confidence(Number128::MAX, Number128::MAX, Number128::ONE)
+        );
+        assert!(within_confidence(n(50, 0), n(-1, 0), max_ratio).is_err());
+        assert!(within_confidence(n(50, 0), n(1, 0), n(-1, 0)).is_err());
+    }
+
+    #[test]
+    fn clamping() {
+        assert_eq!(Ok(n(105, 0)), clamp_change(n(100, 0), n(120, 0), 500));
+        assert_eq!(Ok(n(95, 0)), clamp_change(n(100, 0), n(80, 0), 500));
+        assert_eq!(Ok(n(102, 0)), clamp_change(n(100, 0), n(102, 0), 500));
+        assert_eq!(Ok(n(100, 0)), clamp_change(n(100, 0), n(120, 0), 0));
+
+        // the limit of 0.00000000015 rounds down to 0.0000000001
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(n(3_001, -10)), clamp_change(n(3, -7), n(2, 0), 5));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(n(2_999, -10)), clamp_change(n(3, -7), n(-2, 0), 5));
+        assert_eq!(
+            Ok(Number128::MAX),
+            clamp_change(Number128::MAX, Number128::MAX, 10_000)
+        );
+    }
+
+    #[test]
+    fn non_positive_prices() {
+        assert!(max_deviation_exceeded(Number128::ZERO, n(1, 0), 100).is_err());
+        assert!(within_confidence(n(-1, 0), n(1, 0), n(1, 0)).is_err());
+        assert!(clamp_change(n(-1, 0), n(1, 0), 100).is_err());
+    }
+}
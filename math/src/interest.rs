@@ -0,0 +1,384 @@
+//! Helpers for converting annual rates to per-period rates, and
+//! compounding them over elapsed time.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::number::*;
+
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Convert an annual rate into a rate per slot, returning an error if
+/// there are no slots per year
+pub fn per_slot_rate(apr: Number, slots_per_year: u64) -> Result<Number, Error> {
+    RatePeriod::Slot { slots_per_year }.rate(apr)
+}
+
+/// Convert an annual rate into a rate per second
+pub fn per_second_rate(apr: Number) -> Number {
+    apr / SECONDS_PER_YEAR
+}
+
+/// The length of time that a per-period rate applies to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RatePeriod {
+    Second,
+    Slot { slots_per_year: u64 },
+}
+
+impl RatePeriod {
+    /// The number of these periods in a year
+    pub fn per_year(&self) -> u64 {
+        match self {
+            RatePeriod::Second => SECONDS_PER_YEAR,
+            RatePeriod::Slot { slots_per_year } => *slots_per_year,
+        }
+    }
+
+    /// Convert an annual rate into a rate per one of these periods
+    pub fn rate(&self, apr: Number) -> Result<Number, Error> {
+        match self.per_year() {
+            0 => Err(Error::DivideByZero),
+            per_year => Ok(apr / per_year),
+        }
+    }
+
+    /// Convert a number of these periods into the equivalent number of
+    /// another period, rounded down.
+    ///
+    /// Returns `None` if there are no periods per year to convert from, or
+    /// the result does not fit in a `u64`.
+    pub fn convert(&self, count: u64, to: RatePeriod) -> Option<u64> {
+        let product = count as u128 * to.per_year() as u128;
+
+        product
+            .checked_div(self.per_year() as u128)
+            .and_then(|converted| u64::try_from(converted).ok())
+    }
+
+    /// Compound a balance at an annual rate over some number of these
+    /// periods, returning an error if it overflows.
+    ///
+    /// Unlike the free `accrue`, this takes the annual rate and converts it
+    /// to a rate per period first.
+    pub fn accrue(&self, balance: Number, apr: Number, elapsed: u64) -> Result<Number, Error> {
+        accrue(balance, self.rate(apr)?, elapsed)
+    }
+}
+
+/// Compound a balance at the given per-period rate over some number
+/// of elapsed periods.
+///
+/// The rate and elapsed time must be in the same unit, e.g. a rate from
+/// `per_second_rate` with the elapsed time in seconds. Returns an error if
+/// the result overflows.
+pub fn accrue(balance: Number, rate: Number, elapsed: u64) -> Result<Number, Error> {
+    Number::ONE
+        .checked_add(&rate)
+        .and_then(|base| checked_compound_pow(base, elapsed))
+        .and_then(|growth| balance.checked_mul(&growth))
+        .ok_or(Error::Overflow(balance))
+}
+
+/// Accrue simple interest on a balance at a rate per second, limiting the
+/// result to `cap`.
+///
+/// A result that would overflow saturates at the cap too, so a market that
+/// goes a long time without an update can still be brought up to date. The
+/// cap only limits growth: a balance already above it is returned unchanged.
+pub fn accrue_linear_capped(
+    balance: Number,
+    rate_per_sec: Number,
+    elapsed: u64,
+    cap: Number,
+) -> Number {
+    if balance >= cap {
+        return balance;
+    }
+
+    // the rate times a whole number of seconds is exact, so the result is
+    // only rounded once
+    rate_per_sec
+        .checked_mul(&Number::from(elapsed))
+        .and_then(|rate| balance.checked_mul(&rate))
+        .and_then(|interest| balance.checked_add(&interest))
+        .map_or(cap, |accrued| accrued.min(cap))
+}
+
+/// Raise one plus a per-period rate to the number of elapsed periods,
+/// returning `None` if any step overflows.
+///
+/// This takes `O(log n)` multiplications by repeated squaring, instead of
+/// multiplying once per period.
+pub fn checked_compound_pow(base_one_plus_rate: Number, exp: u64) -> Option<Number> {
+    base_one_plus_rate.checked_powi(exp)
+}
+
+/// A cumulative interest index, which starts at one and grows by the
+/// compounded interest rate over time.
+///
+/// Snapshot the index when a position is opened, and the interest owed
+/// since then is the ratio of the current index to the snapshot. The
+/// index never decreases, so that ratio is always at least one.
+#[derive(Pod, Zeroable, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[repr(transparent)]
+pub struct CumulativeIndex(Number);
+
+impl CumulativeIndex {
+    pub const ONE: Self = Self(Number::ONE);
+
+    pub fn value(&self) -> Number {
+        self.0
+    }
+
+    /// Compound the index at the given per-period rate over some number
+    /// of elapsed periods.
+    ///
+    /// The index is left unchanged if the result would overflow.
+    pub fn accrue(&mut self, rate: Number, elapsed: u64) -> Result<(), Error> {
+        let next = Number::ONE
+            .checked_add(&rate)
+            .and_then(|base| checked_compound_pow(base, elapsed))
+            .and_then(|growth| self.0.checked_mul(&growth))
+            .ok_or(Error::Overflow(self.0))?;
+
+        // multiplying by a factor of at least one rounds down to no less
+        // than the original value, but report a broken invariant as an
+        // error rather than aborting the program
+        if next < self.0 {
+            return Err(Error::InvalidInput("interest index cannot decrease"));
+        }
+        self.0 = next;
+
+        Ok(())
+    }
+
+    /// The growth of the index since an earlier snapshot of it, rounded
+    /// down.
+    ///
+    /// Multiply a balance from the time of the snapshot by this ratio to
+    /// get the balance with interest.
+    pub fn ratio_since(&self, snapshot: &CumulativeIndex) -> Result<Number, Error> {
+        if snapshot.0 == Number::ZERO {
+            return Err(Error::DivideByZero);
+        }
+        if snapshot > self {
+            return Err(Error::InvalidInput("snapshot is later than the index"));
+        }
+
+        self.0
+            .checked_div(&snapshot.0)
+            .ok_or(Error::Overflow(self.0))
+    }
+}
+
+impl Default for CumulativeIndex {
+    fn default() -> Self {
+        Self::ONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_period_rates() {
+        assert_eq!(
+            Number::from_decimal(1, -3),
+            per_slot_rate(Number::from_decimal(1, 0), 1_000).unwrap()
+        );
+        assert_eq!(Err(Error::DivideByZero), per_slot_rate(Number::ONE, 0));
+        assert_eq!(
+            Number::from(1u64),
+            per_second_rate(Number::from(SECONDS_PER_YEAR))
+        );
+    }
+
+    #[test]
+    fn rate_period_conversion() {
+        let slot = RatePeriod::Slot {
+            slots_per_year: SECONDS_PER_YEAR * 2,
+        };
+
+        assert_eq!(Some(50), slot.convert(100, RatePeriod::Second));
+        assert_eq!(Some(200), RatePeriod::Second.convert(100, slot));
+        assert_eq!(
+            Some(u64::MAX / 2),
+            slot.convert(u64::MAX, RatePeriod::Second)
+        );
+        assert_eq!(None, RatePeriod::Second.convert(u64::MAX, slot));
+
+        let empty = RatePeriod::Slot { slots_per_year: 0 };
+        assert_eq!(None, empty.convert(1, RatePeriod::Second));
+        assert_eq!(Some(0), RatePeriod::Second.convert(1, empty));
+    }
+
+    #[test]
+    fn rate_period_accrual() {
+        let apr = Number::from_bps(500);
+        let slot = RatePeriod::Slot {
+            slots_per_year: 1_000,
+        };
+
+        assert_eq!(per_second_rate(apr), RatePeriod::Second.rate(apr).unwrap());
+        assert_eq!(per_slot_rate(apr, 1_000), slot.rate(apr));
+        assert_eq!(
+            accrue(Number::ONE, per_slot_rate(apr, 1_000).unwrap(), 10),
+            slot.accrue(Number::ONE, apr, 10)
+        );
+
+        let empty = RatePeriod::Slot { slots_per_year: 0 };
+        assert_eq!(Err(Error::DivideByZero), empty.rate(apr));
+        assert_eq!(Err(Error::DivideByZero), empty.accrue(Number::ONE, apr, 1));
+        assert!(slot.accrue(Number::ONE, Number::MAX, 2).is_err());
+
+        // one plus the rate overflows before any compounding
+        let yearly = RatePeriod::Slot { slots_per_year: 1 };
+        assert_eq!(
+            Err(Error::Overflow(Number::ONE)),
+            yearly.accrue(Number::ONE, Number::MAX, 1)
+        );
+    }
+
+    #[test]
+    fn accrue_zero_elapsed() {
+        let balance = Number::from(1_000u64);
+        assert_eq!(Ok(balance), accrue(balance, Number::from_bps(100), 0));
+        assert!(accrue(balance, Number::MAX, 0).is_err());
+    }
+
+    #[test]
+    fn accrue_compounds() {
+        let balance = Number::from(1_000u64);
+        let rate = Number::from_bps(1000);
+
+        assert_eq!(Ok(Number::from(1_100u64)), accrue(balance, rate, 1));
+        assert_eq!(Ok(Number::from(1_331u64)), accrue(balance, rate, 3));
+    }
+
+    #[test]
+    fn accrue_matches_loop() {
+        let balance = Number::from(5_000u64);
+        let rate = per_second_rate(Number::from_bps(500));
+
+        let mut expected = balance;
+        for _ in 0..1_000 {
+            expected *= Number::ONE + rate;
+        }
+
+        let accrued = accrue(balance, rate, 1_000).unwrap();
+        let difference = if accrued > expected {
+            accrued - expected
+        } else {
+            expected - accrued
+        };
+        assert!(difference < Number::from_decimal(1, -9));
+    }
+
+    #[test]
+    fn linear_accrual_saturates_at_cap() {
+        let balance = Number::from(1_000u64);
+        let rate = Number::from_decimal(1, -6);
+        let cap = Number::from(2_000u64);
+
+        assert_eq!(balance, accrue_linear_capped(balance, rate, 0, cap));
+        assert_eq!(
+            Number::from(1_100u64),
+            accrue_linear_capped(balance, rate, 100_000, cap)
+        );
+        assert_eq!(cap, accrue_linear_capped(balance, rate, 2_000_000, cap));
+
+        // overflowing intermediate results saturate instead of panicking
+        assert_eq!(
+            Number::MAX,
+            accrue_linear_capped(balance, Number::MAX, u64::MAX, Number::MAX)
+        );
+        assert_eq!(
+            cap,
+            accrue_linear_capped(balance, Number::ONE, u64::MAX, cap)
+        );
+
+        let above_cap = Number::from(3_000u64);
+        assert_eq!(
+            above_cap,
+            accrue_linear_capped(above_cap, rate, u64::MAX, cap)
+        );
+    }
+
+    #[test]
+    fn compound_pow_by_squaring() {
+        let base = Number::ONE + Number::from_bps(1000);
+
+        assert_eq!(Some(Number::ONE), checked_compound_pow(base, 0));
+        assert_eq!(Some(base), checked_compound_pow(base, 1));
+        assert_eq!(
+            Some(Number::from_decimal(14641, -4)),
+            checked_compound_pow(base, 4)
+        );
+        assert_eq!(
+            Some(Number::from(1024u64)),
+            checked_compound_pow(Number::from(2u64), 10)
+        );
+    }
+
+    #[test]
+    fn compound_pow_long_periods() {
+        let base = Number::ONE + per_second_rate(Number::from_bps(500));
+
+        // ten years of per-second compounding, close to e^0.5 = 1.6487212...
+        let growth = checked_compound_pow(base, SECONDS_PER_YEAR * 10).unwrap();
+        assert!(growth > Number::from_decimal(1_648_720, -6));
+        assert!(growth < Number::from_decimal(1_648_722, -6));
+
+        assert_eq!(None, checked_compound_pow(Number::from(2u64), 1_000));
+        assert_eq!(
+            Some(Number::ONE),
+            checked_compound_pow(Number::ONE, u64::MAX)
+        );
+    }
+
+    #[test]
+    fn index_matches_accrue() {
+        let rate = per_second_rate(Number::from_bps(500));
+        let mut index = CumulativeIndex::default();
+
+        index.accrue(rate, 1_000).unwrap();
+        assert_eq!(Ok(index.value()), accrue(Number::ONE, rate, 1_000));
+    }
+
+    #[test]
+    fn index_ratio_since_snapshot() {
+        let rate = Number::from_bps(1000);
+        let mut index = CumulativeIndex::ONE;
+        index.accrue(rate, 1).unwrap();
+
+        let snapshot = index;
+        index.accrue(rate, 2).unwrap();
+        index.accrue(Number::ZERO, 100).unwrap();
+
+        assert_eq!(
+            Number::from_decimal(121, -2),
+            index.ratio_since(&snapshot).unwrap()
+        );
+        assert_eq!(Number::ONE, index.ratio_since(&index).unwrap());
+        assert!(snapshot.ratio_since(&index).is_err());
+        assert_eq!(
+            Err(Error::DivideByZero),
+            index.ratio_since(&CumulativeIndex::zeroed())
+        );
+    }
+
+    #[test]
+    fn index_overflow_is_unchanged() {
+        let mut index = CumulativeIndex::ONE;
+
+        assert!(index.accrue(Number::ONE, 1_000).is_err());
+        assert_eq!(CumulativeIndex::ONE, index);
+
+        assert_eq!(
+            Err(Error::Overflow(Number::ONE)),
+            index.accrue(Number::MAX, 1)
+        );
+        assert_eq!(CumulativeIndex::ONE, index);
+    }
+}
@@ -0,0 +1,261 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::{Error, Number128};
+
+/// A closed range of values `[lo, hi]`, e.g. an oracle price and its
+/// confidence interval.
+///
+/// Arithmetic on intervals produces an interval containing every possible
+/// result, so values can be propagated through a formula without losing
+/// track of the uncertainty. Results are rounded outward: the lower bound
+/// down and the upper bound up. Like the operators on `Number128`, the
+/// operators panic on overflow, and division also panics if the divisor
+/// contains zero, which `checked_div` reports as an error instead.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Interval<T> {
+    lo: T,
+    hi: T,
+}
+
+impl<T: PartialOrd + Copy> Interval<T> {
+    /// Create an interval, returning an error if `lo` is greater than `hi`
+    pub fn new(lo: T, hi: T) -> Result<Self, Error> {
+        match lo <= hi {
+            true => Ok(Self { lo, hi }),
+            false => Err(Error::InvalidInput("interval bounds are reversed")),
+        }
+    }
+
+    /// An interval containing a single value
+    pub fn point(value: T) -> Self {
+        Self {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    pub fn lo(&self) -> T {
+        self.lo
+    }
+
+    pub fn hi(&self) -> T {
+        self.hi
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+}
+
+impl Interval<Number128> {
+    /// The interval `[value - confidence, value + confidence]`
+    pub fn from_confidence(value: Number128, confidence: Number128) -> Result<Self, Error> {
+        if confidence < Number128::ZERO {
+            return Err(Error::InvalidInput("confidence is negative"));
+        }
+
+        Self::new(
+            value.checked_sub(&confidence).ok_or(Error::OutOfRange)?,
+            value.checked_add(&confidence).ok_or(Error::OutOfRange)?,
+        )
+    }
+
+    /// The distance between the bounds
+    pub fn width(&self) -> Number128 {
+        self.hi - self.lo
+    }
+
+    /// The value halfway between the bounds
+    pub fn midpoint(&self) -> Number128 {
+        self.lo.midpoint(self.hi)
+    }
+
+    /// Divide, returning an error if the divisor contains zero or the
+    /// result overflows
+    pub fn checked_div(&self, rhs: &Self) -> Result<Self, Error> {
+        if rhs.contains(Number128::ZERO) {
+            return Err(Error::DivideByZero);
+        }
+
+        outward(
+            [
+                (self.lo, rhs.lo),
+                (self.lo, rhs.hi),
+                (self.hi, rhs.lo),
+                (self.hi, rhs.hi),
+            ],
+            Number128::checked_div_floor,
+            Number128::checked_div_ceil,
+        )
+        .ok_or(Error::OutOfRange)
+    }
+}
+
+/// The smallest interval containing the results of an operation on every
+/// pair of bounds, rounded outward, or `None` if any result overflows
+fn outward(
+    pairs: [(Number128, Number128); 4],
+    floor: fn(Number128, Number128) -> Option<Number128>,
+    ceil: fn(Number128, Number128) -> Option<Number128>,
+) -> Option<Interval<Number128>> {
+    let (mut lo, mut hi) = (Number128::MAX, Number128::MIN);
+    for (a, b) in pairs {
+        lo = lo.min(floor(a, b)?);
+        hi = hi.max(ceil(a, b)?);
+    }
+
+    Some(Interval { lo, hi })
+}
+
+impl From<Number128> for Interval<Number128> {
+    fn from(value: Number128) -> Self {
+        Self::point(value)
+    }
+}
+
+impl Add<Interval<Number128>> for Interval<Number128> {
+    type Output = Interval<Number128>;
+
+    fn add(self, rhs: Interval<Number128>) -> Self::Output {
+        Self {
+            lo: self.lo + rhs.lo,
+            hi: self.hi + rhs.hi,
+        }
+    }
+}
+
+impl Sub<Interval<Number128>> for Interval<Number128> {
+    type Output = Interval<Number128>;
+
+    fn sub(self, rhs: Interval<Number128>) -> Self::Output {
+        Self {
+            lo: self.lo - rhs.hi,
+            hi: self.hi - rhs.lo,
+        }
+    }
+}
+
+impl Mul<Interval<Number128>> for Interval<Number128> {
+    type Output = Interval<Number128>;
+
+    fn mul(self, rhs: Interval<Number128>) -> Self::Output {
+        outward(
+            [
+                (self.lo, rhs.lo),
+                (self.lo, rhs.hi),
+                (self.hi, rhs.lo),
+                (self.hi, rhs.hi),
+            ],
+            Number128::checked_mul_floor,
+            Number128::checked_mul_ceil,
+        )
+        .unwrap_or_else(|| panic!("cannot multiply due to overflow"))
+    }
+}
+
+impl Div<Interval<Number128>> for Interval<Number128> {
+    type Output = Interval<Number128>;
+
+    fn div(self, rhs: Interval<Number128>) -> Self::Output {
+        self.checked_div(&rhs).unwrap()
+    }
+}
+
+impl Neg for Interval<Number128> {
+    type Output = Interval<Number128>;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(value: i128, exponent: i32) -> Number128 {
+        Number128::from_decimal(value, exponent)
+    }
+
+    fn interval(lo: i128, hi: i128) -> Interval<Number128> {
+        Interval::new(n(lo, 0), n(hi, 0)).unwrap()
+    }
+
+    #[test]
+    fn construction() {
+        let price = Interval::from_confidence(n(100, 0), n(2, 0)).unwrap();
+
+        assert_eq!(interval(98, 102), price);
+        assert_eq!(n(4, 0), price.width());
+        assert_eq!(n(100, 0), price.midpoint());
+        assert!(price.contains(n(98, 0)));
+        assert!(!price.contains(n(103, 0)));
+        assert!(Interval::new(n(2, 0), n(1, 0)).is_err());
+        assert_eq!(interval(3, 3), Interval::from(n(3, 0)));
+        assert!(Interval::from_confidence(n(1, 0), n(-1, 0)).is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = interval(-2, 3);
+        let b = interval(4, 5);
+
+        assert_eq!(interval(2, 8), a + b);
+        assert_eq!(interval(-7, -1), a - b);
+        assert_eq!(interval(-10, 15), a * b);
+        assert_eq!(interval(-15, 10), a * -b);
+        assert_eq!(interval(-3, 2), -a);
+        assert_eq!(Interval::new(n(-5, -1), n(75, -2)).unwrap(), a / b);
+    }
+
+    #[test]
+    fn rounds_outward() {
+        let third = interval(1, 1) / interval(3, 3);
+        assert!(third.lo() < third.hi());
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(n(3_333_333_333, -10), third.lo());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(n(3_333_333_334, -10), third.hi());
+        assert!(third.contains(third.midpoint()));
+
+        let product = third * interval(3, 3);
+        assert!(product.contains(Number128::ONE));
+
+        let negative = -interval(1, 1) / interval(3, 3);
+        assert!(negative.lo() < negative.hi());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(n(-3_333_333_334, -10), negative.lo());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(n(-3_333_333_333, -10), negative.hi());
+    }
+
+    #[test]
+    fn divide_by_zero() {
+        assert_eq!(
+            Err(Error::DivideByZero),
+            interval(1, 2).checked_div(&interval(-1, 1))
+        );
+        assert_eq!(
+            Err(Error::DivideByZero),
+            interval(1, 2).checked_div(&interval(0, 1))
+        );
+    }
+
+    #[test]
+    fn division_overflow() {
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Interval::point(Number128::MAX).checked_div(&Interval::point(n(1, -10)))
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Interval::new(n(1, 0), Number128::MAX)
+                .unwrap()
+                .checked_div(&Interval::new(n(1, -10), n(1, 0)).unwrap())
+        );
+    }
+}
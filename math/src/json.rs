@@ -0,0 +1,257 @@
+//! Serde representations of the number types for JSON APIs, to use with
+//! `#[serde(with = "jet_proto_math::json::number128")]`.
+//!
+//! JavaScript numbers can't hold every value exactly, so values are
+//! serialized with a string mantissa, e.g. 12.3456 as
+//! `{ "mantissa": "123456", "exponent": -4 }`. Deserializing also accepts
+//! a plain decimal string, e.g. `"12.3456"`, and rejects values that can't
+//! be represented exactly. The `schema` feature describes this form as
+//! `Number128Json`.
+
+/// `Number128` as `{ "mantissa": "...", "exponent": n }`
+pub mod number128 {
+    use std::fmt;
+
+    use serde::de::{self, MapAccess, Visitor};
+    use serde::ser::SerializeStruct;
+    use serde::{Deserializer, Serializer};
+
+    use crate::bigint::U256;
+    use crate::{Error, Number128};
+
+    /// The number of decimal places needed to write any `Number128`
+    /// exactly, i.e. the smallest power of ten divisible by `ONE`
+    #[cfg(not(feature = "binary-number128"))]
+    const EXACT_DECIMALS: u32 = 10;
+    #[cfg(feature = "binary-number128")]
+    const EXACT_DECIMALS: u32 = 34;
+
+    /// The largest power of ten that fits in a `U256`
+    const MAX_POWER_OF_TEN: i64 = 77;
+
+    pub fn serialize<S: Serializer>(n: &Number128, serializer: S) -> Result<S::Ok, S::Error> {
+        let (mantissa, exponent) = to_parts(*n);
+
+        let mut s = serializer.serialize_struct("Number128", 2)?;
+        s.serialize_field("mantissa", &mantissa)?;
+        s.serialize_field("exponent", &exponent)?;
+        s.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Number128, D::Error> {
+        deserializer.deserialize_any(Number128Visitor)
+    }
+
+    struct Number128Visitor;
+
+    impl<'de> Visitor<'de> for Number128Visitor {
+        type Value = Number128;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a decimal string, or an object with a mantissa and exponent")
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Number128, E> {
+            s.parse().map_err(E::custom)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Number128, A::Error> {
+            let mut mantissa: Option<String> = None;
+            let mut exponent: Option<i32> = None;
+
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "mantissa" if mantissa.is_some() => {
+                        return Err(de::Error::duplicate_field("mantissa"))
+                    }
+                    "exponent" if exponent.is_some() => {
+                        return Err(de::Error::duplicate_field("exponent"))
+                    }
+                    "mantissa" => mantissa = Some(map.next_value()?),
+                    "exponent" => exponent = Some(map.next_value()?),
+                    _ => return Err(de::Error::unknown_field(&key, &["mantissa", "exponent"])),
+                }
+            }
+
+            let mantissa = mantissa.ok_or_else(|| de::Error::missing_field("mantissa"))?;
+            let exponent = exponent.ok_or_else(|| de::Error::missing_field("exponent"))?;
+
+            from_parts(&mantissa, exponent).map_err(de::Error::custom)
+        }
+    }
+
+    fn pow10(exponent: u32) -> U256 {
+        U256::from(10).pow(U256::from(exponent))
+    }
+
+    /// The shortest exact mantissa and exponent of a value
+    fn to_parts(n: Number128) -> (String, i32) {
+        let raw = n.to_i128();
+        if raw == 0 {
+            return ("0".to_string(), 0);
+        }
+
+        // exact, since `ONE` divides 10^EXACT_DECIMALS
+        let one = U256::from(Number128::ONE.to_i128() as u128);
+        let mut mantissa = U256::from(raw.unsigned_abs()) * pow10(EXACT_DECIMALS) / one;
+        let mut exponent = -(EXACT_DECIMALS as i32);
+
+        let ten = U256::from(10);
+        while (mantissa % ten).is_zero() {
+            mantissa /= ten;
+            exponent += 1;
+        }
+
+        let sign = if raw < 0 { "-" } else { "" };
+        (format!("{sign}{mantissa}"), exponent)
+    }
+
+    fn from_parts(mantissa: &str, exponent: i32) -> Result<Number128, Error> {
+        let (negative, digits) = match mantissa.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, mantissa),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidInput("mantissa is not an integer"));
+        }
+
+        let ten = U256::from(10);
+        let mut value = U256::zero();
+        for digit in digits.bytes() {
+            value = value
+                .checked_mul(ten)
+                .and_then(|v| v.checked_add(U256::from(digit - b'0')))
+                .ok_or(Error::OutOfRange)?;
+        }
+        if value.is_zero() {
+            return Ok(Number128::ZERO);
+        }
+
+        let mut exponent = exponent as i64;
+        while (value % ten).is_zero() {
+            value /= ten;
+            exponent += 1;
+        }
+
+        let one = U256::from(Number128::ONE.to_i128() as u128);
+        let raw = match exponent >= 0 {
+            true if exponent > MAX_POWER_OF_TEN => return Err(Error::OutOfRange),
+            true => value
+                .checked_mul(pow10(exponent as u32))
+                .and_then(|v| v.checked_mul(one))
+                .ok_or(Error::OutOfRange)?,
+            // without trailing zeros, the value needs all of its decimal
+            // places, so there is no exact `Number128` with more of them
+            false if -exponent > EXACT_DECIMALS as i64 => {
+                return Err(Error::InvalidInput("value can't be represented exactly"))
+            }
+            false => {
+                let product = value.checked_mul(one).ok_or(Error::OutOfRange)?;
+                let (raw, remainder) = product.div_mod(pow10(-exponent as u32));
+                if !remainder.is_zero() {
+                    return Err(Error::InvalidInput("value can't be represented exactly"));
+                }
+                raw
+            }
+        };
+
+        if raw.bits() > 128 {
+            return Err(Error::OutOfRange);
+        }
+        let raw = raw.low_u128();
+
+        let raw = match negative {
+            true if raw > i128::MIN.unsigned_abs() => return Err(Error::OutOfRange),
+            true => (raw as i128).wrapping_neg(),
+            false => i128::try_from(raw).map_err(|_| Error::OutOfRange)?,
+        };
+
+        Ok(Number128::from_i128(raw))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn to_json(n: Number128) -> String {
+            let mut buf = Vec::new();
+            serialize(&n, &mut serde_json::Serializer::new(&mut buf)).unwrap();
+            String::from_utf8(buf).unwrap()
+        }
+
+        fn from_json(s: &str) -> Result<Number128, serde_json::Error> {
+            deserialize(&mut serde_json::Deserializer::from_str(s))
+        }
+
+        #[test]
+        fn round_trip() {
+            let numbers = [
+                Number128::ZERO,
+                Number128::ONE,
+                Number128::MIN,
+                Number128::MAX,
+                Number128::from_decimal(-5, -1),
+                Number128::from_i128(1),
+                Number128::from_decimal(123_456, -4),
+            ];
+
+            for n in numbers {
+                assert_eq!(n, from_json(&to_json(n)).unwrap(), "{n}");
+            }
+        }
+
+        #[cfg(not(feature = "binary-number128"))]
+        #[test]
+        fn serializes_shortest_form() {
+            assert_eq!(
+                r#"{"mantissa":"123456","exponent":-4}"#,
+                to_json(Number128::from_decimal(123_456, -4))
+            );
+            assert_eq!(
+                r#"{"mantissa":"-25","exponent":3}"#,
+                to_json(Number128::from_decimal(-25_000, 0))
+            );
+            assert_eq!(r#"{"mantissa":"0","exponent":0}"#, to_json(Number128::ZERO));
+            assert_eq!(
+                r#"{"mantissa":"170141183460469231731687303715884105727","exponent":-10}"#,
+                to_json(Number128::MAX)
+            );
+        }
+
+        #[cfg(not(feature = "binary-number128"))]
+        #[test]
+        fn accepts_decimal_strings() {
+            let n = Number128::from_decimal(-123_456, -4);
+
+            assert_eq!(n, from_json(r#""-12.3456""#).unwrap());
+            assert_eq!(
+                n,
+                from_json(r#"{ "exponent": -8, "mantissa": "-1234560000" }"#).unwrap()
+            );
+            assert_eq!(
+                Number128::ZERO,
+                from_json(r#"{ "mantissa": "000", "exponent": 2147483647 }"#).unwrap()
+            );
+        }
+
+        #[test]
+        fn rejects_invalid_values() {
+            let invalid = [
+                r#"{ "mantissa": "1", "exponent": -35 }"#,
+                r#"{ "mantissa": "1", "exponent": 40 }"#,
+                r#"{ "mantissa": "1.5", "exponent": 0 }"#,
+                r#"{ "mantissa": "", "exponent": 0 }"#,
+                r#"{ "mantissa": 1, "exponent": 0 }"#,
+                r#"{ "mantissa": "1" }"#,
+                r#"{ "mantissa": "1", "exponent": 0, "scale": 2 }"#,
+                r#"{ "mantissa": "1", "mantissa": "2", "exponent": 0 }"#,
+                r#""1.00000000001""#,
+                r#"1.5"#,
+            ];
+
+            for json in invalid {
+                assert!(from_json(json).is_err(), "{json}");
+            }
+        }
+    }
+}
@@ -1,15 +1,289 @@
+//! Fixed-point math for solana programs
+//!
+//! # Display
+//!
+//! The `Display` (and `Debug`) impls of the number types render the exact
+//! stored value: every significant decimal digit is printed, trailing zeros
+//! are trimmed, and nothing is ever rounded. Values that were truncated on
+//! the way in (e.g. by `from_decimal` with more digits than the type can
+//! store) are printed as stored. The output parses back to the same value
+//! with `FromStr`; with the `binary-number128` feature, use
+//! `Number128::to_exact_string` for that guarantee. These strings are the
+//! wire format described by the `schema` module.
+//!
+//! The alternate `Debug` format (`{:#?}`, or `to_debug_string`) also prints
+//! the raw stored integer, e.g. `Number128(1.5; raw=15000000000)`, so that
+//! exact values can be recovered from logs.
+//!
+//! When a fixed number of decimals is wanted, wrap the value in
+//! [`DisplayRounded`], which rounds half away from zero and pads to the
+//! requested number of decimal places.
+
+/// Record an arithmetic operation when the `audit` feature is enabled. The
+/// result is passed as an `Option<&T>`, and the arguments are not evaluated
+/// when the feature is disabled.
+macro_rules! audit {
+    ($op:expr, $lhs:expr, $rhs:expr, $result:expr) => {
+        audit!($op, $lhs, $rhs, $result, rounded: false, saturated: false)
+    };
+    ($op:expr, $lhs:expr, $rhs:expr, $result:expr, rounded: $rounded:expr) => {
+        audit!($op, $lhs, $rhs, $result, rounded: $rounded, saturated: false)
+    };
+    ($op:expr, $lhs:expr, $rhs:expr, $result:expr, saturated: $saturated:expr) => {
+        audit!($op, $lhs, $rhs, $result, rounded: false, saturated: $saturated)
+    };
+    ($op:expr, $lhs:expr, $rhs:expr, $result:expr, rounded: $rounded:expr, saturated: $saturated:expr) => {
+        #[cfg(feature = "audit")]
+        crate::audit::record($op, &$lhs, &$rhs, $result, $rounded, $saturated);
+    };
+}
+
+/// Implement the arithmetic operators for references, forwarding to the
+/// impls on owned values.
+macro_rules! impl_ref_ops {
+    ($t:ty) => {
+        impl_ref_ops!(@op $t, Add add, AddAssign add_assign);
+        impl_ref_ops!(@op $t, Sub sub, SubAssign sub_assign);
+        impl_ref_ops!(@op $t, Mul mul, MulAssign mul_assign);
+        impl_ref_ops!(@op $t, Div div, DivAssign div_assign);
+    };
+    (@op $t:ty, $op:ident $method:ident, $assign:ident $assign_method:ident) => {
+        impl std::ops::$op<&$t> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> $t {
+                std::ops::$op::$method(self, *rhs)
+            }
+        }
+
+        impl std::ops::$op<$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: $t) -> $t {
+                std::ops::$op::$method(*self, rhs)
+            }
+        }
+
+        impl std::ops::$op<&$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> $t {
+                std::ops::$op::$method(*self, *rhs)
+            }
+        }
+
+        impl std::ops::$assign<&$t> for $t {
+            fn $assign_method(&mut self, rhs: &$t) {
+                std::ops::$assign::$assign_method(self, *rhs)
+            }
+        }
+    };
+}
+
+/// Create a `Number` from a decimal literal, parsed at compile time.
+///
+/// A literal with more decimal places than a `Number` can store fails to
+/// compile, unless it is marked with `~` to truncate it. The literal may
+/// also be written as a string.
+///
+/// ```
+/// # use jet_proto_math::{number, Number};
+/// const FEE: Number = number!(0.0025);
+///
+/// assert_eq!(Number::from_decimal(25u64, -4), FEE);
+/// assert_eq!(Number::from_decimal(125u64, -2), number!("1.25"));
+/// assert_eq!(number!(0.333333333333333), number!(~0.3333333333333333333));
+/// ```
+///
+/// ```compile_fail
+/// # use jet_proto_math::number;
+/// let too_precise = number!(0.3333333333333333333);
+/// ```
+#[macro_export]
+macro_rules! number {
+    (~ $value:literal) => {{
+        const VALUE: $crate::Number = $crate::Number::from_decimal_str_lossy(stringify!($value));
+        VALUE
+    }};
+    ($value:literal) => {{
+        const VALUE: $crate::Number = $crate::Number::from_decimal_str(stringify!($value));
+        VALUE
+    }};
+}
+
+/// Create a `Number128` from a decimal literal, parsed at compile time.
+///
+/// A literal that a `Number128` cannot store exactly fails to compile,
+/// unless it is marked with `~` to truncate it. That is a literal with more
+/// than 10 decimal places, or with the `binary-number128` feature, any
+/// fraction that is not a multiple of 2^-34, e.g. 0.05. The literal may
+/// also be written as a string.
+///
+/// ```
+/// # use jet_proto_math::{number128, Number128};
+/// const OFFSET: Number128 = number128!(-0.125);
+///
+/// assert_eq!(Number128::from_decimal(-125, -3), OFFSET);
+/// assert_eq!(number128!(~0.3333333333), number128!(~"0.333333333333"));
+/// ```
+///
+/// ```compile_fail
+/// # use jet_proto_math::number128;
+/// let too_precise = number128!(0.333333333333);
+/// ```
+#[macro_export]
+macro_rules! number128 {
+    (~ $value:literal) => {{
+        const VALUE: $crate::Number128 =
+            $crate::Number128::from_decimal_str_lossy(stringify!($value));
+        VALUE
+    }};
+    ($value:literal) => {{
+        const VALUE: $crate::Number128 = $crate::Number128::from_decimal_str(stringify!($value));
+        VALUE
+    }};
+}
+
+/// Declare `Number128` constants from decimal literals.
+///
+/// Each literal is parsed at compile time with `number128!`, so a literal
+/// that a `Number128` cannot store exactly fails to compile rather than
+/// being silently rounded, unless it is marked with `~` to truncate it.
+///
+/// ```
+/// # use jet_proto_math::{number_consts, Number128};
+/// number_consts! {
+///     /// The maximum utilization rate
+///     pub MAX_UTIL = 0.75,
+///     LIQ_BONUS = 0.0625,
+///     /// Truncated with the `binary-number128` feature
+///     TARGET_UTIL = ~0.95,
+/// }
+///
+/// assert_eq!(Number128::from_decimal(75, -2), MAX_UTIL);
+/// assert_eq!(Number128::from_decimal(95, -2), TARGET_UTIL);
+/// ```
+///
+/// ```compile_fail
+/// # use jet_proto_math::number_consts;
+/// number_consts! { TOO_PRECISE = 0.123456789012 }
+/// ```
+#[macro_export]
+macro_rules! number_consts {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis $name:ident = ~ $value:literal $(, $($rest:tt)*)?) => {
+        $(#[$attr])*
+        $vis const $name: $crate::Number128 = $crate::number128!(~ $value);
+        $($crate::number_consts! { $($rest)* })?
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident = $value:literal $(, $($rest:tt)*)?) => {
+        $(#[$attr])*
+        $vis const $name: $crate::Number128 = $crate::number128!($value);
+        $($crate::number_consts! { $($rest)* })?
+    };
+}
+
+mod accumulator;
+mod decimal_value;
+mod display;
+mod expr;
 mod functions;
+mod interval;
 mod number;
 mod number_128;
+mod pod;
+mod policy;
+mod remainder;
+
+pub mod bigint;
+pub mod bps;
+pub mod conversions;
+pub mod curve;
+pub mod dimension;
+pub mod distribution;
+pub mod fees;
+pub mod funding;
+pub mod guards;
+pub mod interest;
+pub mod liquidation;
+pub mod margin_math;
+pub mod oracle;
+pub mod orderbook;
+pub mod pool_share;
+pub mod q64;
+pub mod quote;
+pub mod rate;
+pub mod rewards;
+pub mod slippage;
+pub mod stable_swap;
+pub mod stats;
+pub mod term;
+
+#[cfg(feature = "archive")]
+mod archive;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg(feature = "decimal")]
+mod decimal;
+
+#[cfg(feature = "serde")]
+pub mod json;
+
+#[cfg(feature = "rayon")]
+pub mod parallel;
+
+#[cfg(feature = "reference")]
+pub mod reference;
+
+#[cfg(feature = "schema")]
+pub mod schema;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
+#[cfg(feature = "token")]
+pub mod token;
 
 #[cfg(feature = "traits")]
 pub mod traits;
 
+#[doc(inline)]
+pub use accumulator::*;
+
+#[doc(inline)]
+pub use bigint::U192;
+
+#[doc(inline)]
+pub use bps::Bps;
+
+#[doc(inline)]
+pub use decimal_value::*;
+
+#[doc(inline)]
+pub use display::*;
+
+#[doc(inline)]
+pub use expr::*;
+
 #[doc(inline)]
 pub use functions::*;
 
+#[doc(inline)]
+pub use interval::*;
+
 #[doc(inline)]
 pub use number::*;
 
 #[doc(inline)]
 pub use number_128::*;
+
+#[doc(inline)]
+pub use pod::*;
+
+#[doc(inline)]
+pub use policy::*;
+
+#[doc(inline)]
+pub use remainder::*;
@@ -0,0 +1,233 @@
+//! Sizing the liquidation of an unhealthy margin account.
+//!
+//! A liquidator repays some of the account's debt, and in exchange seizes
+//! collateral worth the repaid value plus a bonus. Weights are those used
+//! for margin requirements (see `margin_math`): an account is healthy when
+//! its weighted collateral covers its weighted liabilities.
+//!
+//! Rounding is conservative towards the liquidated account:
+//!
+//! * the repayable amount rounds down, except for the amount that restores
+//!   the account's health, which rounds up so that repaying it does restore
+//!   health
+//! * the seized collateral and the bonus round down
+
+use crate::{Error, Number128, Rounding, RoundingPolicy};
+
+const DOWN: RoundingPolicy = RoundingPolicy::new(Rounding::Down);
+const UP: RoundingPolicy = RoundingPolicy::new(Rounding::Up);
+
+/// A token held or owed by the liquidated account
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LiquidationAsset {
+    /// The amount of tokens
+    pub amount: Number128,
+    /// The price of one token
+    pub price: Number128,
+    /// The weight of the token in the account's margin requirements
+    pub weight: Number128,
+}
+
+/// Limits on a single liquidation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LiquidationParams {
+    /// The largest fraction of a debt that can be repaid at once
+    pub close_factor: Number128,
+    /// The extra collateral value awarded to the liquidator, as a fraction
+    /// of the repaid value
+    pub bonus: Number128,
+}
+
+/// The outcome of a liquidation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Liquidation {
+    /// The amount of debt tokens repaid
+    pub repay: Number128,
+    /// The amount of collateral tokens seized, including the bonus
+    pub seized: Number128,
+    /// The part of the seized collateral that is the liquidator's bonus
+    pub bonus: Number128,
+}
+
+/// The most debt that can be repaid in one liquidation
+///
+/// `shortfall` is the account's weighted liabilities minus its weighted
+/// collateral. The repayment is limited by the close factor, by the
+/// collateral available to seize, and by the amount that restores the
+/// account's health. A healthy account can't be liquidated, so nothing can
+/// be repaid.
+pub fn max_repay(
+    debt: &LiquidationAsset,
+    collateral: &LiquidationAsset,
+    shortfall: Number128,
+    params: &LiquidationParams,
+) -> Result<Number128, Error> {
+    validate(debt, collateral, params)?;
+    if shortfall <= Number128::ZERO {
+        return Ok(Number128::ZERO);
+    }
+
+    let seize_price = seize_price(debt, params)?;
+    let close_limit = DOWN.mul(debt.amount, params.close_factor)?;
+    let collateral_limit = DOWN.mul_div(collateral.amount, collateral.price, seize_price)?;
+    let mut repay = close_limit.min(collateral_limit);
+
+    // the reduction in the shortfall per debt token repaid, which can only
+    // restore health if repaying reduces liabilities by more than seizing
+    // reduces collateral
+    let weighted_debt = DOWN.mul(debt.price, debt.weight)?;
+    let weighted_seizure = UP.mul(seize_price, collateral.weight)?;
+    if weighted_debt > weighted_seizure {
+        let health_limit = UP.div(shortfall, weighted_debt - weighted_seizure)?;
+        repay = repay.min(health_limit);
+    }
+
+    Ok(repay)
+}
+
+/// The collateral seized for repaying some debt, and the bonus within it
+pub fn seized_collateral(
+    repay: Number128,
+    debt: &LiquidationAsset,
+    collateral: &LiquidationAsset,
+    params: &LiquidationParams,
+) -> Result<(Number128, Number128), Error> {
+    validate(debt, collateral, params)?;
+    if repay < Number128::ZERO {
+        return Err(Error::InvalidInput("negative repayment"));
+    }
+
+    let seized = DOWN.mul_div(repay, seize_price(debt, params)?, collateral.price)?;
+    let repaid_value = UP.mul_div(repay, debt.price, collateral.price)?;
+    let bonus = (seized - repaid_value).max(Number128::ZERO);
+
+    Ok((seized, bonus))
+}
+
+/// The largest liquidation of an account
+pub fn liquidate(
+    debt: &LiquidationAsset,
+    collateral: &LiquidationAsset,
+    shortfall: Number128,
+    params: &LiquidationParams,
+) -> Result<Liquidation, Error> {
+    let repay = max_repay(debt, collateral, shortfall, params)?;
+    let (seized, bonus) = seized_collateral(repay, debt, collateral, params)?;
+
+    Ok(Liquidation {
+        repay,
+        seized,
+        bonus,
+    })
+}
+
+/// The collateral value seized per debt token repaid
+fn seize_price(debt: &LiquidationAsset, params: &LiquidationParams) -> Result<Number128, Error> {
+    DOWN.mul(debt.price, Number128::ONE + params.bonus)
+}
+
+fn validate(
+    debt: &LiquidationAsset,
+    collateral: &LiquidationAsset,
+    params: &LiquidationParams,
+) -> Result<(), Error> {
+    for asset in [debt, collateral] {
+        if asset.price <= Number128::ZERO {
+            return Err(Error::InvalidInput("non-positive price"));
+        }
+        if asset.amount < Number128::ZERO || asset.weight < Number128::ZERO {
+            return Err(Error::InvalidInput("negative amount or weight"));
+        }
+    }
+    if params.close_factor < Number128::ZERO || params.close_factor > Number128::ONE {
+        return Err(Error::InvalidInput("close factor outside of [0, 1]"));
+    }
+    if params.bonus < Number128::ZERO || params.bonus > Number128::ONE {
+        return Err(Error::InvalidInput("bonus outside of [0, 1]"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: LiquidationParams = LiquidationParams {
+        close_factor: number128!(0.5),
+        bonus: number128!(~0.05),
+    };
+
+    fn asset(amount: Number128, price: Number128, weight: Number128) -> LiquidationAsset {
+        LiquidationAsset {
+            amount,
+            price,
+            weight,
+        }
+    }
+
+    fn debt() -> LiquidationAsset {
+        asset(number128!(100), Number128::ONE, Number128::ONE)
+    }
+
+    #[test]
+    fn limited_by_close_factor() {
+        // 94.5 of weighted collateral against 100 of liabilities
+        let collateral = asset(number128!(100), number128!(~1.05), number128!(~0.9));
+        let liquidation = liquidate(&debt(), &collateral, number128!(5.5), &PARAMS).unwrap();
+
+        assert_eq!(number128!(50), liquidation.repay);
+        assert_eq!(number128!(50), liquidation.seized);
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(number128!(2.3809523809), liquidation.bonus);
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn limited_by_restoring_health() {
+        // 99.45 of weighted collateral against 100 of liabilities
+        let collateral = asset(number128!(100), number128!(1.105), number128!(0.9));
+        let liquidation = liquidate(&debt(), &collateral, number128!(0.55), &PARAMS).unwrap();
+
+        // each token repaid reduces the shortfall by 1 - 1.05 * 0.9
+        assert_eq!(number128!(10), liquidation.repay);
+        assert_eq!(number128!(9.5022624434), liquidation.seized);
+        assert_eq!(number128!(0.4524886877), liquidation.bonus);
+    }
+
+    #[test]
+    fn limited_by_collateral() {
+        let collateral = asset(number128!(10), Number128::ONE, number128!(0.5));
+        let liquidation = liquidate(&debt(), &collateral, number128!(95), &PARAMS).unwrap();
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(number128!(9.5238095238), liquidation.repay);
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(number128!(9.9999999999), liquidation.seized);
+        assert!(liquidation.seized <= collateral.amount);
+    }
+
+    #[test]
+    fn healthy_accounts_are_not_liquidated() {
+        let collateral = asset(number128!(200), Number128::ONE, Number128::ONE);
+
+        assert_eq!(
+            Ok(Number128::ZERO),
+            max_repay(&debt(), &collateral, number128!(-100), &PARAMS)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let collateral = asset(number128!(100), Number128::ZERO, Number128::ONE);
+        assert!(liquidate(&debt(), &collateral, Number128::ONE, &PARAMS).is_err());
+
+        let collateral = asset(number128!(100), Number128::ONE, Number128::ONE);
+        let params = LiquidationParams {
+            close_factor: number128!(1.5),
+            ..PARAMS
+        };
+        assert!(liquidate(&debt(), &collateral, Number128::ONE, &params).is_err());
+        assert!(seized_collateral(-Number128::ONE, &debt(), &collateral, &PARAMS).is_err());
+    }
+}
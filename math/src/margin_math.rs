@@ -0,0 +1,149 @@
+//! Collateral weighting and health calculations for margin accounts.
+//!
+//! Each position is given as `(amount, price, weight)`. Products are summed
+//! exactly in 256 bits and rounded once at the end, always against the
+//! account: collateral rounds down and required margin rounds up. Programs
+//! and off-chain services using these functions get identical results.
+
+use crate::bigint::{U192, U256};
+use crate::{Error, Number, Number128};
+
+/// A position's token amount, the token price, and its weight
+pub type WeightedPosition = (Number, Number128, Number128);
+
+/// The total weighted value of the collateral positions, rounded down
+pub fn weighted_collateral(positions: &[WeightedPosition]) -> Result<Number, Error> {
+    let (numerator, denominator) = weighted_sum(positions)?;
+
+    to_number(numerator / denominator)
+}
+
+/// The total margin required by the liability positions, rounded up
+pub fn required_margin(positions: &[WeightedPosition]) -> Result<Number, Error> {
+    let (numerator, denominator) = weighted_sum(positions)?;
+    let (quotient, remainder) = numerator.div_rem(denominator);
+
+    match remainder.is_zero() {
+        true => to_number(quotient),
+        false => to_number(quotient + 1),
+    }
+}
+
+/// The ratio of weighted collateral to required margin, rounded down
+///
+/// An account with no required margin has a health factor of `Number::MAX`.
+pub fn health_factor(
+    collateral: &[WeightedPosition],
+    liabilities: &[WeightedPosition],
+) -> Result<Number, Error> {
+    let collateral = weighted_collateral(collateral)?;
+    let required = required_margin(liabilities)?;
+
+    if required == Number::ZERO {
+        return Ok(Number::MAX);
+    }
+
+    let numerator = U256::from(collateral.to_u192()) * U256::from(Number::ONE.to_u192());
+
+    to_number(numerator / U256::from(required.to_u192()))
+}
+
+/// Sum `amount * price * weight` exactly, returning the sum along with the
+/// divisor that scales it back to a `Number`.
+fn weighted_sum(positions: &[WeightedPosition]) -> Result<(U256, U256), Error> {
+    let one = U256::from(Number128::ONE.to_i128() as u128);
+    let mut sum = U256::zero();
+
+    for (amount, price, weight) in positions {
+        if *price < Number128::ZERO || *weight < Number128::ZERO {
+            return Err(Error::InvalidInput("negative price or weight"));
+        }
+
+        let product = U256::from(amount.to_u192())
+            .checked_mul(U256::from(price.to_i128() as u128))
+            .and_then(|p| p.checked_mul(U256::from(weight.to_i128() as u128)))
+            .ok_or(Error::OutOfRange)?;
+
+        sum = sum.checked_add(product).ok_or(Error::OutOfRange)?;
+    }
+
+    Ok((sum, one * one))
+}
+
+fn to_number(value: U256) -> Result<Number, Error> {
+    U192::try_from(value)
+        .map(Number::from_u192)
+        .map_err(|_| Error::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(amount: u64, price: Number128, weight: Number128) -> WeightedPosition {
+        (Number::from(amount), price, weight)
+    }
+
+    #[test]
+    fn weights_positions() {
+        #[cfg(not(feature = "binary-number128"))]
+        let positions = [
+            position(
+                10,
+                Number128::from_decimal(25, 0),
+                Number128::from_bps(8000),
+            ),
+            position(100, Number128::from_decimal(1, 0), Number128::ONE),
+        ];
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(Number::from(300)), weighted_collateral(&positions));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(Number::from(300)), required_margin(&positions));
+        assert_eq!(Ok(Number::ZERO), weighted_collateral(&[]));
+    }
+
+    #[test]
+    fn rounds_against_the_account() {
+        let dust = [(
+            Number::from_decimal(1, -15),
+            Number128::from_decimal(5, -1),
+            Number128::ONE,
+        )];
+
+        assert_eq!(Ok(Number::ZERO), weighted_collateral(&dust));
+        assert_eq!(Ok(Number::from_decimal(1, -15)), required_margin(&dust));
+    }
+
+    #[test]
+    fn computes_health_factor() {
+        let collateral = [position(
+            10,
+            Number128::from_decimal(25, 0),
+            Number128::from_bps(8000),
+        )];
+        #[cfg(not(feature = "binary-number128"))]
+        let liabilities = [position(
+            100,
+            Number128::ONE,
+            Number128::from_decimal(12, -1),
+        )];
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(Number::from_decimal(1666666666666666u64, -15)),
+            health_factor(&collateral, &liabilities)
+        );
+        assert_eq!(Ok(Number::MAX), health_factor(&collateral, &[]));
+    }
+
+    #[test]
+    fn rejects_negative_inputs() {
+        let positions = [position(1, -Number128::ONE, Number128::ONE)];
+
+        assert_eq!(
+            Err(Error::InvalidInput("negative price or weight")),
+            weighted_collateral(&positions)
+        );
+    }
+}
@@ -3,44 +3,182 @@
 use std::{
     fmt::Debug,
     iter::Sum,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Shl, Shr, Sub, SubAssign},
+    str::FromStr,
 };
 
-use bytemuck::{Pod, Zeroable};
+use bytemuck::{Pod, PodCastError, Zeroable};
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
-use uint::construct_uint;
 
-construct_uint! {
-    #[derive(Pod, Zeroable)]
-    pub struct U192(3);
-}
+use crate::bigint::{U192, U256, U384};
 
 pub const BPS_EXPONENT: i32 = -4;
-const PRECISION: i32 = 15;
+/// The decimal places of a `Number`, distinct from the `PRECISION`
+/// parameter of a `NumberP`
+const NUMBER_DECIMALS: i32 = 15;
 const ONE: U192 = U192([1_000_000_000_000_000, 0, 0]);
 const U64_MAX: U192 = U192([u64::MAX, 0x0, 0x0]);
 
-/// A large unsigned integer
+/// A large unsigned integer, with `PRECISION` decimal places
 #[derive(Pod, Zeroable, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 #[repr(transparent)]
-pub struct Number(U192);
+pub struct NumberP<const PRECISION: u32>(U192);
+
+/// A large unsigned integer, with 15 decimal places
+pub type Number = NumberP<15>;
 
 static_assertions::const_assert_eq!(24, std::mem::size_of::<Number>());
 static_assertions::const_assert_eq!(0, std::mem::size_of::<Number>() % 8);
-impl Number {
-    pub const ONE: Self = Self(ONE);
+
+impl<const PRECISION: u32> NumberP<PRECISION> {
+    pub const ONE: Self = Self(U192(pow10_limbs(PRECISION)));
     pub const ZERO: Self = Self(U192::zero());
     pub const MAX: Self = Self(U192([u64::MAX, u64::MAX, u64::MAX]));
     pub const MIN: Self = Self::ZERO;
     pub const BITS: u32 = 192;
 
+    /// Convert to a different number of decimal places, rounding as
+    /// specified if any are dropped
+    pub fn to_precision<const TARGET: u32>(
+        &self,
+        rounding: Rounding,
+    ) -> Result<NumberP<TARGET>, Error> {
+        let exponent = (TARGET as i64 - PRECISION as i64).clamp(i32::MIN as i64, i32::MAX as i64);
+        let scaled = self.scale_by_pow10(exponent as i32, rounding)?;
+
+        Ok(NumberP(scaled.0))
+    }
+
+    /// Get the underlying U192 value
+    pub fn to_u192(self) -> U192 {
+        self.0
+    }
+
+    /// Create a number from an underlying U192 value
+    pub fn from_u192(value: U192) -> Self {
+        Self(value)
+    }
+
+    /// The number of leading zero bits in the raw representation
+    pub fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros()
+    }
+
+    /// The smallest step between two numbers, i.e. one raw unit
+    pub fn ulp(&self) -> Self {
+        Self(U192::one())
+    }
+
+    /// The smallest number greater than this one, or `None` for `MAX`
+    pub fn next_up(&self) -> Option<Self> {
+        self.0.checked_add(U192::one()).map(Self)
+    }
+
+    /// The largest number less than this one, or `None` for zero
+    pub fn next_down(&self) -> Option<Self> {
+        self.0.checked_sub(U192::one()).map(Self)
+    }
+
+    /// Multiply by `2^bits`, returning `None` on overflow
+    pub fn checked_shl(&self, bits: u32) -> Option<Self> {
+        match self.0.is_zero() || self.leading_zeros() >= bits {
+            true if bits >= Self::BITS => Some(Self::ZERO),
+            true => Some(Self(self.0 << bits as usize)),
+            false => None,
+        }
+    }
+
+    /// Multiply by `2^exponent`, rounding down if the exponent is negative
+    pub fn scale_pow2(&self, exponent: i32) -> Result<Self, Error> {
+        let bits = exponent.unsigned_abs();
+
+        match exponent >= 0 {
+            true => self.checked_shl(bits).ok_or(Error::OutOfRange),
+            false => Ok(*self >> bits),
+        }
+    }
+
+    /// Add two numbers, returning `None` on overflow
+    pub fn checked_add(&self, v: &Self) -> Option<Self> {
+        let result = self.0.checked_add(v.0).map(Self);
+        audit!("Number::checked_add", self, v, result.as_ref());
+        result
+    }
+
+    /// Subtract two numbers, returning `None` on underflow
+    pub fn checked_sub(&self, v: &Self) -> Option<Self> {
+        let result = self.0.checked_sub(v.0).map(Self);
+        audit!("Number::checked_sub", self, v, result.as_ref());
+        result
+    }
+
+    /// Multiply two numbers, returning `None` on overflow
+    pub fn checked_mul(&self, v: &Self) -> Option<Self> {
+        let one = Self::ONE.0;
+        // the product can only overflow if the operands have fewer than
+        // 192 leading zeros between them
+        let result = match self.leading_zeros() + v.leading_zeros() >= Self::BITS {
+            true => Some(Self(self.0 * v.0 / one)),
+            false => self.0.checked_mul_div(v.0, one).map(Self),
+        };
+        audit!("Number::checked_mul", self, v, result.as_ref(),
+            rounded: result.is_some_and(|r| r.0.full_mul(one) != self.0.full_mul(v.0)));
+        result
+    }
+
+    /// Divide two numbers, returning `None` on overflow or division by zero
+    pub fn checked_div(&self, v: &Self) -> Option<Self> {
+        let one = Self::ONE.0;
+        let result = match self.leading_zeros() + one.leading_zeros() >= Self::BITS {
+            true => self.0.checked_mul(one).and_then(|n| n.checked_div(v.0)),
+            false => self.0.checked_mul_div(one, v.0),
+        }
+        .map(Self);
+        audit!("Number::checked_div", self, v, result.as_ref(),
+            rounded: result.is_some_and(|r| r.0.full_mul(v.0) != self.0.full_mul(one)));
+        result
+    }
+
+    /// Multiply by `10^exponent`, rounding as specified if the exponent is
+    /// negative
+    fn scale_by_pow10(&self, exponent: i32, rounding: Rounding) -> Result<Self, Error> {
+        let scale = U192::from(10u64).checked_pow(U192::from(exponent.unsigned_abs()));
+
+        if exponent >= 0 {
+            return match self.0.is_zero() {
+                true => Ok(*self),
+                false => scale
+                    .and_then(|scale| self.0.checked_mul(scale))
+                    .map(Self)
+                    .ok_or(Error::OutOfRange),
+            };
+        }
+
+        // the scale is larger than any value if it overflows, so only
+        // rounding up can leave anything
+        let Some(scale) = scale else {
+            return Ok(match rounding == Rounding::Up && !self.0.is_zero() {
+                true => Self(U192::one()),
+                false => Self::ZERO,
+            });
+        };
+
+        Ok(Self(div_rounded(self.0, scale, rounding)))
+    }
+}
+
+impl Number {
+    /// A recommended dust threshold of 10^-12: a thousand of the smallest
+    /// units, which covers the rounding error of a long chain of operations
+    pub const DUST_EPSILON: Self = Self(U192([1_000, 0, 0]));
+
     /// Convert this number to fit in a u64
     ///
     /// The precision of the number in the u64 is based on the
     /// exponent provided.
     pub fn as_u64(&self, exponent: impl Into<i32>) -> u64 {
-        let extra_precision = PRECISION + exponent.into();
+        let extra_precision = NUMBER_DECIMALS + exponent.into();
         let prec_value = Self::ten_pow(extra_precision.unsigned_abs());
 
         let target_value = if extra_precision < 0 {
@@ -64,7 +202,7 @@ impl Number {
     /// The result is rounded up to the nearest one, based on the
     /// target precision.
     pub fn as_u64_ceil(&self, exponent: impl Into<i32>) -> u64 {
-        let extra_precision = PRECISION + exponent.into();
+        let extra_precision = NUMBER_DECIMALS + exponent.into();
         let prec_value = Self::ten_pow(extra_precision.unsigned_abs());
 
         let target_rounded = prec_value - U192::from(1) + self.0;
@@ -89,31 +227,261 @@ impl Number {
     /// The result is rounded to the nearest one, based on the
     /// target precision.
     pub fn as_u64_rounded(&self, exponent: impl Into<i32>) -> u64 {
-        let extra_precision = PRECISION + exponent.into();
-        let prec_value = Self::ten_pow(extra_precision.unsigned_abs());
+        self.as_u64_rounded_with(exponent, Rounding::HalfUp)
+    }
 
-        let rounding = match extra_precision > 0 {
-            true => U192::from(1) * prec_value / 2,
-            false => U192::zero(),
-        };
+    /// Convert this number to fit in a u64, rounding at the target
+    /// precision as specified by `rounding`.
+    pub fn as_u64_rounded_with(&self, exponent: impl Into<i32>, rounding: Rounding) -> u64 {
+        self.checked_as_u64_rounded(exponent.into(), rounding)
+            .unwrap_or_else(|| panic!("cannot convert to u64 due to overflow"))
+    }
+
+    pub(crate) fn checked_as_u64_rounded(&self, exponent: i32, rounding: Rounding) -> Option<u64> {
+        let extra_precision = NUMBER_DECIMALS + exponent;
+        let prec_value = Self::ten_pow(extra_precision.unsigned_abs());
 
-        let target_rounded = rounding + self.0;
         let target_value = if extra_precision < 0 {
-            target_rounded * prec_value
+            self.0.checked_mul(prec_value)?
         } else {
-            target_rounded / prec_value
+            div_rounded(self.0, prec_value, rounding)
         };
 
-        if target_value > U64_MAX {
-            panic!("cannot convert to u64 due to overflow");
+        match target_value > U64_MAX {
+            true => None,
+            false => Some(target_value.as_u64()),
         }
+    }
 
-        target_value.as_u64()
+    /// Convert this number to fit in an i64, truncating at the precision
+    /// given by the exponent, or return an error on overflow
+    pub fn try_as_i64(&self, exponent: impl Into<i32>) -> Result<i64, Error> {
+        i64::try_from(self.try_as_u128(exponent)?).map_err(|_| Error::OutOfRange)
+    }
+
+    /// Convert this number to fit in a u128, truncating at the precision
+    /// given by the exponent, or return an error on overflow
+    pub fn try_as_u128(&self, exponent: impl Into<i32>) -> Result<u128, Error> {
+        let shift = -(NUMBER_DECIMALS as i64 + exponent.into() as i64);
+        let value = self
+            .scale_by_pow10(
+                shift.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+                Rounding::Down,
+            )?
+            .0;
+
+        match value.bits() > 128 {
+            true => Err(Error::OutOfRange),
+            false => Ok(value.low_u128()),
+        }
+    }
+
+    /// Convert this number to fit in an i128, truncating at the precision
+    /// given by the exponent, or return an error on overflow
+    pub fn try_as_i128(&self, exponent: impl Into<i32>) -> Result<i128, Error> {
+        i128::try_from(self.try_as_u128(exponent)?).map_err(|_| Error::OutOfRange)
+    }
+
+    /// Convert a raw token amount into a `Number` of whole tokens
+    ///
+    /// Digits beyond the precision of a `Number` are truncated, so amounts
+    /// of a token with very many decimals can round down to zero. Use
+    /// `conversions::token_amount_to_number` to round another way.
+    pub fn from_token_amount(amount: u64, decimals: u8) -> Self {
+        crate::conversions::token_amount_to_number(amount, decimals, Rounding::Down)
+    }
+
+    /// Convert a `Number` of whole tokens into a raw token amount, returning
+    /// an error if it does not fit in a `u64`
+    pub fn to_token_amount(&self, decimals: u8, rounding: Rounding) -> Result<u64, Error> {
+        let amount = self
+            .scale_by_pow10(decimals as i32 - NUMBER_DECIMALS, rounding)?
+            .0;
+
+        match amount > U64_MAX {
+            true => Err(Error::OutOfRange),
+            false => Ok(amount.as_u64()),
+        }
+    }
+
+    /// Convert a value with `from_decimals` of precision to `to_decimals`,
+    /// i.e. multiply by 10 to the power of the difference, rounding the
+    /// result to the precision of this type
+    pub fn rescale(
+        &self,
+        from_decimals: u8,
+        to_decimals: u8,
+        rounding: Rounding,
+    ) -> Result<Self, Error> {
+        self.scale_by_pow10(to_decimals as i32 - from_decimals as i32, rounding)
+    }
+
+    /// Rescale values that each have their own decimals, e.g. prices of
+    /// tokens with different decimals, to the same number of decimals
+    pub fn normalize_to(
+        values: &[(Number, u8)],
+        decimals: u8,
+        rounding: Rounding,
+    ) -> Result<Vec<Self>, Error> {
+        values
+            .iter()
+            .map(|(value, from_decimals)| value.rescale(*from_decimals, decimals, rounding))
+            .collect()
+    }
+
+    /// Format the exact value as a string that parses back to the same number
+    ///
+    /// This is the same as `to_string`, since every `Number` has an exact
+    /// decimal representation.
+    pub fn to_exact_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Format the value along with its raw representation, for logging
+    pub fn to_debug_string(&self) -> String {
+        format!("{self:#?}")
+    }
+
+    /// Format with a thousands suffix, e.g. `1.24M`, rounded half up to
+    /// at least the given number of significant digits
+    pub fn to_si_string(&self, significant_digits: u32) -> String {
+        crate::display::format_si(&self.to_string(), significant_digits as usize)
+    }
+
+    /// The largest integer `k` such that `2^k <= self`, or `None` if the
+    /// number is zero.
+    pub fn log2_floor(&self) -> Option<i32> {
+        if self.0.is_zero() {
+            return None;
+        }
+
+        let k = ONE.leading_zeros() as i32 - self.0.leading_zeros() as i32;
+
+        // k is either exact or one too large
+        let fits = match k >= 0 {
+            true => ONE << k as usize <= self.0,
+            false => self.0 << -k as usize >= ONE,
+        };
+
+        Some(if fits { k } else { k - 1 })
+    }
+
+    /// The largest integer `k` such that `10^k <= self`, or `None` if the
+    /// number is zero.
+    pub fn log10_floor(&self) -> Option<i32> {
+        if self.0.is_zero() {
+            return None;
+        }
+
+        // floor(log10(2^bits)) is an upper bound for the number of digits
+        let mut digits = (self.0.bits() as i32 * 1233) >> 12;
+        while U192::exp10(digits as usize) > self.0 {
+            digits -= 1;
+        }
+
+        Some(digits - NUMBER_DECIMALS)
+    }
+
+    /// Round to `figures` significant decimal figures, e.g. to compare
+    /// prices regardless of their magnitude
+    pub fn round_to_sig_figs(&self, figures: u32, rounding: Rounding) -> Result<Number, Error> {
+        if figures == 0 {
+            return Err(Error::InvalidInput("no significant figures"));
+        }
+        let Some(exponent) = self.log10_floor() else {
+            return Ok(*self);
+        };
+
+        // the number of raw digits after the last significant figure
+        let dropped = exponent as i64 + NUMBER_DECIMALS as i64 - (figures as i64 - 1);
+        if dropped <= 0 {
+            return Ok(*self);
+        }
+
+        self.scale_by_pow10(-dropped as i32, rounding)?
+            .scale_by_pow10(dropped as i32, Rounding::Down)
+    }
+
+    /// Convert this number to a f64, see `as_f64_lossy`
+    pub fn as_f64(&self) -> f64 {
+        self.as_f64_lossy()
+    }
+
+    /// Convert this number to a f64, losing precision
+    ///
+    /// An f64 has a 53-bit significand, while a `Number` has about 50 bits
+    /// of fraction on top of up to 142 bits of integer, so the result is
+    /// only accurate to within two ulps relative to the exact value. This is
+    /// intended for analytics and logging, not for further calculation.
+    pub fn as_f64_lossy(&self) -> f64 {
+        let bits = self.0.bits();
+        let value = match bits {
+            0..=64 => self.0.low_u64() as f64,
+            _ => (self.0 >> (bits - 64)).low_u64() as f64 * 2f64.powi(bits as i32 - 64),
+        };
+
+        value / ONE.low_u64() as f64
+    }
+
+    /// Parse a decimal literal such as `"0.95"` in a const context
+    ///
+    /// Panics, which in a const is a compile error, if the string is not a
+    /// plain decimal, has more decimal places than can be stored, or
+    /// overflows. See `number!`.
+    pub const fn from_decimal_str(s: &str) -> Self {
+        Self::parse_decimal_str(s, false)
+    }
+
+    /// Like `from_decimal_str`, but truncates any decimal places that
+    /// cannot be stored
+    pub const fn from_decimal_str_lossy(s: &str) -> Self {
+        Self::parse_decimal_str(s, true)
+    }
+
+    const fn parse_decimal_str(s: &str, lossy: bool) -> Self {
+        let bytes = s.as_bytes();
+        let (mut i, end) = literal_bounds(bytes);
+        assert!(i == end || bytes[i] != b'-', "a Number cannot be negative");
+
+        // the digits are accumulated into the underlying integer directly
+        let mut limbs = [0u64; 3];
+        let mut int_digits = 0;
+        while i < end && bytes[i] != b'.' {
+            if bytes[i] != b'_' {
+                assert!(bytes[i].is_ascii_digit(), "not a decimal number");
+                limbs = mul_add_limbs(limbs, 10, (bytes[i] - b'0') as u64);
+                int_digits += 1;
+            }
+            i += 1;
+        }
+        assert!(int_digits > 0, "not a decimal number");
+
+        let mut places = 0;
+        i += 1;
+        while i < end {
+            if bytes[i] != b'_' {
+                assert!(bytes[i].is_ascii_digit(), "not a decimal number");
+                match places < NUMBER_DECIMALS {
+                    true => {
+                        limbs = mul_add_limbs(limbs, 10, (bytes[i] - b'0') as u64);
+                        places += 1;
+                    }
+                    false => assert!(lossy || bytes[i] == b'0', "too many decimal places"),
+                }
+            }
+            i += 1;
+        }
+        while places < NUMBER_DECIMALS {
+            limbs = mul_add_limbs(limbs, 10, 0);
+            places += 1;
+        }
+
+        Self(U192(limbs))
     }
 
     /// Convert another integer into a `Number`.
     pub fn from_decimal(value: impl Into<U192>, exponent: impl Into<i32>) -> Self {
-        let extra_precision = PRECISION + exponent.into();
+        let extra_precision = NUMBER_DECIMALS + exponent.into();
         let prec_value = Self::ten_pow(extra_precision.unsigned_abs());
 
         if extra_precision < 0 {
@@ -123,27 +491,270 @@ impl Number {
         }
     }
 
+    /// Convert `value * 10^exponent`, rounding as specified instead of
+    /// truncating like `from_decimal` does
+    ///
+    /// Panics on overflow.
+    pub fn from_u64_rounded(value: u64, exponent: impl Into<i32>, rounding: Rounding) -> Self {
+        Self::from_u192(U192::from(value))
+            .scale_by_pow10(exponent.into().saturating_add(NUMBER_DECIMALS), rounding)
+            .unwrap_or_else(|_| panic!("cannot convert from u64 due to overflow"))
+    }
+
+    /// Convert `value * 10^exponent`, returning an error if it would have
+    /// to be rounded, e.g. an amount of a token with more decimals than a
+    /// `Number` has
+    pub fn try_from_u64_exact(value: u64, exponent: impl Into<i32>) -> Result<Self, Error> {
+        let raw = Self::from_u192(U192::from(value));
+        let exponent = exponent.into().saturating_add(NUMBER_DECIMALS);
+        let result = raw.scale_by_pow10(exponent, Rounding::Down)?;
+
+        match result == raw.scale_by_pow10(exponent, Rounding::Up)? {
+            true => Ok(result),
+            false => Err(Error::InvalidInput("value cannot be represented exactly")),
+        }
+    }
+
+    /// Convert `value * 10^exponent`, truncating like `from_decimal`, but
+    /// returning an error on overflow
+    pub fn from_u128(value: u128, exponent: impl Into<i32>) -> Result<Self, Error> {
+        Self::from_u192(U192::from(value)).scale_by_pow10(
+            exponent.into().saturating_add(NUMBER_DECIMALS),
+            Rounding::Down,
+        )
+    }
+
+    /// Convert a signed `value * 10^exponent` like `from_u128`, returning an
+    /// error if the value is negative
+    pub fn try_from_i128(value: i128, exponent: impl Into<i32>) -> Result<Self, Error> {
+        let value = u128::try_from(value).map_err(|_| Error::InvalidInput("negative value"))?;
+
+        Self::from_u128(value, exponent)
+    }
+
     /// Convert from basis points into a `Number`
     pub fn from_bps(basis_points: u16) -> Number {
         Number::from_decimal(basis_points, BPS_EXPONENT)
     }
 
+    /// Raise this number to a whole number power
+    ///
+    /// Previously this raised the raw representations to each other's power,
+    /// which was only correct for trivial cases. It now returns the fixed-point
+    /// power, and panics if the exponent has a fractional part.
+    #[deprecated(note = "use `powi`, which takes the exponent as an integer")]
     pub fn pow(&self, exp: impl Into<Number>) -> Number {
-        let value = self.0.pow(exp.into().0);
+        let (exp, frac) = exp.into().0.div_mod(ONE);
 
-        Self(value)
+        if !frac.is_zero() || exp > U192::from(u32::MAX) {
+            panic!("unsupported exponent for pow");
+        }
+
+        self.powi(exp.as_u32())
+    }
+
+    /// Raise this number to an integer power, panicking on overflow
+    pub fn powi(&self, exp: u32) -> Number {
+        self.checked_powi(exp.into()).unwrap()
+    }
+
+    /// Raise this number to an integer power, returning `None` on overflow
+    ///
+    /// Uses square-and-multiply with a wide intermediate product, rescaling
+    /// after every step, so the result may be rounded down by a few units in
+    /// the last place.
+    pub fn checked_powi(&self, mut exp: u64) -> Option<Number> {
+        let mut base = *self;
+        let mut acc = Number::ONE;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.checked_mul_div(&base, &Number::ONE)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul_div(&base, &Number::ONE)?;
+            }
+        }
+
+        Some(acc)
+    }
+
+    /// Compute `self * mul / div` without losing precision in the
+    /// intermediate product, returning `None` on overflow or division by zero
+    pub fn checked_mul_div(&self, mul: &Number, div: &Number) -> Option<Number> {
+        let result = self.0.checked_mul_div(mul.0, div.0).map(Self);
+        audit!("Number::checked_mul_div", self, (mul, div), result.as_ref(),
+            rounded: result.is_some_and(|r| r.0.full_mul(div.0) != self.0.full_mul(mul.0)));
+        result
+    }
+
+    /// Compute `self * mul / div` like `checked_mul_div`, rounding as
+    /// specified instead of truncating
+    pub fn checked_mul_div_rounded(
+        &self,
+        mul: &Number,
+        div: &Number,
+        rounding: Rounding,
+    ) -> Option<Number> {
+        if div.0.is_zero() {
+            return None;
+        }
+
+        let divisor = U384::from(div.0);
+        let product = U384::from(self.0) * U384::from(mul.0);
+
+        let result = div_rounded(product, divisor, rounding)
+            .checked_as_u192()
+            .map(Self);
+        audit!("Number::checked_mul_div_rounded", self, (mul, div), result.as_ref(),
+            rounded: !(product % divisor).is_zero());
+        result
+    }
+
+    /// Multiply the underlying values without overflow or scaling, returning
+    /// the high and low halves of the 384-bit product as `(hi, lo)`.
+    ///
+    /// The product is scaled by `ONE` twice, so divide it by `ONE` to get
+    /// the underlying value of the result.
+    pub fn full_mul(&self, rhs: &Number) -> (U192, U192) {
+        self.0.full_mul(rhs.0)
+    }
+
+    /// Add two numbers, returning an error with the operands on overflow
+    pub fn try_add(&self, rhs: &Number) -> Result<Number, Error> {
+        self.checked_add(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number::try_add", self, rhs))
+    }
+
+    /// Subtract two numbers, returning an error with the operands on
+    /// underflow
+    pub fn try_sub(&self, rhs: &Number) -> Result<Number, Error> {
+        self.checked_sub(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number::try_sub", self, rhs))
+    }
+
+    /// Multiply two numbers, returning an error with the operands on
+    /// overflow
+    pub fn try_mul(&self, rhs: &Number) -> Result<Number, Error> {
+        self.checked_mul(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number::try_mul", self, rhs))
+    }
+
+    /// Divide two numbers, returning an error with the operands on overflow
+    /// or division by zero
+    pub fn try_div(&self, rhs: &Number) -> Result<Number, Error> {
+        if *rhs == Number::ZERO {
+            return Err(Error::DivideByZero.context("Number::try_div", self, rhs));
+        }
+
+        self.checked_div(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number::try_div", self, rhs))
+    }
+
+    /// Multiply in place, leaving the value unchanged on overflow
+    pub fn try_mul_assign(&mut self, rhs: Number) -> Result<(), Error> {
+        *self = self.checked_mul(&rhs).ok_or(Error::OutOfRange)?;
+        Ok(())
+    }
+
+    /// Divide in place, leaving the value unchanged on overflow or
+    /// division by zero
+    pub fn try_div_assign(&mut self, rhs: Number) -> Result<(), Error> {
+        if rhs == Number::ZERO {
+            return Err(Error::DivideByZero);
+        }
+
+        *self = self.checked_div(&rhs).ok_or(Error::OutOfRange)?;
+        Ok(())
+    }
+
+    /// Divide by an integer, rounding the result up. Dividing by an
+    /// integer with `/` rounds down.
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_by_int_ceil(self, rhs: impl Into<U192>) -> Number {
+        let rhs = rhs.into();
+        let (quotient, remainder) = self.0.div_mod(rhs);
+        let result = match remainder.is_zero() {
+            true => Self(quotient),
+            false => Self(quotient + 1),
+        };
+        audit!("Number::div_int_ceil", self, rhs, Some(&result),
+            rounded: !remainder.is_zero());
+        result
+    }
+
+    /// Divide by an integer, rounding the result to the nearest value, with
+    /// ties rounded up
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div_by_int_rounded(self, rhs: impl Into<U192>) -> Number {
+        let rhs = rhs.into();
+        let (quotient, remainder) = self.0.div_mod(rhs);
+        let result = match !remainder.is_zero() && remainder >= rhs - remainder {
+            true => Self(quotient + 1),
+            false => Self(quotient),
+        };
+        audit!("Number::div_int_rounded", self, rhs, Some(&result),
+            rounded: !remainder.is_zero());
+        result
     }
 
     pub fn saturating_add(&self, n: Number) -> Number {
-        Number(self.0.saturating_add(n.0))
+        let result = Self(self.0.saturating_add(n.0));
+        audit!("Number::saturating_add", self, n, Some(&result),
+            saturated: self.0.checked_add(n.0).is_none());
+        result
     }
 
     pub fn saturating_sub(&self, n: Number) -> Number {
-        Number(self.0.saturating_sub(n.0))
+        let result = Self(self.0.saturating_sub(n.0));
+        audit!("Number::saturating_sub", self, n, Some(&result),
+            saturated: self.0.checked_sub(n.0).is_none());
+        result
     }
 
     pub fn saturating_mul(&self, n: Number) -> Number {
-        Number(self.0.saturating_mul(n.0))
+        let result = Self(self.0.saturating_mul(n.0));
+        audit!("Number::saturating_mul", self, n, Some(&result),
+            saturated: self.0.checked_mul(n.0).is_none());
+        result
+    }
+
+    /// The value halfway between two numbers, rounded down, computed
+    /// without overflowing
+    pub fn midpoint(&self, other: Number) -> Number {
+        Self((self.0 & other.0) + ((self.0 ^ other.0) >> 1))
+    }
+
+    /// Check if the value is at most `threshold`, e.g. `DUST_EPSILON`, so
+    /// that it should be treated as zero
+    pub fn is_dust(&self, threshold: Number) -> bool {
+        *self <= threshold
+    }
+
+    /// Replace the value with zero if it is dust
+    pub fn to_zero_if_dust(self, threshold: Number) -> Number {
+        match self.is_dust(threshold) {
+            true => Number::ZERO,
+            false => self,
+        }
+    }
+
+    /// The arithmetic mean of the values, rounded down, computed without
+    /// overflowing
+    pub fn mean(values: &[Number]) -> Result<Number, Error> {
+        if values.is_empty() {
+            return Err(Error::InvalidInput("no values"));
+        }
+
+        let sum = values
+            .iter()
+            .fold(U256::zero(), |acc, n| acc + U256::from(n.0));
+        let mean = sum / U256::from(values.len());
+
+        Ok(Self(U192::try_from(mean).unwrap()))
     }
 
     pub fn ten_pow(exponent: u32) -> U192 {
@@ -172,20 +783,142 @@ impl Number {
     }
 
     /// Get the underlying representation in bits
+    ///
+    /// Uses the target endianness of the caller. Prefer `to_le_bytes` or
+    /// `to_be_bytes`, as this will be deprecated in the next major release.
     pub fn into_bits(self) -> [u8; 24] {
         unsafe { std::mem::transmute(self.0 .0) }
     }
 
     /// Read a number from a raw 196-bit representation, which was previously
     /// returned by a call to `into_bits`.
+    ///
+    /// Uses the target endianness of the caller. Prefer `from_le_bytes` or
+    /// `from_be_bytes`, as this will be deprecated in the next major release.
     pub fn from_bits(bits: [u8; 24]) -> Self {
         Self(U192(unsafe { std::mem::transmute(bits) }))
     }
+
+    /// Read numbers from the representations returned by `into_bits`
+    pub fn from_bits_slice(bits: &[[u8; 24]]) -> Vec<Number> {
+        bits.iter().copied().map(Number::from_bits).collect()
+    }
+
+    /// Reinterpret raw representations, like those returned by
+    /// `into_bits`, as numbers without copying
+    ///
+    /// Returns an error if the bytes are not aligned to 8 bytes, which
+    /// `from_bits_slice` does not require.
+    pub fn cast_bits_slice(bits: &[[u8; 24]]) -> Result<&[Number], Error> {
+        Self::cast_slice(bytemuck::cast_slice(bits))
+    }
+
+    /// Reinterpret bytes as numbers without copying, using the target
+    /// endianness like `from_bits`
+    ///
+    /// Returns an error if the bytes are not aligned to 8 bytes, or the
+    /// length is not a multiple of 24.
+    pub fn cast_slice(bytes: &[u8]) -> Result<&[Number], Error> {
+        bytemuck::try_cast_slice(bytes).map_err(|e| match e {
+            PodCastError::OutputSliceWouldHaveSlop => {
+                Error::InvalidInput("length is not a multiple of 24 bytes")
+            }
+            _ => Error::InvalidInput("bytes are not aligned to 8 bytes"),
+        })
+    }
+
+    /// Get the underlying representation as little-endian bytes
+    pub fn to_le_bytes(self) -> [u8; 24] {
+        let mut bytes = [0u8; 24];
+        self.0.to_little_endian(&mut bytes);
+        bytes
+    }
+
+    /// Read a number from its little-endian byte representation
+    pub fn from_le_bytes(bytes: [u8; 24]) -> Self {
+        Self(U192::from_little_endian(&bytes))
+    }
+
+    /// Get the underlying representation as big-endian bytes
+    pub fn to_be_bytes(self) -> [u8; 24] {
+        let mut bytes = [0u8; 24];
+        self.0.to_big_endian(&mut bytes);
+        bytes
+    }
+
+    /// Read a number from its big-endian byte representation
+    pub fn from_be_bytes(bytes: [u8; 24]) -> Self {
+        Self(U192::from_big_endian(&bytes))
+    }
+}
+
+/// The range of a literal passed through `stringify!`, without the quotes
+/// if it was a string literal
+pub(crate) const fn literal_bounds(bytes: &[u8]) -> (usize, usize) {
+    match bytes {
+        [b'"', .., b'"'] => (1, bytes.len() - 1),
+        _ => (0, bytes.len()),
+    }
+}
+
+/// The limbs of `10^exponent` as a U192, in a const context
+const fn pow10_limbs(exponent: u32) -> [u64; 3] {
+    let mut limbs = [1, 0, 0];
+    let mut i = 0;
+
+    while i < exponent {
+        limbs = mul_add_limbs(limbs, 10, 0);
+        i += 1;
+    }
+
+    limbs
+}
+
+/// Computes `limbs * mul + add` on the limbs of a U192 in a const context
+const fn mul_add_limbs(limbs: [u64; 3], mul: u64, add: u64) -> [u64; 3] {
+    let mut result = [0u64; 3];
+    let mut carry = add as u128;
+    let mut i = 0;
+
+    while i < 3 {
+        let value = limbs[i] as u128 * mul as u128 + carry;
+        result[i] = value as u64;
+        carry = value >> 64;
+        i += 1;
+    }
+    assert!(carry == 0, "decimal number is out of range");
+
+    result
 }
 
 impl<T: Into<U192>> From<T> for Number {
     fn from(n: T) -> Number {
-        Number(n.into() * ONE)
+        Self(n.into() * ONE)
+    }
+}
+
+impl PartialEq<u64> for Number {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == U192::from(*other) * ONE
+    }
+}
+
+impl PartialOrd<u64> for Number {
+    /// Compare with a whole number, by value
+    fn partial_cmp(&self, other: &u64) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(&(U192::from(*other) * ONE))
+    }
+}
+
+impl PartialEq<Number> for u64 {
+    fn eq(&self, other: &Number) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<Number> for u64 {
+    fn partial_cmp(&self, other: &Number) -> Option<std::cmp::Ordering> {
+        other.partial_cmp(self).map(std::cmp::Ordering::reverse)
     }
 }
 
@@ -195,93 +928,284 @@ impl From<Number> for [u8; 24] {
     }
 }
 
-impl Debug for Number {
+impl FromStr for Number {
+    type Err = Error;
+
+    /// Parse a decimal string, as printed by `Display`. Values with more
+    /// decimal places than a `Number` can store are rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, int, frac) = crate::display::split_decimal(s)?;
+
+        if negative {
+            return Err(Error::InvalidInput("negative value"));
+        }
+        if frac.len() > NUMBER_DECIMALS as usize {
+            return Err(Error::InvalidInput("too many decimal places"));
+        }
+
+        let int = U192::from_dec_str(int).map_err(|_| Error::OutOfRange)?;
+        let frac = match frac.is_empty() {
+            true => U192::zero(),
+            false => {
+                U192::from_dec_str(frac).unwrap()
+                    * Self::ten_pow(NUMBER_DECIMALS as u32 - frac.len() as u32)
+            }
+        };
+
+        int.checked_mul(ONE)
+            .and_then(|n| n.checked_add(frac))
+            .map(Self)
+            .ok_or(Error::OutOfRange)
+    }
+}
+
+impl<const PRECISION: u32> Debug for NumberP<PRECISION> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return match PRECISION {
+                15 => write!(f, "Number({}; raw={})", self, self.0),
+                _ => write!(f, "NumberP<{}>({}; raw={})", PRECISION, self, self.0),
+            };
+        }
+
         <Self as Display>::fmt(self, f)
     }
 }
 
-impl Display for Number {
+impl<const PRECISION: u32> Display for NumberP<PRECISION> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // todo optimize
-        let rem = self.0 % ONE;
+        let one = Self::ONE.0;
+        let rem = self.0 % one;
         let decimal_digits = PRECISION as usize;
         let rem_str = rem.to_string();
         // regular padding like {:010} doesn't work with U192
-        let decimals = "0".repeat(decimal_digits - rem_str.len()) + &*rem_str;
+        let decimals = "0".repeat(decimal_digits.saturating_sub(rem_str.len())) + &*rem_str;
         let stripped_decimals = decimals.trim_end_matches('0');
         let pretty_decimals = if stripped_decimals.is_empty() {
             "0"
         } else {
             stripped_decimals
         };
-        if self.0 < ONE {
+        if self.0 < one {
             write!(f, "0.{}", pretty_decimals)?;
         } else {
-            let int = self.0 / ONE;
+            let int = self.0 / one;
             write!(f, "{}.{}", int, pretty_decimals)?;
         }
         Ok(())
     }
 }
 
-#[derive(Error, Debug, Clone, Eq, PartialEq)]
-pub enum Error {
-    #[error("An integer value overflowed")]
-    Overflow(Number),
-
-    #[error("Attempting to divide by zero")]
-    DivideByZero,
+/// How to round a value that can't be represented exactly
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Round towards zero
+    Down,
+    /// Round away from zero
+    Up,
+    /// Round ties up, away from zero
+    HalfUp,
+    /// Round ties to the nearest even value (banker's rounding)
+    HalfEven,
 }
 
-impl Add<Number> for Number {
-    type Output = Number;
+/// Unsigned integers that `div_rounded` can divide
+pub(crate) trait UnsignedDiv:
+    Copy + Ord + From<u8> + Add<Output = Self> + Sub<Output = Self>
+{
+    fn div_mod(self, divisor: Self) -> (Self, Self);
 
-    fn add(self, rhs: Number) -> Self::Output {
-        Self(self.0.add(rhs.0))
-    }
+    fn is_odd(&self) -> bool;
 }
 
-impl AddAssign<Number> for Number {
-    fn add_assign(&mut self, rhs: Number) {
-        self.0.add_assign(rhs.0)
-    }
+macro_rules! impl_unsigned_div {
+    ($($t:ty),*) => {$(
+        impl UnsignedDiv for $t {
+            fn div_mod(self, divisor: Self) -> (Self, Self) {
+                <$t>::div_mod(self, divisor)
+            }
+
+            fn is_odd(&self) -> bool {
+                self.bit(0)
+            }
+        }
+    )*};
 }
 
-impl SubAssign<Number> for Number {
-    fn sub_assign(&mut self, rhs: Number) {
-        self.0.sub_assign(rhs.0)
+impl_unsigned_div!(U192, U256, U384);
+
+impl UnsignedDiv for u128 {
+    fn div_mod(self, divisor: Self) -> (Self, Self) {
+        (self / divisor, self % divisor)
+    }
+
+    fn is_odd(&self) -> bool {
+        self & 1 == 1
     }
 }
 
-impl Sub<Number> for Number {
-    type Output = Number;
+/// Divide, rounding the quotient as specified. Panics if the divisor is
+/// zero.
+///
+/// Rounding up can't overflow, since the quotient is at most half of the
+/// maximum value whenever there is a remainder.
+pub(crate) fn div_rounded<T: UnsignedDiv>(numerator: T, divisor: T, rounding: Rounding) -> T {
+    let (quotient, remainder) = numerator.div_mod(divisor);
+    let half = divisor - remainder;
+
+    let round_up = remainder != T::from(0)
+        && match rounding {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp => remainder >= half,
+            Rounding::HalfEven => remainder > half || (remainder == half && quotient.is_odd()),
+        };
 
-    fn sub(self, rhs: Number) -> Self::Output {
-        Self(self.0.sub(rhs.0))
+    match round_up {
+        true => quotient + T::from(1),
+        false => quotient,
     }
 }
 
-impl Mul<Number> for Number {
-    type Output = Number;
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    #[error("An integer value overflowed")]
+    Overflow(Number),
+
+    #[error("Attempting to divide by zero")]
+    DivideByZero,
+
+    #[error("The exponent {0} is out of the supported range")]
+    ExponentOutOfRange(i32),
+
+    #[error("The value is out of the representable range")]
+    OutOfRange,
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(&'static str),
+
+    #[error(transparent)]
+    Freshness(#[from] crate::oracle::FreshnessError),
+
+    #[error("{context} failed: {error}")]
+    Context {
+        context: MathErrorCtx,
+        error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Attach the operation that caused this error, and its operands if
+    /// the `debug-errors` feature is enabled
+    pub fn context(self, op: &'static str, lhs: &impl Debug, rhs: &impl Debug) -> Self {
+        #[cfg(feature = "debug-errors")]
+        let operands = Some(format!("{lhs:?}, {rhs:?}"));
+        #[cfg(not(feature = "debug-errors"))]
+        let operands = {
+            let _ = (lhs, rhs);
+            None
+        };
 
-    fn mul(self, rhs: Number) -> Self::Output {
-        Self(self.0.mul(rhs.0).div(ONE))
+        Error::Context {
+            context: MathErrorCtx { op, operands },
+            error: Box::new(self),
+        }
     }
 }
 
-impl MulAssign<Number> for Number {
-    fn mul_assign(&mut self, rhs: Number) {
-        self.0.mul_assign(rhs.0);
-        self.0.div_assign(ONE);
+/// The operation that caused an error
+///
+/// The operands are only formatted with the `debug-errors` feature, since
+/// formatting numbers costs a lot of compute on-chain. Without it,
+/// `operands` is `None`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MathErrorCtx {
+    pub op: &'static str,
+    pub operands: Option<String>,
+}
+
+impl Display for MathErrorCtx {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.operands {
+            Some(operands) => write!(f, "{}({})", self.op, operands),
+            None => write!(f, "{}", self.op),
+        }
     }
 }
 
-impl Div<Number> for Number {
-    type Output = Number;
+impl<const PRECISION: u32> Add for NumberP<PRECISION> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs).unwrap()
+    }
+}
+
+impl<const PRECISION: u32> AddAssign for NumberP<PRECISION> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const PRECISION: u32> SubAssign for NumberP<PRECISION> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const PRECISION: u32> Sub for NumberP<PRECISION> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs).unwrap()
+    }
+}
+
+impl<const PRECISION: u32> Mul for NumberP<PRECISION> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(&rhs).unwrap()
+    }
+}
+
+impl<const PRECISION: u32> MulAssign for NumberP<PRECISION> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
 
-    fn div(self, rhs: Number) -> Self::Output {
-        Self(self.0.mul(ONE).div(rhs.0))
+impl<const PRECISION: u32> Div for NumberP<PRECISION> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(&rhs).unwrap()
+    }
+}
+
+impl<const PRECISION: u32> DivAssign for NumberP<PRECISION> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const PRECISION: u32> Shl<u32> for NumberP<PRECISION> {
+    type Output = Self;
+
+    fn shl(self, rhs: u32) -> Self::Output {
+        self.checked_shl(rhs).unwrap()
+    }
+}
+
+impl<const PRECISION: u32> Shr<u32> for NumberP<PRECISION> {
+    type Output = Self;
+
+    fn shr(self, rhs: u32) -> Self::Output {
+        match rhs >= Self::BITS {
+            true => Self::ZERO,
+            false => Self(self.0 >> rhs as usize),
+        }
     }
 }
 
@@ -289,7 +1213,10 @@ impl<T: Into<U192>> Mul<T> for Number {
     type Output = Number;
 
     fn mul(self, rhs: T) -> Self::Output {
-        Self(self.0.mul(rhs.into()))
+        let rhs = rhs.into();
+        let result = Self(self.0.mul(rhs));
+        audit!("Number::mul_int", self, rhs, Some(&result));
+        result
     }
 }
 
@@ -297,41 +1224,111 @@ impl<T: Into<U192>> Div<T> for Number {
     type Output = Number;
 
     fn div(self, rhs: T) -> Self::Output {
-        Self(self.0.div(rhs.into()))
+        let rhs = rhs.into();
+        let result = Self(self.0.div(rhs));
+        audit!("Number::div_int", self, rhs, Some(&result),
+            rounded: !(self.0 % rhs).is_zero());
+        result
     }
 }
 
+impl_ref_ops!(Number);
+
+/// Panics on overflow, naming the index of the element that overflowed.
+/// Use `CheckedSum` or `SaturatingSum` to handle overflow instead.
 impl Sum for Number {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        iter.reduce(|a, b| a + b).unwrap_or(Self::ZERO)
+        iter.enumerate().fold(Self::ZERO, |total, (index, n)| {
+            total
+                .checked_add(&n)
+                .unwrap_or_else(|| panic!("overflow adding element {index} to sum"))
+        })
+    }
+}
+
+impl<'a> Sum<&'a Number> for Number {
+    fn sum<I: Iterator<Item = &'a Number>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// The number types that `CheckedSum` and `SaturatingSum` can add up
+pub trait Summand: Copy {
+    const ZERO: Self;
+
+    fn checked_add(&self, v: &Self) -> Option<Self>;
+
+    fn saturating_add(&self, v: &Self) -> Self;
+}
+
+impl Summand for Number {
+    const ZERO: Self = Number::ZERO;
+
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        Number::checked_add(self, v)
+    }
+
+    fn saturating_add(&self, v: &Self) -> Self {
+        Number::saturating_add(self, *v)
+    }
+}
+
+/// Sum an iterator of numbers, returning `None` on overflow instead of
+/// panicking like `Sum`
+pub trait CheckedSum: Iterator {
+    fn checked_sum(self) -> Option<Self::Item>;
+}
+
+impl<T: Summand, I: Iterator<Item = T>> CheckedSum for I {
+    fn checked_sum(mut self) -> Option<T> {
+        self.try_fold(T::ZERO, |total, n| Summand::checked_add(&total, &n))
+    }
+}
+
+/// Sum an iterator of numbers, clamping each partial sum to the range of
+/// the type
+pub trait SaturatingSum: Iterator {
+    fn saturating_sum(self) -> Self::Item;
+}
+
+impl<T: Summand, I: Iterator<Item = T>> SaturatingSum for I {
+    fn saturating_sum(self) -> T {
+        self.fold(T::ZERO, |total, n| Summand::saturating_add(&total, &n))
     }
 }
 
 #[cfg(feature = "traits")]
 impl num_traits::CheckedAdd for Number {
     fn checked_add(&self, v: &Self) -> Option<Self> {
-        self.0.checked_add(v.0).map(Number)
+        Number::checked_add(self, v)
     }
 }
 
 #[cfg(feature = "traits")]
 impl num_traits::CheckedDiv for Number {
     fn checked_div(&self, v: &Self) -> Option<Self> {
-        self.0.checked_div(v.0).map(Number)
+        self.0.checked_div(v.0).map(Self)
     }
 }
 
 #[cfg(feature = "traits")]
 impl num_traits::CheckedMul for Number {
     fn checked_mul(&self, v: &Self) -> Option<Self> {
-        self.0.checked_mul(v.0).map(Number)
+        self.0.checked_mul(v.0).map(Self)
     }
 }
 
 #[cfg(feature = "traits")]
 impl num_traits::CheckedSub for Number {
     fn checked_sub(&self, v: &Self) -> Option<Self> {
-        self.0.checked_sub(v.0).map(Number)
+        Number::checked_sub(self, v)
+    }
+}
+
+#[cfg(feature = "traits")]
+impl num_traits::SaturatingAdd for Number {
+    fn saturating_add(&self, v: &Self) -> Self {
+        Number::saturating_add(self, *v)
     }
 }
 
@@ -345,6 +1342,15 @@ mod tests {
         assert_eq!(Number::ZERO, Number::from(0u64));
     }
 
+    #[test]
+    fn zeroed_and_default_equal_zero() {
+        let bytes = [0u8; std::mem::size_of::<Number>()];
+
+        assert_eq!(Number::ZERO, bytemuck::pod_read_unaligned::<Number>(&bytes));
+        assert_eq!(Number::ZERO, Number::zeroed());
+        assert_eq!(Number::ZERO, Number::default());
+    }
+
     #[test]
     fn one_equals_one() {
         assert_eq!(Number::ONE, Number::from_decimal(1, 0));
@@ -379,6 +1385,204 @@ mod tests {
         );
     }
 
+    #[test]
+    fn assign_ops_match_binary_ops() {
+        let a = Number::from_decimal(15, -1);
+        let b = Number::from_decimal(4, 0);
+
+        let mut n = a;
+        n *= b;
+        assert_eq!(a * b, n);
+        n /= b;
+        assert_eq!(a, n);
+    }
+
+    #[test]
+    fn try_assign_ops() {
+        let mut n = Number::from_decimal(3, 0);
+
+        assert_eq!(Ok(()), n.try_div_assign(Number::from_decimal(4, 0)));
+        assert_eq!(Number::from_decimal(75, -2), n);
+        assert_eq!(Err(Error::DivideByZero), n.try_div_assign(Number::ZERO));
+
+        let mut max = Number::MAX;
+        assert_eq!(
+            Err(Error::OutOfRange),
+            max.try_mul_assign(Number::from_decimal(2, 0))
+        );
+        assert_eq!(Number::MAX, max);
+    }
+
+    #[test]
+    fn errors_with_context() {
+        let two = Number::from(2u64);
+
+        assert_eq!(Ok(Number::from(4u64)), two.try_mul(&two));
+        assert_eq!(Ok(Number::ONE), two.try_div(&two));
+
+        let err = Number::MAX.try_mul(&two).unwrap_err();
+        let Error::Context { context, error } = &err else {
+            panic!("no context: {err:?}");
+        };
+        assert_eq!("Number::try_mul", context.op);
+        assert_eq!(Error::OutOfRange, **error);
+
+        #[cfg(feature = "debug-errors")]
+        assert_eq!(
+            format!(
+                "Number::try_mul({}, 2.0) failed: The value is out of the representable range",
+                Number::MAX
+            ),
+            err.to_string()
+        );
+        #[cfg(not(feature = "debug-errors"))]
+        assert_eq!(
+            "Number::try_mul failed: The value is out of the representable range",
+            err.to_string()
+        );
+        #[cfg(not(feature = "debug-errors"))]
+        assert_eq!(None, context.operands);
+
+        assert_eq!(
+            Error::DivideByZero.context("Number::try_div", &two, &Number::ZERO),
+            two.try_div(&Number::ZERO).unwrap_err()
+        );
+        assert_eq!(
+            Error::OutOfRange.context("Number::try_sub", &Number::ZERO, &two),
+            Number::ZERO.try_sub(&two).unwrap_err()
+        );
+        assert_eq!(
+            Error::OutOfRange.context("Number::try_add", &Number::MAX, &two),
+            Number::MAX.try_add(&two).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn as_f64_relative_error() {
+        let values = [
+            Number::from_decimal(1, -15),
+            Number::from_decimal(1242, -3),
+            Number::from_decimal(123_456_789_012_345_678u64, -15),
+            Number::from_decimal(u64::MAX, 0),
+            Number::from_decimal(u64::MAX, -15),
+            Number::MAX,
+        ];
+
+        for value in values {
+            let exact: f64 = value.to_string().parse().unwrap();
+            let error = ((value.as_f64() - exact) / exact).abs();
+
+            assert!(error <= 2.0 * f64::EPSILON, "{value}: {error}");
+        }
+
+        assert_eq!(0.0, Number::ZERO.as_f64());
+        assert_eq!(1.5, Number::from_decimal(15, -1).as_f64());
+    }
+
+    #[test]
+    fn reference_ops() {
+        let a = Number::from_decimal(15, -1);
+        let b = Number::from_decimal(5, -1);
+
+        let (ra, rb) = (&a, &b);
+
+        assert_eq!(a + b, ra + rb);
+        assert_eq!(a - b, ra - b);
+        assert_eq!(a * b, a * rb);
+        assert_eq!(a / b, ra / rb);
+
+        let mut n = a;
+        n += rb;
+        n -= rb;
+        n *= rb;
+        n /= rb;
+        assert_eq!(a, n);
+
+        let values = [a, b];
+        assert_eq!(Number::from(2u64), values.iter().sum::<Number>());
+        assert_eq!(
+            Number::from(2u64),
+            values.iter().fold(Number::ZERO, |acc, n| acc + n)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow adding element 2 to sum")]
+    fn sum_overflow_names_element() {
+        let _: Number = [Number::MAX, Number::ZERO, Number::ONE].into_iter().sum();
+    }
+
+    #[test]
+    fn overflow_checked_sums() {
+        let deposits = [Number::ONE, Number::MAX, Number::ONE];
+
+        assert_eq!(None, deposits.into_iter().checked_sum());
+        assert_eq!(Number::MAX, deposits.into_iter().saturating_sum());
+        assert_eq!(
+            Some(Number::from(2u64)),
+            [Number::ONE, Number::ONE].into_iter().checked_sum()
+        );
+    }
+
+    #[test]
+    fn powi() {
+        let base = Number::from_decimal(15, -1);
+
+        assert_eq!(Number::ONE, base.powi(0));
+        assert_eq!(base, base.powi(1));
+        assert_eq!(Number::from_decimal(225, -2), base.powi(2));
+        assert_eq!(Number::from_decimal(50625, -4), base.powi(4));
+        assert_eq!(Number::from(1024u64), Number::from(2u64).powi(10));
+        assert_eq!(Number::ONE, Number::ZERO.powi(0));
+        assert_eq!(Number::ZERO, Number::ZERO.powi(3));
+
+        let rate = Number::from_decimal(11, -1).powi(10);
+        assert!((rate.as_f64() - 1.1f64.powi(10)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn checked_powi_overflow() {
+        assert_eq!(None, Number::from(u64::MAX).checked_powi(4));
+        assert!(Number::from(u64::MAX).checked_powi(2).is_some());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn pow_matches_powi() {
+        let base = Number::from_decimal(15, -1);
+
+        assert_eq!(base.powi(3), base.pow(3u64));
+    }
+
+    #[test]
+    fn logarithms() {
+        assert_eq!(Some(0), Number::ONE.log2_floor());
+        assert_eq!(Some(0), Number::ONE.log10_floor());
+        assert_eq!(Some(3), Number::from(15u64).log2_floor());
+        assert_eq!(Some(4), Number::from(16u64).log2_floor());
+        assert_eq!(Some(-1), Number::from_decimal(5, -1).log2_floor());
+        assert_eq!(Some(-50), Number::from_decimal(1, -15).log2_floor());
+        assert_eq!(Some(1), Number::from(99u64).log10_floor());
+        assert_eq!(Some(2), Number::from(100u64).log10_floor());
+        assert_eq!(Some(-15), Number::from_decimal(1, -15).log10_floor());
+        assert_eq!(Some(42), Number::MAX.log10_floor());
+        assert_eq!(Some(142), Number::MAX.log2_floor());
+        assert_eq!(None, Number::ZERO.log2_floor());
+        assert_eq!(0, Number::MAX.leading_zeros());
+    }
+
+    #[test]
+    fn mul_div_with_wide_intermediate() {
+        let big = Number::from(u64::MAX) * Number::from(u64::MAX);
+
+        assert_eq!(Number::from(u64::MAX), big / Number::from(u64::MAX));
+        assert_eq!(
+            Some(big),
+            Number::from(u64::MAX).checked_mul(&Number::from(u64::MAX))
+        );
+        assert_eq!(None, big.checked_mul(&big));
+    }
+
     #[test]
     fn multiply_by_u64() {
         assert_eq!(
@@ -408,6 +1612,361 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rounded_ties() {
+        let tie = Number::from_decimal(25, -1);
+        let above = Number::from_decimal(2500001, -6);
+
+        assert_eq!(3, tie.as_u64_rounded(0));
+        assert_eq!(3, tie.as_u64_rounded_with(0, Rounding::HalfUp));
+        assert_eq!(2, tie.as_u64_rounded_with(0, Rounding::HalfEven));
+        assert_eq!(
+            4,
+            Number::from_decimal(35, -1).as_u64_rounded_with(0, Rounding::HalfEven)
+        );
+        assert_eq!(3, above.as_u64_rounded_with(0, Rounding::HalfEven));
+        assert_eq!(
+            2,
+            Number::from(2u64).as_u64_rounded_with(0, Rounding::HalfUp)
+        );
+        assert_eq!(250, tie.as_u64_rounded_with(-2, Rounding::HalfEven));
+    }
+
+    #[test]
+    fn rescale() {
+        let amount = Number::from(1_234_567u64);
+        let rescaled = amount.rescale(6, 9, Rounding::Down).unwrap();
+        assert_eq!(Number::from(1_234_567_000u64), rescaled);
+        assert_eq!(Ok(amount), rescaled.rescale(9, 6, Rounding::Down));
+        assert_eq!(
+            Ok(Number::from_decimal(1_234_567, -2)),
+            amount.rescale(8, 6, Rounding::Down)
+        );
+
+        let smallest = Number::from_decimal(15, -15);
+        assert_eq!(
+            Ok(Number::from_decimal(1, -15)),
+            smallest.rescale(1, 0, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(2, -15)),
+            smallest.rescale(1, 0, Rounding::Up)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(2, -15)),
+            smallest.rescale(1, 0, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(2, -15)),
+            smallest.rescale(1, 0, Rounding::HalfEven)
+        );
+        assert_eq!(
+            Ok(Number::ZERO),
+            Number::MAX.rescale(255, 0, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(1, -15)),
+            Number::MAX.rescale(255, 0, Rounding::Up)
+        );
+
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number::MAX.rescale(0, 1, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number::ZERO),
+            Number::ZERO.rescale(0, 255, Rounding::Down)
+        );
+    }
+
+    #[test]
+    fn from_u64_with_rounding() {
+        // 18 decimals, three more than a `Number` can hold
+        let (value, exponent) = (1_234_567_890_123_456_789u64, -18);
+
+        assert_eq!(
+            Number::from_decimal(value, exponent),
+            Number::from_u64_rounded(value, exponent, Rounding::Down)
+        );
+        assert_eq!(
+            Number::from_decimal(1_234_567_890_123_457u64, -15),
+            Number::from_u64_rounded(value, exponent, Rounding::Up)
+        );
+        assert_eq!(
+            Number::from_decimal(1_234_567_890_123_457u64, -15),
+            Number::from_u64_rounded(value, exponent, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Number::from_decimal(1, -15),
+            Number::from_u64_rounded(1, -40, Rounding::Up)
+        );
+        assert_eq!(
+            Number::from(u64::MAX),
+            Number::from_u64_rounded(u64::MAX, 0, Rounding::Down)
+        );
+
+        assert_eq!(
+            Ok(Number::from_decimal(1_234_567_890_123u64, -12)),
+            Number::try_from_u64_exact(1_234_567_890_123, -12)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(1, -15)),
+            Number::try_from_u64_exact(1_000, -18)
+        );
+        assert!(Number::try_from_u64_exact(value, exponent).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number::try_from_u64_exact(1, i32::MAX)
+        );
+        assert_eq!(Ok(Number::ZERO), Number::try_from_u64_exact(0, i32::MIN));
+    }
+
+    #[test]
+    #[should_panic = "cannot convert from u64 due to overflow"]
+    fn from_u64_rounded_overflow() {
+        Number::from_u64_rounded(1, 50, Rounding::Down);
+    }
+
+    #[test]
+    fn from_128_bit_integers() {
+        assert_eq!(
+            Ok(Number::from_decimal(12_345u64, -3)),
+            Number::from_u128(12_345, -3)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(1, -15)),
+            Number::from_u128(1_999, -18)
+        );
+        assert_eq!(Ok(Number::ZERO), Number::from_u128(u128::MAX, -60));
+        assert_eq!(
+            Number::from_str("340282366920938463463374607431768211455").ok(),
+            Number::from_u128(u128::MAX, 0).ok()
+        );
+        assert_eq!(Err(Error::OutOfRange), Number::from_u128(u128::MAX, 20));
+
+        assert_eq!(Ok(Number::from(5u64)), Number::try_from_i128(50, -1));
+        assert_eq!(Ok(Number::ZERO), Number::try_from_i128(0, 0));
+        assert_eq!(
+            Err(Error::InvalidInput("negative value")),
+            Number::try_from_i128(-1, -3)
+        );
+        assert!(Number::try_from_i128(i128::MAX, 30).is_err());
+    }
+
+    #[test]
+    fn compare_with_integers() {
+        let utilization = Number::from_decimal(105, -2);
+
+        assert!(utilization > 1u64);
+        assert!(utilization < 2u64);
+        assert!(1u64 < utilization);
+        assert!(Number::ONE == 1u64);
+        assert!(1u64 == Number::ONE);
+        assert!(Number::from_decimal(1, -15) != 0u64);
+        assert!(Number::MAX > u64::MAX);
+        assert!(u64::MAX == Number::from(u64::MAX));
+        assert_eq!(
+            Some(std::cmp::Ordering::Greater),
+            Number::from(u64::MAX).partial_cmp(&(u64::MAX - 1))
+        );
+    }
+
+    #[test]
+    fn significant_figures() {
+        let n = Number::from_decimal(123_456_789u64, -6);
+
+        assert_eq!(
+            Ok(Number::from_decimal(123_456u64, -3)),
+            n.round_to_sig_figs(6, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(123_457u64, -3)),
+            n.round_to_sig_figs(6, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(Number::from(100u64)),
+            n.round_to_sig_figs(1, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(123_457u64, -9)),
+            Number::from_decimal(123_456_789u64, -12).round_to_sig_figs(6, Rounding::HalfEven)
+        );
+        assert_eq!(
+            Ok(Number::from(1_000_000u64)),
+            Number::from_decimal(9_999_995u64, -1).round_to_sig_figs(6, Rounding::HalfUp)
+        );
+        assert_eq!(Ok(n), n.round_to_sig_figs(9, Rounding::Up));
+        assert_eq!(Ok(n), n.round_to_sig_figs(u32::MAX, Rounding::Up));
+        assert_eq!(
+            Ok(Number::ZERO),
+            Number::ZERO.round_to_sig_figs(6, Rounding::Up)
+        );
+
+        assert!(n.round_to_sig_figs(0, Rounding::Down).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number::MAX.round_to_sig_figs(6, Rounding::Up)
+        );
+    }
+
+    #[test]
+    fn checked_integer_conversions() {
+        let n = Number::from_decimal(12_345u64, -3);
+
+        assert_eq!(Ok(12), n.try_as_i64(0));
+        assert_eq!(Ok(12_345), n.try_as_i64(-3));
+        assert_eq!(Ok(123_450_000), n.try_as_u128(-7));
+        assert_eq!(Ok(1), n.try_as_i128(1));
+        assert_eq!(Ok(0), n.try_as_u128(i32::MAX));
+        assert_eq!(Ok(i64::MAX), Number::from(i64::MAX as u64).try_as_i64(0));
+
+        let large = Number::from(u64::MAX);
+        assert_eq!(Err(Error::OutOfRange), large.try_as_i64(0));
+        assert_eq!(Ok(u64::MAX as u128 * 1_000), large.try_as_u128(-3));
+        assert_eq!(Err(Error::OutOfRange), large.try_as_u128(-20));
+        assert_eq!(Err(Error::OutOfRange), large.try_as_i128(-19));
+        assert_eq!(Err(Error::OutOfRange), Number::MAX.try_as_u128(0));
+        assert_eq!(Err(Error::OutOfRange), Number::ONE.try_as_u128(i32::MIN));
+    }
+
+    #[test]
+    fn ulp_steps() {
+        let price = Number::from_decimal(125u64, -2);
+        let ulp = Number::from_decimal(1u64, -15);
+
+        assert_eq!(ulp, price.ulp());
+        assert_eq!(Some(price + ulp), price.next_up());
+        assert_eq!(Some(price - ulp), price.next_down());
+        assert_eq!(Some(price), price.next_up().unwrap().next_down());
+        assert_eq!(Some(ulp), Number::ZERO.next_up());
+        assert_eq!(None, Number::ZERO.next_down());
+        assert_eq!(None, Number::MAX.next_up());
+        assert_eq!(
+            Some(NumberP::<6>::from_u192(U192::one())),
+            NumberP::<6>::ZERO.next_up()
+        );
+    }
+
+    #[test]
+    fn powers_of_two() {
+        let n = Number::from_decimal(3, -1);
+
+        assert_eq!(Number::from_decimal(12, -1), n << 2);
+        assert_eq!(Number::from_decimal(15, -2), n >> 1);
+        assert_eq!(Number::from_decimal(75, -3), n >> 2);
+        assert_eq!(Number::from_decimal(37_500_000_000_000u64, -15), n >> 3);
+        assert_eq!(Number::ZERO, n >> 192);
+        assert_eq!(Number::ZERO, Number::MAX >> u32::MAX);
+        assert_eq!(Number::ZERO, Number::ZERO << 1_000);
+        assert_eq!(Number::MAX, Number::MAX << 0);
+
+        assert_eq!(Ok(n << 10), n.scale_pow2(10));
+        assert_eq!(Ok(n >> 10), n.scale_pow2(-10));
+        assert_eq!(Ok(Number::ZERO), n.scale_pow2(i32::MIN));
+        assert_eq!(Err(Error::OutOfRange), n.scale_pow2(i32::MAX));
+        assert_eq!(Err(Error::OutOfRange), Number::MAX.scale_pow2(1));
+        assert_eq!(
+            None,
+            Number::ONE.checked_shl(Number::ONE.leading_zeros() + 1)
+        );
+        assert!(Number::ONE
+            .checked_shl(Number::ONE.leading_zeros())
+            .is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn shl_overflow() {
+        let _ = Number::MAX << 1;
+    }
+
+    #[test]
+    fn other_precisions() {
+        type Micro = NumberP<6>;
+
+        let n = Number::from_decimal(1_234_567, -6);
+        let micro: Micro = n.to_precision(Rounding::Down).unwrap();
+        assert_eq!(Micro::from_u192(U192::from(1_234_567u64)), micro);
+        assert_eq!("1.234567", micro.to_string());
+        assert_eq!(Ok(n), micro.to_precision::<15>(Rounding::Down));
+
+        let third = Number::ONE / Number::from(3u64);
+        let down: Micro = third.to_precision(Rounding::Down).unwrap();
+        let up: Micro = third.to_precision(Rounding::Up).unwrap();
+        assert_eq!("0.333333", down.to_string());
+        assert_eq!("0.333334", up.to_string());
+
+        let two = Micro::ONE + Micro::ONE;
+        assert_eq!("0.5", (Micro::ONE / two).to_string());
+        assert_eq!("4.0", (two * two).to_string());
+        assert_eq!("NumberP<6>(2.0; raw=2000000)", format!("{two:#?}"));
+
+        assert_eq!(
+            Ok(Number::MAX),
+            Number::MAX.to_precision::<15>(Rounding::Up)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number::MAX.to_precision::<18>(Rounding::Down)
+        );
+        assert_eq!(
+            Ok(NumberP::<0>::ONE),
+            Number::from_decimal(1, -15).to_precision::<0>(Rounding::Up)
+        );
+        assert_eq!("1.0", NumberP::<0>::ONE.to_string());
+    }
+
+    #[test]
+    fn normalize_to() {
+        let prices = [
+            (Number::from(25u64), 0),
+            (Number::from(2_500u64), 2),
+            (Number::from(25_000_000_000u64), 9),
+        ];
+
+        assert_eq!(
+            Ok(vec![Number::from(25_000_000u64); 3]),
+            Number::normalize_to(&prices, 6, Rounding::Down)
+        );
+    }
+
+    #[test]
+    fn token_amounts() {
+        let amount = Number::from_token_amount(1_234_567, 6);
+        assert_eq!(Number::from_decimal(1_234_567, -6), amount);
+        assert_eq!(Ok(1_234_567), amount.to_token_amount(6, Rounding::Down));
+        assert_eq!(Ok(123), amount.to_token_amount(2, Rounding::Down));
+        assert_eq!(Ok(124), amount.to_token_amount(2, Rounding::Up));
+        assert_eq!(Ok(123), amount.to_token_amount(2, Rounding::HalfUp));
+        assert_eq!(Ok(1), amount.to_token_amount(0, Rounding::HalfEven));
+
+        assert_eq!(
+            Number::from_decimal(184_467_440_737_095u64, -15),
+            Number::from_token_amount(u64::MAX, 20)
+        );
+        assert_eq!(Number::ZERO, Number::from_token_amount(1, 40));
+        assert_eq!(Number::ZERO, Number::from_token_amount(u64::MAX, u8::MAX));
+
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number::from(u64::MAX).to_token_amount(1, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            amount.to_token_amount(32, Rounding::Down)
+        );
+        assert_eq!(Ok(0), Number::ZERO.to_token_amount(32, Rounding::Up));
+        assert_eq!(Ok(0), Number::ZERO.to_token_amount(u8::MAX, Rounding::Up));
+        assert_eq!(
+            Ok(10u64.pow(17)),
+            Number::from_decimal(1, -15).to_token_amount(32, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number::from_decimal(1, -15).to_token_amount(35, Rounding::Down)
+        );
+    }
+
     #[test]
     fn to_string() {
         assert_eq!("1000.0", Number::from(1000).to_string());
@@ -415,6 +1974,55 @@ mod tests {
         assert_eq!("0.001", Number::from_decimal(1, -3).to_string());
     }
 
+    #[test]
+    fn string_round_trip() {
+        let values = [
+            Number::ZERO,
+            Number::ONE,
+            Number::from_decimal(1, -15),
+            Number::from_decimal(1242, -3),
+            Number::from_decimal(u64::MAX, -7),
+            Number::MAX,
+        ];
+
+        for value in values {
+            assert_eq!(Ok(value), value.to_string().parse());
+            assert_eq!(Ok(value), value.to_exact_string().parse());
+        }
+
+        let mut raw = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..1000 {
+            raw = raw.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let value = Number::from_u192(U192::from(raw) * U192::from(raw));
+            assert_eq!(Ok(value), value.to_string().parse());
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Number::from_decimal(15, -1)), "1.50".parse());
+        assert_eq!(Ok(Number::from(7u64)), "7".parse());
+        assert!("-1.5".parse::<Number>().is_err());
+        assert!("1.0000000000000001".parse::<Number>().is_err());
+        assert!("1e5".parse::<Number>().is_err());
+        assert!(".5".parse::<Number>().is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            format!("1{}", Number::MAX).parse::<Number>()
+        );
+    }
+
+    #[test]
+    fn debug_string() {
+        let number = Number::from_decimal(15, -1);
+
+        assert_eq!("1.5", format!("{number:?}"));
+        assert_eq!(
+            "Number(1.5; raw=1500000000000000)",
+            number.to_debug_string()
+        );
+    }
+
     #[test]
     fn into_bits() {
         let bits = Number::from_decimal(1242, -3).into_bits();
@@ -431,4 +2039,161 @@ mod tests {
         };
         assert_eq!(Number::from_decimal(100, 0).into_bits(), bits);
     }
+
+    #[test]
+    fn div_by_int() {
+        let n = Number::from_decimal(10u64, -15);
+
+        assert_eq!(Number::from_decimal(3u64, -15), n / 3u64);
+        assert_eq!(Number::from_decimal(4u64, -15), n.div_by_int_ceil(3u64));
+        assert_eq!(Number::from_decimal(3u64, -15), n.div_by_int_rounded(3u64));
+        assert_eq!(Number::from_decimal(3u64, -15), n.div_by_int_rounded(4u64));
+        assert_eq!(Number::from_decimal(2u64, -15), n.div_by_int_rounded(5u64));
+        assert_eq!(Number::from_decimal(2u64, -15), n.div_by_int_ceil(5u64));
+        assert_eq!(Number::from_decimal(2u64, -15), n.div_by_int_rounded(6u64));
+        assert_eq!(Number::MAX, Number::MAX.div_by_int_ceil(1u64));
+    }
+
+    #[test]
+    fn decimal_literal_macro() {
+        const RATE: Number = number!(0.0525);
+
+        assert_eq!(Number::from_decimal(525u64, -4), RATE);
+        assert_eq!(Number::from_decimal(125u64, -2), number!("1.25"));
+        assert_eq!(Number::from_decimal(1_000_001u64, 0), number!(1_000_001));
+        assert_eq!(
+            Number::from_decimal(123_456_789_012_345u64, -15),
+            number!(~0.123456789012345678)
+        );
+        assert_eq!(Number::ONE, number!(~"1.0000000000000009"));
+        assert_eq!(Number::ONE, number!(1.000000000000000000));
+        assert_eq!(
+            Number::MAX,
+            Number::from_decimal_str("6277101735386680763835789423207666416102355.444464034512895")
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn decimal_str_rejects_lost_precision() {
+        Number::from_decimal_str("0.0000000000000001");
+    }
+
+    #[test]
+    #[should_panic]
+    fn decimal_str_rejects_overflow() {
+        Number::from_decimal_str("6277101735386680763835789423207666416102355.444464034512896");
+    }
+
+    #[test]
+    fn bits_slices() {
+        let numbers = [Number::ONE, Number::MAX, Number::from_decimal(1242, -3)];
+        let bits = numbers.map(Number::into_bits);
+
+        assert_eq!(numbers.to_vec(), Number::from_bits_slice(&bits));
+
+        // copy into a buffer that is known to be aligned
+        let mut words = [0u64; 9];
+        bytemuck::cast_slice_mut(&mut words).copy_from_slice(bits.as_flattened());
+        let bytes: &[u8] = bytemuck::cast_slice(&words);
+
+        assert_eq!(Ok(&numbers[..]), Number::cast_slice(bytes));
+        assert_eq!(Ok(&numbers[1..2]), Number::cast_slice(&bytes[24..48]));
+        assert!(Number::cast_slice(&bytes[..30]).is_err());
+        assert!(Number::cast_slice(&bytes[4..28]).is_err());
+        assert_eq!(
+            Ok(&numbers[..]),
+            Number::cast_bits_slice(bytemuck::cast_slice(bytes))
+        );
+    }
+
+    #[test]
+    fn explicit_endian_bytes() {
+        let number = Number::from_decimal(1242, -3);
+        let mut expected = [0u8; 24];
+        expected[..8].copy_from_slice(&1_242_000_000_000_000u64.to_le_bytes());
+
+        assert_eq!(expected, number.to_le_bytes());
+        expected.reverse();
+        assert_eq!(expected, number.to_be_bytes());
+
+        assert_eq!(number, Number::from_le_bytes(number.to_le_bytes()));
+        assert_eq!(number, Number::from_be_bytes(number.to_be_bytes()));
+        assert_eq!(
+            Number::MAX,
+            Number::from_le_bytes(Number::MAX.to_le_bytes())
+        );
+    }
+
+    #[test]
+    fn full_mul() {
+        let (hi, lo) = Number::MAX.full_mul(&Number::MAX);
+        assert_eq!(U192::MAX - 1, hi);
+        assert_eq!(U192::one(), lo);
+
+        let (hi, lo) = Number::ONE.full_mul(&Number::from(2u64));
+        assert_eq!(U192::zero(), hi);
+        assert_eq!(ONE * ONE * 2, lo);
+    }
+
+    #[test]
+    fn midpoint_and_mean() {
+        let two = Number::from(2u64);
+        let three = Number::from(3u64);
+
+        assert_eq!(Number::from_decimal(25u64, -1), two.midpoint(three));
+        assert_eq!(Number::MAX, Number::MAX.midpoint(Number::MAX));
+        assert_eq!(
+            Number::from_u192(U192::MAX >> 1),
+            Number::MAX.midpoint(Number::ZERO)
+        );
+
+        assert_eq!(
+            Ok(Number::from_decimal(25u64, -1)),
+            Number::mean(&[two, three])
+        );
+        assert_eq!(Ok(Number::MAX), Number::mean(&[Number::MAX; 3]));
+        assert!(Number::mean(&[]).is_err());
+    }
+
+    #[test]
+    fn dust() {
+        let threshold = Number::DUST_EPSILON;
+
+        assert_eq!(Number::from_decimal(1u64, -12), threshold);
+        assert!(Number::ZERO.is_dust(threshold));
+        assert!(threshold.is_dust(threshold));
+        assert!(!Number::from_decimal(2u64, -12).is_dust(threshold));
+        assert_eq!(
+            Number::ZERO,
+            Number::from_decimal(5u64, -13).to_zero_if_dust(threshold)
+        );
+        assert_eq!(Number::ONE, Number::ONE.to_zero_if_dust(threshold));
+    }
+
+    #[test]
+    fn si_string() {
+        assert_eq!("1.24M", Number::from(1_235_000u64).to_si_string(3));
+        assert_eq!("0.333", (Number::ONE / Number::from(3u64)).to_si_string(3));
+    }
+
+    #[test]
+    #[cfg(feature = "traits")]
+    fn num_traits_checked_mul_div_use_raw_values() {
+        use num_traits::{CheckedDiv, CheckedMul};
+
+        let two = Number::from(2u64);
+        let three = Number::from(3u64);
+
+        assert_eq!(
+            Some(Number::from(6u64) * Number::from(10u64.pow(15))),
+            CheckedMul::checked_mul(&two, &three)
+        );
+        assert_eq!(
+            Some(Number::from_decimal(1, -15)),
+            CheckedDiv::checked_div(&three, &two)
+        );
+        assert_eq!(None, CheckedMul::checked_mul(&Number::MAX, &two));
+        assert_eq!(None, CheckedDiv::checked_div(&two, &Number::ZERO));
+    }
 }
@@ -2,9 +2,24 @@ use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssi
 
 use bytemuck::{Pod, Zeroable};
 
+use crate::bigint::U256;
+use crate::{div_rounded, Error, Rounding, Summand};
+
 const PRECISION: i32 = 10;
+
+#[cfg(not(feature = "binary-number128"))]
 const ONE: i128 = 10_000_000_000;
 
+/// With the `binary-number128` feature, values are scaled by 2^34 rather
+/// than 10^10, so that scaling in mul/div compiles down to shifts. The
+/// decimal `PRECISION` is still used for conversions and display.
+#[cfg(feature = "binary-number128")]
+const ONE: i128 = 1 << 34;
+
+/// The largest power of ten that fits in an `i128`
+#[cfg(feature = "binary-number128")]
+const MAX_POWER_OF_TEN: u32 = 38;
+
 const POWERS_OF_TEN: &[i128] = &[
     1,
     10,
@@ -19,10 +34,41 @@ const POWERS_OF_TEN: &[i128] = &[
     10_000_000_000,
     100_000_000_000,
     1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+    10_000_000_000_000_000_000,
+    100_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000,
+    10_000_000_000_000_000_000_000,
+    100_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000,
+    10_000_000_000_000_000_000_000_000,
+    100_000_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000_000,
+    10_000_000_000_000_000_000_000_000_000,
+    100_000_000_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000_000_000,
+    10_000_000_000_000_000_000_000_000_000_000,
+    100_000_000_000_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000_000_000_000,
+    10_000_000_000_000_000_000_000_000_000_000_000,
+    100_000_000_000_000_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000_000_000_000_000,
+    10_000_000_000_000_000_000_000_000_000_000_000_000,
+    100_000_000_000_000_000_000_000_000_000_000_000_000,
 ];
 
+/// Get 10^exponent as an `i128`, if it is representable
+pub fn ten_pow_i128(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
 /// A fixed-point decimal number 128 bits wide
-#[derive(Pod, Zeroable, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Pod, Zeroable, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(C)]
 pub struct Number128(i128);
 
@@ -33,21 +79,55 @@ impl Number128 {
     pub const MIN: Self = Self(i128::MIN);
     pub const BITS: u32 = i128::BITS;
 
+    /// A recommended dust threshold of 10^-8: a hundred of the smallest
+    /// decimal units, which covers the rounding error of a long chain of
+    /// operations
+    pub const DUST_EPSILON: Self = Self::from_decimal_str_lossy("0.00000001");
+
     /// Convert this number to fit in a u64
     ///
     /// The precision of the number in the u64 is based on the
     /// exponent provided.
     pub fn as_u64(&self, exponent: impl Into<i32>) -> u64 {
-        let extra_precision = PRECISION + exponent.into();
-        let prec_value = POWERS_OF_TEN[extra_precision.unsigned_abs() as usize];
+        let target_value = self
+            .to_decimal(exponent.into())
+            .unwrap_or_else(|| panic!("cannot convert to u64 due to overflow"));
 
-        let target_value = if extra_precision < 0 {
-            self.0 * prec_value
-        } else {
-            self.0 / prec_value
+        if target_value > std::u64::MAX as i128 {
+            panic!("cannot convert to u64 due to overflow");
+        }
+
+        if target_value < 0 {
+            panic!("cannot convert to u64 because value < 0");
+        }
+
+        target_value as u64
+    }
+
+    /// Convert this number to fit in a u64
+    ///
+    /// The precision of the number in the u64 is based on the
+    /// exponent provided.
+    ///
+    /// The result is rounded to the nearest one, based on the
+    /// target precision.
+    pub fn as_u64_rounded(&self, exponent: impl Into<i32>) -> u64 {
+        self.as_u64_rounded_with(exponent, Rounding::HalfUp)
+    }
+
+    /// Convert this number to fit in a u64, rounding at the target
+    /// precision as specified by `rounding`.
+    pub fn as_u64_rounded_with(&self, exponent: impl Into<i32>, rounding: Rounding) -> u64 {
+        let exponent = exponent.into();
+        let target_value = match self.to_decimal_rounded(exponent, rounding) {
+            Ok(value) => value,
+            Err(Error::ExponentOutOfRange(_)) => {
+                panic!("cannot convert to u64: exponent {exponent} is out of range")
+            }
+            Err(_) => panic!("cannot convert to u64 due to overflow"),
         };
 
-        if target_value > std::u64::MAX as i128 {
+        if target_value > u64::MAX as i128 {
             panic!("cannot convert to u64 due to overflow");
         }
 
@@ -58,21 +138,333 @@ impl Number128 {
         target_value as u64
     }
 
+    /// Convert this number to fit in an i64, truncating at the precision
+    /// given by the exponent, or return an error on overflow
+    pub fn try_as_i64(&self, exponent: impl Into<i32>) -> Result<i64, Error> {
+        i64::try_from(self.try_as_i128(exponent)?).map_err(|_| Error::OutOfRange)
+    }
+
+    /// Convert this number to fit in a u128, truncating at the precision
+    /// given by the exponent, or return an error on overflow or if the
+    /// result is negative
+    pub fn try_as_u128(&self, exponent: impl Into<i32>) -> Result<u128, Error> {
+        u128::try_from(self.try_as_i128(exponent)?).map_err(|_| Error::OutOfRange)
+    }
+
+    /// Convert this number to fit in an i128, truncating at the precision
+    /// given by the exponent, or return an error on overflow
+    pub fn try_as_i128(&self, exponent: impl Into<i32>) -> Result<i128, Error> {
+        self.to_decimal(exponent.into()).ok_or(Error::OutOfRange)
+    }
+
+    /// The value as a multiple of `10^exponent`, rounded as specified
+    fn to_decimal_rounded(self, exponent: i32, rounding: Rounding) -> Result<i128, Error> {
+        let scale =
+            ten_pow_i128(exponent.unsigned_abs()).ok_or(Error::ExponentOutOfRange(exponent))?;
+        let mode = DivRounding::new(rounding, self.0 < 0);
+
+        match exponent < 0 {
+            true => mul_div(self.0, scale, ONE, mode),
+            // anything smaller than the divisor's range rounds to zero
+            false => ONE
+                .checked_mul(scale)
+                .map_or(Some(0), |divisor| mul_div(self.0, 1, divisor, mode)),
+        }
+        .ok_or(Error::OutOfRange)
+    }
+
+    /// Convert a value with `from_decimals` of precision to `to_decimals`,
+    /// i.e. multiply by 10 to the power of the difference, rounding the
+    /// result to the precision of this type
+    pub fn rescale(
+        &self,
+        from_decimals: u8,
+        to_decimals: u8,
+        rounding: Rounding,
+    ) -> Result<Self, Error> {
+        let difference = (to_decimals as i32 - from_decimals as i32).unsigned_abs();
+        let scale = ten_pow_i128(difference);
+
+        if to_decimals >= from_decimals {
+            return match self.0 {
+                0 => Ok(*self),
+                _ => scale
+                    .and_then(|scale| self.0.checked_mul(scale))
+                    .map(Self)
+                    .ok_or(Error::OutOfRange),
+            };
+        }
+
+        let mode = DivRounding::new(rounding, self.0 < 0);
+        let result = match scale {
+            Some(scale) => mul_div(self.0, 1, scale, mode).unwrap(),
+            // the scale is larger than any value, so only rounding up can
+            // leave anything
+            None => match rounding {
+                Rounding::Up => self.0.signum(),
+                _ => 0,
+            },
+        };
+
+        Ok(Self(result))
+    }
+
+    /// Rescale values that each have their own decimals, e.g. prices of
+    /// tokens with different decimals, to the same number of decimals
+    pub fn normalize_to(
+        values: &[(Number128, u8)],
+        decimals: u8,
+        rounding: Rounding,
+    ) -> Result<Vec<Self>, Error> {
+        values
+            .iter()
+            .map(|(value, from_decimals)| value.rescale(*from_decimals, decimals, rounding))
+            .collect()
+    }
+
+    /// The number of leading zero bits in the raw representation
+    pub fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros()
+    }
+
+    /// The largest integer `k` such that `2^k <= self`, or `None` if the
+    /// number is not positive.
+    pub fn log2_floor(&self) -> Option<i32> {
+        if self.0 <= 0 {
+            return None;
+        }
+
+        let raw = self.0 as u128;
+        let one = ONE as u128;
+        let k = one.leading_zeros() as i32 - raw.leading_zeros() as i32;
+
+        // k is either exact or one too large
+        let fits = match k >= 0 {
+            true => one << k <= raw,
+            false => raw << -k >= one,
+        };
+
+        Some(if fits { k } else { k - 1 })
+    }
+
+    /// The largest integer `k` such that `10^k <= self`, or `None` if the
+    /// number is not positive.
+    pub fn log10_floor(&self) -> Option<i32> {
+        if self.0 <= 0 {
+            return None;
+        }
+
+        let digits = |n: i128| POWERS_OF_TEN.partition_point(|p| *p <= n) as i32;
+        let k = digits(self.0) - digits(ONE);
+
+        // k is either exact or one too large
+        let fits = match k >= 0 {
+            true => ONE
+                .checked_mul(POWERS_OF_TEN[k as usize])
+                .is_some_and(|n| n <= self.0),
+            false => match self.0.checked_mul(POWERS_OF_TEN[-k as usize]) {
+                Some(n) => n >= ONE,
+                None => true,
+            },
+        };
+
+        Some(if fits { k } else { k - 1 })
+    }
+
+    /// Round to `figures` significant decimal figures, e.g. to compare
+    /// prices regardless of their magnitude
+    pub fn round_to_sig_figs(&self, figures: u32, rounding: Rounding) -> Result<Number128, Error> {
+        if figures == 0 {
+            return Err(Error::InvalidInput("no significant figures"));
+        }
+        let magnitude = Self(self.0.checked_abs().unwrap_or(i128::MAX));
+        let Some(exponent) = magnitude.log10_floor() else {
+            return Ok(*self);
+        };
+
+        // the decimal exponent of the last significant figure
+        let last = exponent as i64 - (figures as i64 - 1);
+        if last <= -(PRECISION as i64) {
+            return Ok(*self);
+        }
+
+        let mantissa = self.to_decimal_rounded(last as i32, rounding)?;
+        Self::try_from_decimal(mantissa, last as i32)
+    }
+
     /// Convert this number to a f64
     pub fn as_f64(&self) -> f64 {
         // i128::{MAX|MIN} fits within f64
-        self.to_i128() as f64 / 10_000_000_000.0
+        self.to_i128() as f64 / ONE as f64
+    }
+
+    /// Parse a decimal literal such as `"-0.95"` in a const context
+    ///
+    /// Panics, which in a const is a compile error, if the string is not a
+    /// plain decimal, has more decimal places than can be stored, or
+    /// overflows. With the `binary-number128` feature, it also panics if the
+    /// value has no exact binary representation, e.g. `"0.95"`. See
+    /// `number128!` and `number_consts!`.
+    pub const fn from_decimal_str(s: &str) -> Self {
+        Self::parse_decimal_str(s, false)
+    }
+
+    /// Like `from_decimal_str`, but truncates any decimal places that
+    /// cannot be stored, or a fraction with no exact binary representation
+    pub const fn from_decimal_str_lossy(s: &str) -> Self {
+        Self::parse_decimal_str(s, true)
+    }
+
+    const fn parse_decimal_str(s: &str, lossy: bool) -> Self {
+        let bytes = s.as_bytes();
+        let (mut i, end) = crate::number::literal_bounds(bytes);
+        let negative = i < end && bytes[i] == b'-';
+        i += negative as usize;
+
+        let mut int: i128 = 0;
+        let mut int_digits = 0;
+        while i < end && bytes[i] != b'.' {
+            if bytes[i] != b'_' {
+                assert!(bytes[i].is_ascii_digit(), "not a decimal number");
+                int = match int.checked_mul(10) {
+                    Some(n) => n + (bytes[i] - b'0') as i128,
+                    None => panic!("decimal number is out of range"),
+                };
+                int_digits += 1;
+            }
+            i += 1;
+        }
+        assert!(int_digits > 0, "not a decimal number");
+
+        let mut frac: i128 = 0;
+        let mut places = 0;
+        i += 1;
+        while i < end {
+            if bytes[i] != b'_' {
+                assert!(bytes[i].is_ascii_digit(), "not a decimal number");
+                match places < PRECISION as usize {
+                    true => {
+                        frac = frac * 10 + (bytes[i] - b'0') as i128;
+                        places += 1;
+                    }
+                    false => assert!(lossy || bytes[i] == b'0', "too many decimal places"),
+                }
+            }
+            i += 1;
+        }
+
+        let int = match int.checked_mul(ONE) {
+            Some(n) => n,
+            None => panic!("decimal number is out of range"),
+        };
+        // always exact with decimal scaling, but not with binary scaling
+        assert!(
+            lossy || frac * ONE % POWERS_OF_TEN[places] == 0,
+            "decimal number cannot be represented exactly"
+        );
+        let frac = frac * ONE / POWERS_OF_TEN[places];
+        let value = match negative {
+            true => (-int).checked_sub(frac),
+            false => int.checked_add(frac),
+        };
+
+        match value {
+            Some(value) => Self(value),
+            None => panic!("decimal number is out of range"),
+        }
     }
 
     /// Convert another integer
+    ///
+    /// Panics if the exponent is out of range, or the result overflows.
     pub fn from_decimal(value: impl Into<i128>, exponent: impl Into<i32>) -> Self {
-        let extra_precision = PRECISION + exponent.into();
-        let prec_value = POWERS_OF_TEN[extra_precision.unsigned_abs() as usize];
+        match Self::try_from_decimal(value, exponent) {
+            Ok(n) => n,
+            Err(e) => panic!("cannot convert from decimal: {}", e),
+        }
+    }
 
-        if extra_precision < 0 {
-            Self(value.into() / prec_value)
-        } else {
-            Self(value.into() * prec_value)
+    /// Convert another integer, returning an error if the exponent is out
+    /// of range or the result overflows
+    pub fn try_from_decimal(
+        value: impl Into<i128>,
+        exponent: impl Into<i32>,
+    ) -> Result<Self, Error> {
+        Self::scale_decimal(value.into(), exponent.into()).map(Self)
+    }
+
+    /// Scale a decimal value into the internal representation
+    ///
+    /// Exponents too small for the power of ten to fit in an `i128` scale
+    /// every value to zero, and exponents too large are an error.
+    #[cfg(not(feature = "binary-number128"))]
+    fn scale_decimal(value: i128, exponent: i32) -> Result<i128, Error> {
+        let extra_precision = PRECISION
+            .checked_add(exponent)
+            .ok_or(Error::ExponentOutOfRange(exponent))?;
+
+        match (
+            extra_precision < 0,
+            ten_pow_i128(extra_precision.unsigned_abs()),
+        ) {
+            (true, Some(prec_value)) => Ok(value / prec_value),
+            (true, None) => Ok(0),
+            (false, Some(prec_value)) => value.checked_mul(prec_value).ok_or(Error::OutOfRange),
+            (false, None) => Err(Error::ExponentOutOfRange(exponent)),
+        }
+    }
+
+    #[cfg(feature = "binary-number128")]
+    fn scale_decimal(value: i128, exponent: i32) -> Result<i128, Error> {
+        if exponent < 0 {
+            // divide in two steps when the power of ten doesn't fit, which
+            // truncates the same as dividing once
+            let places = exponent.unsigned_abs();
+            let first = places.min(MAX_POWER_OF_TEN);
+            let scaled = mul_div(
+                value,
+                ONE,
+                POWERS_OF_TEN[first as usize],
+                DivRounding::Truncate,
+            )
+            .ok_or(Error::OutOfRange)?;
+
+            return Ok(ten_pow_i128(places - first).map_or(0, |p| scaled / p));
+        }
+
+        let prec_value =
+            ten_pow_i128(exponent.unsigned_abs()).ok_or(Error::ExponentOutOfRange(exponent))?;
+
+        value
+            .checked_mul(prec_value)
+            .and_then(|v| v.checked_mul(ONE))
+            .ok_or(Error::OutOfRange)
+    }
+
+    /// Convert the internal representation into an integer with the given
+    /// decimal exponent, truncating any remainder, or `None` if it overflows
+    #[cfg(not(feature = "binary-number128"))]
+    fn to_decimal(self, exponent: i32) -> Option<i128> {
+        let extra_precision = PRECISION.saturating_add(exponent);
+        let prec_value = ten_pow_i128(extra_precision.unsigned_abs());
+
+        match (extra_precision < 0, prec_value) {
+            (true, prec_value) => prec_value.and_then(|p| self.0.checked_mul(p)),
+            (false, Some(prec_value)) => Some(self.0 / prec_value),
+            (false, None) => Some(0),
+        }
+    }
+
+    #[cfg(feature = "binary-number128")]
+    fn to_decimal(self, exponent: i32) -> Option<i128> {
+        let prec_value = ten_pow_i128(exponent.unsigned_abs());
+
+        match (exponent < 0, prec_value) {
+            (true, prec_value) => {
+                prec_value.and_then(|p| mul_div(self.0, p, ONE, DivRounding::Truncate))
+            }
+            (false, Some(prec_value)) => Some(self.0 / ONE / prec_value),
+            (false, None) => Some(0),
         }
     }
 
@@ -83,6 +475,9 @@ impl Number128 {
 
     /// Get the underlying 128-bit representation in bytes.
     /// Uses the target endianness of the caller
+    ///
+    /// Prefer `to_le_bytes` or `to_be_bytes`, as this will be deprecated in
+    /// the next major release.
     pub fn into_bits(self) -> [u8; 16] {
         self.0.to_ne_bytes()
     }
@@ -90,10 +485,58 @@ impl Number128 {
     /// Read a number from a raw 128-bit representation, which was previously
     /// returned by a call to `into_bits`.
     /// Uses the target endianness of the caller
+    ///
+    /// Prefer `from_le_bytes` or `from_be_bytes`, as this will be deprecated
+    /// in the next major release.
     pub fn from_bits(bits: [u8; 16]) -> Self {
         Self(i128::from_ne_bytes(bits))
     }
 
+    /// Get the underlying 128-bit representation as little-endian bytes
+    pub fn to_le_bytes(self) -> [u8; 16] {
+        self.0.to_le_bytes()
+    }
+
+    /// Read a number from its little-endian 128-bit representation
+    pub fn from_le_bytes(bytes: [u8; 16]) -> Self {
+        Self(i128::from_le_bytes(bytes))
+    }
+
+    /// Get the underlying 128-bit representation as big-endian bytes
+    pub fn to_be_bytes(self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// Read a number from its big-endian 128-bit representation
+    pub fn from_be_bytes(bytes: [u8; 16]) -> Self {
+        Self(i128::from_be_bytes(bytes))
+    }
+
+    /// Format the exact value as a string that parses back to the same number
+    ///
+    /// With decimal scaling this is the same as `to_string`. With the
+    /// `binary-number128` feature, values that have no short decimal form are
+    /// printed as a fraction of the raw value over the scale.
+    pub fn to_exact_string(&self) -> String {
+        let decimal = self.to_string();
+
+        match decimal.parse::<Self>() {
+            Ok(parsed) if parsed == *self => decimal,
+            _ => format!("{}/{}", self.0, ONE),
+        }
+    }
+
+    /// Format the value along with its raw representation, for logging
+    pub fn to_debug_string(&self) -> String {
+        format!("{self:#?}")
+    }
+
+    /// Format with a thousands suffix, e.g. `1.24M`, rounded half away from
+    /// zero to at least the given number of significant digits
+    pub fn to_si_string(&self, significant_digits: u32) -> String {
+        crate::display::format_si(&self.to_string(), significant_digits as usize)
+    }
+
     /// Get the underlying i128 value
     pub fn to_i128(self) -> i128 {
         self.0
@@ -103,390 +546,1828 @@ impl Number128 {
     pub fn from_i128(value: i128) -> Self {
         Self(value)
     }
-}
 
-impl std::fmt::Debug for Number128 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <Self as std::fmt::Display>::fmt(self, f)
+    /// Add two numbers, returning `None` on overflow
+    pub fn checked_add(&self, v: &Number128) -> Option<Number128> {
+        let result = self.0.checked_add(v.0).map(Self);
+        audit!("Number128::checked_add", self, v, result.as_ref());
+        result
     }
-}
 
-impl std::fmt::Display for Number128 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // todo optimize
-        let rem = self.0 % ONE;
-        let decimal_digits = PRECISION as usize;
-        // convert to abs to remove sign
-        let rem_str = rem.abs().to_string();
-        // regular padding like {:010} doesn't work with i128
-        let decimals = "0".repeat(decimal_digits - rem_str.len()) + &*rem_str;
-        let stripped_decimals = decimals.trim_end_matches('0');
-        let pretty_decimals = if stripped_decimals.is_empty() {
-            "0"
-        } else {
-            stripped_decimals
-        };
-        if self.0 < -ONE {
-            let int = self.0 / ONE;
-            write!(f, "{}.{}", int, pretty_decimals)?;
-        } else if self.0 < 0 {
-            write!(f, "-0.{}", pretty_decimals)?;
-        } else if self.0 < ONE {
-            write!(f, "0.{}", pretty_decimals)?;
-        } else {
-            let int = self.0 / ONE;
-            write!(f, "{}.{}", int, pretty_decimals)?;
-        }
-        Ok(())
+    /// Subtract two numbers, returning `None` on overflow
+    pub fn checked_sub(&self, v: &Number128) -> Option<Number128> {
+        let result = self.0.checked_sub(v.0).map(Self);
+        audit!("Number128::checked_sub", self, v, result.as_ref());
+        result
     }
-}
 
-impl Add<Number128> for Number128 {
-    type Output = Self;
-
-    fn add(self, rhs: Number128) -> Self::Output {
-        Self(self.0.checked_add(rhs.0).unwrap())
+    /// Negate a number, returning `None` on overflow, which only happens
+    /// for `MIN`
+    pub fn checked_neg(&self) -> Option<Number128> {
+        let result = self.0.checked_neg().map(Self);
+        audit!("Number128::checked_neg", self, (), result.as_ref());
+        result
     }
-}
 
-impl AddAssign<Number128> for Number128 {
-    fn add_assign(&mut self, rhs: Number128) {
-        self.0 = self.0.checked_add(rhs.0).unwrap();
+    /// The smallest step between two numbers, i.e. one raw unit
+    pub fn ulp(&self) -> Number128 {
+        Self(1)
     }
-}
 
-impl Sub<Number128> for Number128 {
-    type Output = Self;
-
-    fn sub(self, rhs: Number128) -> Self::Output {
-        Self(self.0.checked_sub(rhs.0).unwrap())
+    /// The smallest number greater than this one, or `None` for `MAX`
+    pub fn next_up(&self) -> Option<Number128> {
+        self.0.checked_add(1).map(Self)
     }
-}
 
-impl SubAssign<Number128> for Number128 {
-    fn sub_assign(&mut self, rhs: Number128) {
-        self.0 = self.0.checked_sub(rhs.0).unwrap();
+    /// The largest number less than this one, or `None` for `MIN`
+    pub fn next_down(&self) -> Option<Number128> {
+        self.0.checked_sub(1).map(Self)
     }
-}
 
-impl Mul<Number128> for Number128 {
-    type Output = Number128;
+    /// Negate a number, wrapping `MIN` around to itself
+    pub fn wrapping_neg(&self) -> Number128 {
+        Self(self.0.wrapping_neg())
+    }
 
-    fn mul(self, rhs: Number128) -> Self::Output {
-        Self(self.0.checked_mul(rhs.0).unwrap().div(ONE))
+    /// Add two numbers, wrapping around at the bounds of the type
+    ///
+    /// Wrapping is only appropriate for values that are never read on their
+    /// own, e.g. a cumulative funding index where only the difference
+    /// between two readings matters. The difference, taken with
+    /// `wrapping_sub`, is correct as long as the true difference fits.
+    pub fn wrapping_add(&self, rhs: &Number128) -> Number128 {
+        Self(self.0.wrapping_add(rhs.0))
     }
-}
 
-impl MulAssign<Number128> for Number128 {
-    fn mul_assign(&mut self, rhs: Number128) {
-        self.0 = self.0 * rhs.0 / ONE;
+    /// Subtract two numbers, wrapping around at the bounds of the type
+    pub fn wrapping_sub(&self, rhs: &Number128) -> Number128 {
+        Self(self.0.wrapping_sub(rhs.0))
     }
-}
 
-impl Div<Number128> for Number128 {
-    type Output = Number128;
+    /// Multiply two numbers, truncating towards zero, and wrapping the
+    /// result around at the bounds of the type
+    pub fn wrapping_mul(&self, rhs: &Number128) -> Number128 {
+        let product = U256::from(self.0.unsigned_abs()) * U256::from(rhs.0.unsigned_abs());
+        let U256([lo, hi, ..]) = product / U256::from(ONE as u128);
+        let magnitude = ((hi as u128) << 64 | lo as u128) as i128;
 
-    fn div(self, rhs: Number128) -> Self::Output {
-        Self(self.0.mul(ONE).div(rhs.0))
+        match (self.0 < 0) != (rhs.0 < 0) {
+            true => Self(magnitude.wrapping_neg()),
+            false => Self(magnitude),
+        }
     }
-}
 
-impl DivAssign<Number128> for Number128 {
-    fn div_assign(&mut self, rhs: Number128) {
-        self.0 = self.0 * ONE / rhs.0;
+    /// Multiply two numbers, returning `None` on overflow
+    pub fn checked_mul(&self, v: &Number128) -> Option<Number128> {
+        let result = mul_div(self.0, v.0, ONE, DivRounding::Truncate).map(Self);
+        audit!("Number128::checked_mul", self, v, result.as_ref(),
+            rounded: is_rounded(self.0, v.0, ONE));
+        result
     }
-}
 
-impl<T: Into<i128>> Mul<T> for Number128 {
-    type Output = Number128;
+    /// Divide two numbers, returning `None` on overflow or division by zero
+    pub fn checked_div(&self, v: &Number128) -> Option<Number128> {
+        let result = mul_div(self.0, ONE, v.0, DivRounding::Truncate).map(Self);
+        audit!("Number128::checked_div", self, v, result.as_ref(),
+            rounded: is_rounded(self.0, ONE, v.0));
+        result
+    }
 
-    fn mul(self, rhs: T) -> Self::Output {
-        Self(self.0.mul(rhs.into()))
+    /// Compute `self * mul / div` without losing precision in the
+    /// intermediate product, returning `None` on overflow or division by zero
+    pub fn checked_mul_div(&self, mul: &Number128, div: &Number128) -> Option<Number128> {
+        let result = mul_div(self.0, mul.0, div.0, DivRounding::Truncate).map(Self);
+        audit!("Number128::checked_mul_div", self, (mul, div), result.as_ref(),
+            rounded: is_rounded(self.0, mul.0, div.0));
+        result
     }
-}
 
-impl<T: Into<i128>> Div<T> for Number128 {
-    type Output = Number128;
+    /// Compute `self * mul / div` like `checked_mul_div`, rounding as
+    /// specified instead of truncating
+    pub fn checked_mul_div_rounded(
+        &self,
+        mul: &Number128,
+        div: &Number128,
+        rounding: Rounding,
+    ) -> Option<Number128> {
+        let negative = (self.0 < 0) ^ (mul.0 < 0) ^ (div.0 < 0);
+        let mode = DivRounding::new(rounding, negative);
+
+        let result = mul_div(self.0, mul.0, div.0, mode).map(Self);
+        audit!("Number128::checked_mul_div_rounded", self, (mul, div), result.as_ref(),
+            rounded: is_rounded(self.0, mul.0, div.0));
+        result
+    }
 
-    fn div(self, rhs: T) -> Self::Output {
-        Self(self.0.div(rhs.into()))
+    /// Multiply the underlying values without overflow or scaling, returning
+    /// the high and low halves of the 256-bit two's complement product as
+    /// `(hi, lo)`.
+    ///
+    /// The product is scaled by `ONE` twice, so divide it by `ONE` to get
+    /// the underlying value of the result.
+    pub fn full_mul(&self, rhs: &Number128) -> (i128, u128) {
+        let product = U256::from(self.0.unsigned_abs()) * U256::from(rhs.0.unsigned_abs());
+        let product = match (self.0 < 0) != (rhs.0 < 0) {
+            true => product.overflowing_neg().0,
+            false => product,
+        };
+        let U256(limbs) = product;
+
+        (
+            ((limbs[3] as u128) << 64 | limbs[2] as u128) as i128,
+            (limbs[1] as u128) << 64 | limbs[0] as u128,
+        )
     }
-}
 
-impl<T: Into<i128>> From<T> for Number128 {
-    fn from(n: T) -> Self {
-        Self::from_i128(n.into())
+    /// Add two numbers, returning an error with the operands on overflow
+    pub fn try_add(&self, rhs: &Number128) -> Result<Number128, Error> {
+        self.checked_add(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number128::try_add", self, rhs))
     }
-}
 
-#[cfg(feature = "traits")]
-impl num_traits::CheckedAdd for Number128 {
-    fn checked_add(&self, v: &Self) -> Option<Self> {
-        self.0.checked_add(v.0).map(|n| n.into())
+    /// Subtract two numbers, returning an error with the operands on
+    /// overflow
+    pub fn try_sub(&self, rhs: &Number128) -> Result<Number128, Error> {
+        self.checked_sub(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number128::try_sub", self, rhs))
     }
-}
 
-#[cfg(feature = "traits")]
-impl num_traits::CheckedDiv for Number128 {
-    fn checked_div(&self, v: &Self) -> Option<Self> {
-        self.0.checked_div(v.0).map(|n| n.into())
+    /// Multiply two numbers, returning an error with the operands on
+    /// overflow
+    pub fn try_mul(&self, rhs: &Number128) -> Result<Number128, Error> {
+        self.checked_mul(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number128::try_mul", self, rhs))
     }
-}
 
-#[cfg(feature = "traits")]
-impl num_traits::CheckedMul for Number128 {
-    fn checked_mul(&self, v: &Self) -> Option<Self> {
-        self.0.checked_mul(v.0).map(|n| n.into())
+    /// Divide two numbers, returning an error with the operands on overflow
+    /// or division by zero
+    pub fn try_div(&self, rhs: &Number128) -> Result<Number128, Error> {
+        if *rhs == Number128::ZERO {
+            return Err(Error::DivideByZero.context("Number128::try_div", self, rhs));
+        }
+
+        self.checked_div(rhs)
+            .ok_or_else(|| Error::OutOfRange.context("Number128::try_div", self, rhs))
     }
-}
 
-#[cfg(feature = "traits")]
-impl num_traits::CheckedSub for Number128 {
-    fn checked_sub(&self, v: &Self) -> Option<Self> {
-        self.0.checked_sub(v.0).map(|n| n.into())
+    /// Multiply in place, leaving the value unchanged on overflow
+    pub fn try_mul_assign(&mut self, rhs: Number128) -> Result<(), Error> {
+        *self = self.checked_mul(&rhs).ok_or(Error::OutOfRange)?;
+        Ok(())
     }
-}
 
-impl Neg for Number128 {
-    type Output = Number128;
+    /// Divide in place, leaving the value unchanged on overflow or
+    /// division by zero
+    pub fn try_div_assign(&mut self, rhs: Number128) -> Result<(), Error> {
+        if rhs == Number128::ZERO {
+            return Err(Error::DivideByZero);
+        }
 
-    fn neg(self) -> Self::Output {
-        Number128(-self.0)
+        *self = self.checked_div(&rhs).ok_or(Error::OutOfRange)?;
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Multiply, rounding the result down (towards negative infinity)
+    ///
+    /// Panics on overflow.
+    pub fn mul_floor(self, rhs: Number128) -> Number128 {
+        self.checked_mul_floor(rhs)
+            .unwrap_or_else(|| panic!("cannot multiply due to overflow"))
+    }
 
-    #[test]
-    fn zero_equals_zero() {
-        assert_eq!(Number128::ZERO, Number128::from_decimal(0, 0));
+    /// Like `mul_floor`, returning `None` on overflow
+    pub fn checked_mul_floor(self, rhs: Number128) -> Option<Number128> {
+        let result = mul_div(self.0, rhs.0, ONE, DivRounding::Floor).map(Self);
+        audit!("Number128::mul_floor", self, rhs, result.as_ref(),
+            rounded: is_rounded(self.0, rhs.0, ONE));
+        result
     }
 
-    #[test]
-    fn one_equals_one() {
-        assert_eq!(Number128::ONE, Number128::from_decimal(1, 0));
+    /// Multiply, rounding the result up (towards positive infinity)
+    ///
+    /// Panics on overflow.
+    pub fn mul_ceil(self, rhs: Number128) -> Number128 {
+        self.checked_mul_ceil(rhs)
+            .unwrap_or_else(|| panic!("cannot multiply due to overflow"))
     }
 
-    #[test]
-    fn negative_one_equals_negative_one() {
-        assert_eq!(-Number128::ONE, Number128::from_decimal(-1, 0));
+    /// Like `mul_ceil`, returning `None` on overflow
+    pub fn checked_mul_ceil(self, rhs: Number128) -> Option<Number128> {
+        let result = mul_div(self.0, rhs.0, ONE, DivRounding::Ceil).map(Self);
+        audit!("Number128::mul_ceil", self, rhs, result.as_ref(),
+            rounded: is_rounded(self.0, rhs.0, ONE));
+        result
     }
 
-    #[test]
-    fn one_plus_one_equals_two() {
-        assert_eq!(
-            Number128::from_decimal(2, 0),
-            Number128::ONE + Number128::ONE
-        );
+    /// Multiply, rounding the result to the nearest value, with ties
+    /// rounded away from zero
+    ///
+    /// Panics on overflow.
+    pub fn mul_round(self, rhs: Number128) -> Number128 {
+        self.checked_mul_round(rhs)
+            .unwrap_or_else(|| panic!("cannot multiply due to overflow"))
     }
 
-    #[test]
-    fn one_minus_one_equals_zero() {
-        assert_eq!(Number128::ONE - Number128::ONE, Number128::ZERO);
+    /// Like `mul_round`, returning `None` on overflow
+    pub fn checked_mul_round(self, rhs: Number128) -> Option<Number128> {
+        let result = mul_div(self.0, rhs.0, ONE, DivRounding::Nearest).map(Self);
+        audit!("Number128::mul_round", self, rhs, result.as_ref(),
+            rounded: is_rounded(self.0, rhs.0, ONE));
+        result
     }
 
-    #[test]
-    fn one_times_one_equals_one() {
-        assert_eq!(Number128::ONE, Number128::ONE * Number128::ONE);
+    /// Divide, rounding the result down (towards negative infinity)
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_floor(self, rhs: Number128) -> Number128 {
+        self.checked_div_floor(rhs)
+            .unwrap_or_else(|| panic!("cannot divide due to overflow or division by zero"))
     }
 
-    #[test]
-    fn one_divided_by_one_equals_one() {
-        assert_eq!(Number128::ONE, Number128::ONE / Number128::ONE);
+    /// Like `div_floor`, returning `None` on overflow
+    /// or division by zero
+    pub fn checked_div_floor(self, rhs: Number128) -> Option<Number128> {
+        let result = mul_div(self.0, ONE, rhs.0, DivRounding::Floor).map(Self);
+        audit!("Number128::div_floor", self, rhs, result.as_ref(),
+            rounded: is_rounded(self.0, ONE, rhs.0));
+        result
+    }
+
+    /// Divide, rounding the result up (towards positive infinity)
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_ceil(self, rhs: Number128) -> Number128 {
+        self.checked_div_ceil(rhs)
+            .unwrap_or_else(|| panic!("cannot divide due to overflow or division by zero"))
+    }
+
+    /// Like `div_ceil`, returning `None` on overflow
+    /// or division by zero
+    pub fn checked_div_ceil(self, rhs: Number128) -> Option<Number128> {
+        let result = mul_div(self.0, ONE, rhs.0, DivRounding::Ceil).map(Self);
+        audit!("Number128::div_ceil", self, rhs, result.as_ref(),
+            rounded: is_rounded(self.0, ONE, rhs.0));
+        result
+    }
+
+    /// Divide, rounding the result to the nearest value, with ties
+    /// rounded away from zero
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_round(self, rhs: Number128) -> Number128 {
+        self.checked_div_round(rhs)
+            .unwrap_or_else(|| panic!("cannot divide due to overflow or division by zero"))
+    }
+
+    /// Like `div_round`, returning `None` on overflow
+    /// or division by zero
+    pub fn checked_div_round(self, rhs: Number128) -> Option<Number128> {
+        let result = mul_div(self.0, ONE, rhs.0, DivRounding::Nearest).map(Self);
+        audit!("Number128::div_round", self, rhs, result.as_ref(),
+            rounded: is_rounded(self.0, ONE, rhs.0));
+        result
+    }
+
+    /// Divide by an integer, rounding the result up (towards positive
+    /// infinity). Dividing by an integer with `/` rounds towards zero.
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_by_int_ceil(self, rhs: impl Into<i128>) -> Number128 {
+        let rhs = rhs.into();
+        let result = Self(mul_div(self.0, 1, rhs, DivRounding::Ceil).unwrap());
+        audit!("Number128::div_int_ceil", self, rhs, Some(&result),
+            rounded: self.0 % rhs != 0);
+        result
+    }
+
+    /// Divide by an integer, rounding the result to the nearest value, with
+    /// ties rounded away from zero
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_by_int_rounded(self, rhs: impl Into<i128>) -> Number128 {
+        let rhs = rhs.into();
+        let result = Self(mul_div(self.0, 1, rhs, DivRounding::Nearest).unwrap());
+        audit!("Number128::div_int_rounded", self, rhs, Some(&result),
+            rounded: self.0 % rhs != 0);
+        result
+    }
+
+    /// The value halfway between two numbers, rounded toward zero, computed
+    /// without overflowing
+    pub fn midpoint(&self, other: Number128) -> Number128 {
+        let (a, b) = (self.0, other.0);
+        let floor = (a >> 1) + (b >> 1) + (a & b & 1);
+
+        match floor < 0 && (a ^ b) & 1 == 1 {
+            true => Self(floor + 1),
+            false => Self(floor),
+        }
+    }
+
+    /// Check if the magnitude of the value is at most `threshold`, e.g.
+    /// `DUST_EPSILON`, so that it should be treated as zero
+    pub fn is_dust(&self, threshold: Number128) -> bool {
+        threshold.0 >= 0 && self.0.unsigned_abs() <= threshold.0 as u128
+    }
+
+    /// Replace the value with zero if it is dust
+    pub fn to_zero_if_dust(self, threshold: Number128) -> Number128 {
+        match self.is_dust(threshold) {
+            true => Number128::ZERO,
+            false => self,
+        }
+    }
+
+    /// Choose `a` if `cond` is true, or `b` otherwise, without branching
+    ///
+    /// The arithmetic operations are not constant time: multiplication and
+    /// division take different paths depending on the magnitude of their
+    /// operands. `select`, `branchless_max` and `branchless_min` are written
+    /// to avoid data-dependent branches, although the compiler does not
+    /// guarantee this, so check the generated code where it matters.
+    pub fn select(cond: bool, a: Number128, b: Number128) -> Number128 {
+        let mask = -(cond as i128);
+        Self((a.0 & mask) | (b.0 & !mask))
+    }
+
+    /// The larger of two numbers, computed without branching
+    pub fn branchless_max(self, other: Number128) -> Number128 {
+        let lt = lt_mask(self.0, other.0);
+        Self((other.0 & lt) | (self.0 & !lt))
+    }
+
+    /// The smaller of two numbers, computed without branching
+    pub fn branchless_min(self, other: Number128) -> Number128 {
+        let lt = lt_mask(self.0, other.0);
+        Self((self.0 & lt) | (other.0 & !lt))
+    }
+}
+
+/// All ones if `a < b`, or zero otherwise, computed without branching
+fn lt_mask(a: i128, b: i128) -> i128 {
+    let difference = a.wrapping_sub(b);
+
+    // the sign of the difference, corrected for overflow
+    (difference ^ ((a ^ b) & (difference ^ a))) >> 127
+}
+
+#[derive(Clone, Copy)]
+enum DivRounding {
+    /// Towards zero
+    Truncate,
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceil,
+    /// To the nearest value, with ties away from zero
+    Nearest,
+    /// To the nearest value, with ties to the even value
+    NearestEven,
+}
+
+impl DivRounding {
+    /// The mode for a `Rounding` of a result with the given sign
+    fn new(rounding: Rounding, negative: bool) -> Self {
+        match rounding {
+            Rounding::Down => DivRounding::Truncate,
+            Rounding::Up if negative => DivRounding::Floor,
+            Rounding::Up => DivRounding::Ceil,
+            Rounding::HalfUp => DivRounding::Nearest,
+            Rounding::HalfEven => DivRounding::NearestEven,
+        }
+    }
+
+    /// The rounding of the magnitude of a result with the given sign
+    fn of_magnitude(self, negative: bool) -> Rounding {
+        match self {
+            DivRounding::Truncate => Rounding::Down,
+            DivRounding::Floor if negative => Rounding::Up,
+            DivRounding::Floor => Rounding::Down,
+            DivRounding::Ceil if negative => Rounding::Down,
+            DivRounding::Ceil => Rounding::Up,
+            DivRounding::Nearest => Rounding::HalfUp,
+            DivRounding::NearestEven => Rounding::HalfEven,
+        }
+    }
+}
+
+/// Whether `a * b / c` is inexact, i.e. truncating it would round
+#[cfg(feature = "audit")]
+fn is_rounded(a: i128, b: i128, c: i128) -> bool {
+    mul_div(a, b, c, DivRounding::Floor) != mul_div(a, b, c, DivRounding::Ceil)
+}
+
+/// Computes `a * b / c` with a 256-bit intermediate product, so that it
+/// only fails when the final result does not fit in an i128, or `c` is zero.
+fn mul_div(a: i128, b: i128, c: i128, rounding: DivRounding) -> Option<i128> {
+    if c == 0 {
+        return None;
+    }
+
+    let negative = (a < 0) != (b < 0) && a != 0 && b != 0;
+    let negative = negative != (c < 0);
+
+    let (a, b, c) = (a.unsigned_abs(), b.unsigned_abs(), c.unsigned_abs());
+    let rounding = rounding.of_magnitude(negative);
+
+    // the product can only overflow 128 bits if the operands have fewer
+    // than 128 leading zeros between them
+    let magnitude = match a.leading_zeros() + b.leading_zeros() >= 128 {
+        true => div_rounded(a * b, c, rounding),
+        false => {
+            let magnitude = div_rounded(U256::from(a) * U256::from(b), U256::from(c), rounding);
+            if magnitude.bits() > 128 {
+                return None;
+            }
+            magnitude.as_u128()
+        }
+    };
+
+    if magnitude > i128::MAX as u128 + 1 {
+        return None;
+    }
+
+    match negative {
+        true => Some(0i128.wrapping_sub_unsigned(magnitude)),
+        false => i128::try_from(magnitude).ok(),
+    }
+}
+
+impl PartialEq<i128> for Number128 {
+    fn eq(&self, other: &i128) -> bool {
+        other.checked_mul(ONE) == Some(self.0)
+    }
+}
+
+impl PartialOrd<i128> for Number128 {
+    /// Compare with a whole number, by value
+    fn partial_cmp(&self, other: &i128) -> Option<std::cmp::Ordering> {
+        // a whole number too large to scale is beyond the range of any value
+        Some(match other.checked_mul(ONE) {
+            Some(other) => self.0.cmp(&other),
+            None if *other > 0 => std::cmp::Ordering::Less,
+            None => std::cmp::Ordering::Greater,
+        })
+    }
+}
+
+impl PartialEq<Number128> for i128 {
+    fn eq(&self, other: &Number128) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<Number128> for i128 {
+    fn partial_cmp(&self, other: &Number128) -> Option<std::cmp::Ordering> {
+        other.partial_cmp(self).map(std::cmp::Ordering::reverse)
+    }
+}
+
+impl std::str::FromStr for Number128 {
+    type Err = Error;
+
+    /// Parse a decimal string as printed by `Display`, or an exact fraction
+    /// `numerator/denominator` as printed by `to_exact_string`. Values that
+    /// can't be represented exactly are rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((numerator, denominator)) = s.split_once('/') {
+            let numerator: i128 = numerator
+                .parse()
+                .map_err(|_| Error::InvalidInput("not a fraction"))?;
+            let denominator: i128 = denominator
+                .parse()
+                .map_err(|_| Error::InvalidInput("not a fraction"))?;
+
+            let floor = mul_div(numerator, ONE, denominator, DivRounding::Floor);
+            let ceil = mul_div(numerator, ONE, denominator, DivRounding::Ceil);
+
+            return match (floor, ceil) {
+                (Some(floor), Some(ceil)) if floor == ceil => Ok(Self(floor)),
+                (Some(_), Some(_)) => {
+                    Err(Error::InvalidInput("value is not exactly representable"))
+                }
+                _ => Err(Error::OutOfRange),
+            };
+        }
+
+        let (negative, int, frac) = crate::display::split_decimal(s)?;
+
+        if frac.len() > PRECISION as usize {
+            return Err(Error::InvalidInput("too many decimal places"));
+        }
+
+        let int: i128 = int.parse().map_err(|_| Error::OutOfRange)?;
+        let int = Self::try_from_decimal(int, 0)?;
+        let frac = match frac.is_empty() {
+            true => Self::ZERO,
+            false => Self::try_from_decimal(frac.parse::<i128>().unwrap(), -(frac.len() as i32))?,
+        };
+
+        let value = match negative {
+            true => Self::ZERO
+                .checked_sub(&int)
+                .and_then(|n| n.checked_sub(&frac)),
+            false => int.checked_add(&frac),
+        };
+
+        value.ok_or(Error::OutOfRange)
+    }
+}
+
+impl std::fmt::Debug for Number128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(f, "Number128({}; raw={})", self, self.0);
+        }
+
+        <Self as std::fmt::Display>::fmt(self, f)
+    }
+}
+
+impl std::fmt::Display for Number128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // todo optimize
+        let rem = self.0 % ONE;
+        let decimal_digits = PRECISION as usize;
+        // convert to abs to remove sign, and to decimal digits
+        let rem_str = (rem.abs() * POWERS_OF_TEN[decimal_digits] / ONE).to_string();
+        // regular padding like {:010} doesn't work with i128
+        let decimals = "0".repeat(decimal_digits - rem_str.len()) + &*rem_str;
+        let stripped_decimals = decimals.trim_end_matches('0');
+        let pretty_decimals = if stripped_decimals.is_empty() {
+            "0"
+        } else {
+            stripped_decimals
+        };
+        if self.0 <= -ONE {
+            let int = self.0 / ONE;
+            write!(f, "{}.{}", int, pretty_decimals)?;
+        } else if self.0 < 0 {
+            write!(f, "-0.{}", pretty_decimals)?;
+        } else if self.0 < ONE {
+            write!(f, "0.{}", pretty_decimals)?;
+        } else {
+            let int = self.0 / ONE;
+            write!(f, "{}.{}", int, pretty_decimals)?;
+        }
+        Ok(())
+    }
+}
+
+impl Add<Number128> for Number128 {
+    type Output = Self;
+
+    fn add(self, rhs: Number128) -> Self::Output {
+        self.checked_add(&rhs).unwrap()
+    }
+}
+
+impl AddAssign<Number128> for Number128 {
+    fn add_assign(&mut self, rhs: Number128) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Number128> for Number128 {
+    type Output = Self;
+
+    fn sub(self, rhs: Number128) -> Self::Output {
+        self.checked_sub(&rhs).unwrap()
+    }
+}
+
+impl SubAssign<Number128> for Number128 {
+    fn sub_assign(&mut self, rhs: Number128) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<Number128> for Number128 {
+    type Output = Number128;
+
+    fn mul(self, rhs: Number128) -> Self::Output {
+        self.checked_mul(&rhs).unwrap()
+    }
+}
+
+impl MulAssign<Number128> for Number128 {
+    fn mul_assign(&mut self, rhs: Number128) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div<Number128> for Number128 {
+    type Output = Number128;
+
+    fn div(self, rhs: Number128) -> Self::Output {
+        self.checked_div(&rhs).unwrap()
+    }
+}
+
+impl DivAssign<Number128> for Number128 {
+    fn div_assign(&mut self, rhs: Number128) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: Into<i128>> Mul<T> for Number128 {
+    type Output = Number128;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        let result = Self(self.0.mul(rhs));
+        audit!("Number128::mul_int", self, rhs, Some(&result));
+        result
+    }
+}
+
+impl<T: Into<i128>> Div<T> for Number128 {
+    type Output = Number128;
+
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        let result = Self(self.0.div(rhs));
+        audit!("Number128::div_int", self, rhs, Some(&result),
+            rounded: self.0 % rhs != 0);
+        result
+    }
+}
+
+impl<T: Into<i128>> From<T> for Number128 {
+    fn from(n: T) -> Self {
+        Self::from_i128(n.into())
+    }
+}
+
+#[cfg(feature = "traits")]
+impl num_traits::CheckedAdd for Number128 {
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        Number128::checked_add(self, v)
+    }
+}
+
+#[cfg(feature = "traits")]
+impl num_traits::CheckedDiv for Number128 {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        self.0.checked_div(v.0).map(Self)
+    }
+}
+
+#[cfg(feature = "traits")]
+impl num_traits::CheckedMul for Number128 {
+    fn checked_mul(&self, v: &Self) -> Option<Self> {
+        self.0.checked_mul(v.0).map(Self)
+    }
+}
+
+#[cfg(feature = "traits")]
+impl num_traits::CheckedSub for Number128 {
+    fn checked_sub(&self, v: &Self) -> Option<Self> {
+        Number128::checked_sub(self, v)
+    }
+}
+
+#[cfg(feature = "traits")]
+impl num_traits::SaturatingAdd for Number128 {
+    fn saturating_add(&self, v: &Self) -> Self {
+        Self(self.0.saturating_add(v.0))
+    }
+}
+
+impl Summand for Number128 {
+    const ZERO: Self = Number128::ZERO;
+
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        Number128::checked_add(self, v)
+    }
+
+    fn saturating_add(&self, v: &Self) -> Self {
+        Self(self.0.saturating_add(v.0))
+    }
+}
+
+impl Neg for Number128 {
+    type Output = Number128;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().unwrap()
+    }
+}
+
+impl Neg for &Number128 {
+    type Output = Number128;
+
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
+impl_ref_ops!(Number128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_equals_zero() {
+        assert_eq!(Number128::ZERO, Number128::from_decimal(0, 0));
+    }
+
+    #[test]
+    fn one_equals_one() {
+        assert_eq!(Number128::ONE, Number128::from_decimal(1, 0));
+    }
+
+    #[test]
+    fn negative_one_equals_negative_one() {
+        assert_eq!(-Number128::ONE, Number128::from_decimal(-1, 0));
+    }
+
+    #[test]
+    fn one_plus_one_equals_two() {
+        assert_eq!(
+            Number128::from_decimal(2, 0),
+            Number128::ONE + Number128::ONE
+        );
+    }
+
+    #[test]
+    fn one_minus_one_equals_zero() {
+        assert_eq!(Number128::ONE - Number128::ONE, Number128::ZERO);
+    }
+
+    #[test]
+    fn one_times_one_equals_one() {
+        assert_eq!(Number128::ONE, Number128::ONE * Number128::ONE);
+    }
+
+    #[test]
+    fn one_divided_by_one_equals_one() {
+        assert_eq!(Number128::ONE, Number128::ONE / Number128::ONE);
+    }
+
+    #[test]
+    fn ten_div_100_equals_point_1() {
+        assert_eq!(
+            Number128::from_decimal(1, -1),
+            Number128::from_decimal(1, 1) / Number128::from_decimal(100, 0)
+        );
+    }
+
+    #[test]
+    fn comparison() {
+        let a = Number128::from_decimal(1000, -4);
+        let b = Number128::from_decimal(10, -2);
+        assert!(a >= b);
+
+        let c = Number128::from_decimal(1001, -4);
+        assert!(c > a);
+        assert!(c > b);
+
+        let d = Number128::from_decimal(9999999, -8);
+        assert!(d < a);
+        assert!(d < b);
+        assert!(d < c);
+        assert!(d <= d);
+
+        assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+        assert_eq!(a.cmp(&c), std::cmp::Ordering::Less);
+        assert_eq!(a.cmp(&d), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn multiply_by_u64() {
+        assert_eq!(
+            Number128::from_decimal(3, 1),
+            Number128::from_decimal(1, 1) * 3u64
+        )
+    }
+
+    #[test]
+    fn test_add_assign_101_2() {
+        let mut a = Number128::from_decimal(101, 0);
+        a += Number128::from_decimal(2, 0);
+        assert_eq!(Number128::from_decimal(103, 0), a);
+    }
+
+    #[test]
+    fn test_sub_assign_101_2() {
+        let mut a = Number128::from_decimal(101, 0);
+        a -= Number128::from_decimal(2, 0);
+        assert_eq!(Number128::from_decimal(99, 0), a);
+    }
+
+    #[test]
+    fn test_mul_assign_101_2() {
+        let mut a = Number128::from_decimal(101, 0);
+        a *= Number128::from_decimal(2, 0);
+        assert_eq!(Number128::from_decimal(202, 0).0, a.0);
+    }
+
+    #[test]
+    fn test_div_assign_101_2() {
+        let mut a = Number128::from_decimal(101, 0);
+        a /= Number128::from_decimal(2, 0);
+        assert_eq!(Number128::from_decimal(505, -1), a);
+    }
+
+    #[test]
+    fn test_div_assign_102_3() {
+        let mut a = Number128::from_decimal(1, 1);
+        a /= Number128::from_decimal(100, 0);
+        assert_eq!(Number128::from_decimal(1, -1).0, a.0);
+    }
+
+    #[test]
+    fn div_into_i128() {
+        let a = Number128::from_decimal(1000, 0);
+        let b = a / 500;
+        assert_eq!(Number128::from_decimal(2, 0), b);
+
+        #[cfg(not(feature = "binary-number128"))]
+        let c = Number128::from_decimal(1000, -3);
+        #[cfg(not(feature = "binary-number128"))]
+        let d = c / 3;
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Number128::from_decimal(3333333333i64, -10).0, d.0);
+    }
+
+    #[test]
+    fn equality() {
+        let a = Number128::from_decimal(1000, -4);
+        let b = Number128::from_decimal(10, -2);
+        assert_eq!(a, b);
+
+        let c = Number128::from_decimal(-1000, -4);
+        assert_ne!(a, c);
+        assert_ne!(b, c);
+    }
+
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    fn as_u64() {
+        let u64in = 31455;
+        let a = Number128::from_decimal(u64in, -3);
+        let b = a.as_u64(-3);
+        assert_eq!(b, u64in);
+    }
+
+    #[test]
+    #[should_panic = "cannot convert to u64 because value < 0"]
+    fn as_u64_panic_neg() {
+        let a = Number128::from_decimal(-10000, -3);
+        a.as_u64(-3);
+    }
+
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    #[should_panic = "cannot convert to u64 due to overflow"]
+    fn as_u64_panic_big() {
+        let a = Number128::from_decimal(u64::MAX as i128 + 1, -3);
+        a.as_u64(-3);
+    }
+
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    fn as_f64() {
+        let n = Number128::from_bps(15000);
+        assert_eq!(1.5, n.as_f64());
+
+        // Test that conversion is within bounds and doesn't lose precision for min
+        let n = Number128::MIN; // -170141183460469231731687303715884105728
+        assert_eq!(-17014118346046923173168730371.5884105728, n.as_f64());
+
+        // Test that conversion is within bounds and doesn't lose precision for max
+        let n = Number128::MAX; // 170141183460469231731687303715884105727
+        assert_eq!(17014118346046923173168730371.5884105727, n.as_f64());
+
+        // More cases
+        let n = Number128::from_bps(0) - Number128::from_bps(15000);
+        assert_eq!(-1.5, n.as_f64());
+
+        let n = Number128::from_decimal(12345678901i128, -10);
+        assert_eq!(1.2345678901, n.as_f64());
+
+        let n = Number128::from_decimal(-12345678901i128, -10);
+        assert_eq!(-1.2345678901, n.as_f64());
+
+        let n = Number128::from_decimal(-12345678901i128, -9);
+        assert_eq!(-12.345678901, n.as_f64());
+
+        let n = Number128::from_decimal(12345678901i128, -9);
+        assert_eq!(12.345678901, n.as_f64());
+
+        let n = Number128::from_decimal(ONE - 1, 1);
+        assert_eq!(99999999990.0, n.as_f64());
+
+        let n = Number128::from_decimal(12345678901i128, -13);
+        assert_eq!(0.0012345678, n.as_f64());
+
+        let n = Number128::from_decimal(-12345678901i128, -13);
+        assert_eq!(-0.0012345678, n.as_f64());
+    }
+
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    fn display() {
+        let a = Number128::from_bps(15000);
+        assert_eq!("1.5", a.to_string().as_str());
+
+        let a = Number128::from_bps(0) - Number128::from_bps(15000);
+        assert_eq!("-1.5", a.to_string().as_str());
+
+        let b = Number128::from_decimal(12345678901i128, -10);
+        assert_eq!("1.2345678901", b.to_string().as_str());
+
+        let b = Number128::from_decimal(-12345678901i128, -10);
+        assert_eq!("-1.2345678901", b.to_string().as_str());
+
+        let c = Number128::from_decimal(-12345678901i128, -9);
+        assert_eq!("-12.345678901", c.to_string().as_str());
+
+        let c = Number128::from_decimal(12345678901i128, -9);
+        assert_eq!("12.345678901", c.to_string().as_str());
+
+        let d = Number128::from_decimal(ONE - 1, 1);
+        assert_eq!("99999999990.0", d.to_string().as_str());
+
+        let e = Number128::from_decimal(12345678901i128, -13);
+        assert_eq!("0.0012345678", e.to_string().as_str());
+
+        let e = Number128::from_decimal(-12345678901i128, -13);
+        assert_eq!("-0.0012345678", e.to_string().as_str());
+    }
+
+    #[test]
+    fn mul_rounding() {
+        let a = Number128::from_decimal(1, -10);
+        let half = Number128::from_decimal(5, -1);
+        let third = Number128::from_decimal(3333333333i64, -10);
+
+        assert_eq!(Number128::ZERO, a.mul_floor(half));
+        assert_eq!(a, a.mul_ceil(half));
+        assert_eq!(a, a.mul_round(half));
+        assert_eq!(Number128::ZERO, a.mul_round(third));
+
+        assert_eq!(-a, (-a).mul_floor(half));
+        assert_eq!(Number128::ZERO, (-a).mul_ceil(half));
+        assert_eq!(-a, (-a).mul_round(half));
+    }
+
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    fn div_rounding() {
+        let one = Number128::ONE;
+        let three = Number128::from_decimal(3, 0);
+        let third = Number128::from_decimal(3333333333i64, -10);
+        let two_thirds = Number128::from_decimal(6666666666i64, -10);
+
+        assert_eq!(third, one.div_floor(three));
+        assert_eq!(third + Number128::from_i128(1), one.div_ceil(three));
+        assert_eq!(third, one.div_round(three));
+        assert_eq!(
+            two_thirds + Number128::from_i128(1),
+            (one + one).div_round(three)
+        );
+
+        assert_eq!(-third - Number128::from_i128(1), (-one).div_floor(three));
+        assert_eq!(-third, (-one).div_ceil(three));
+        assert_eq!(-third, one.div_round(-three));
+    }
+
+    #[test]
+    fn checked_rounded_ops() {
+        let two = Number128::from_decimal(2, 0);
+        let half = Number128::from_decimal(5, -1);
+
+        assert_eq!(Some(Number128::ONE), two.checked_mul_floor(half));
+        assert_eq!(Some(Number128::ONE), two.checked_mul_ceil(half));
+        assert_eq!(Some(Number128::ONE), two.checked_mul_round(half));
+        assert_eq!(Some(Number128::ONE), half.checked_div_floor(half));
+        assert_eq!(Some(Number128::ONE), half.checked_div_ceil(half));
+        assert_eq!(Some(Number128::ONE), half.checked_div_round(half));
+
+        assert_eq!(None, Number128::MAX.checked_mul_floor(two));
+        assert_eq!(None, Number128::MIN.checked_mul_ceil(two));
+        assert_eq!(None, Number128::MAX.checked_mul_round(two));
+        assert_eq!(None, Number128::MAX.checked_div_floor(half));
+        assert_eq!(None, Number128::MIN.checked_div_ceil(half));
+        assert_eq!(None, Number128::MAX.checked_div_round(half));
+
+        assert_eq!(None, two.checked_div_floor(Number128::ZERO));
+        assert_eq!(None, two.checked_div_ceil(Number128::ZERO));
+        assert_eq!(None, two.checked_div_round(Number128::ZERO));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot divide due to overflow or division by zero")]
+    fn div_floor_by_zero_panics() {
+        let _ = Number128::ONE.div_floor(Number128::ZERO);
+    }
+
+    #[test]
+    fn exact_results_are_not_rounded() {
+        let a = Number128::from_decimal(15, -1);
+        let b = Number128::from_decimal(-4, 0);
+
+        for result in [a.mul_floor(b), a.mul_ceil(b), a.mul_round(b)] {
+            assert_eq!(Number128::from_decimal(-6, 0), result);
+        }
+        let half = Number128::from_decimal(5, -1);
+        for result in [b.div_floor(half), b.div_ceil(half), b.div_round(half)] {
+            assert_eq!(Number128::from_decimal(-8, 0), result);
+        }
+    }
+
+    #[test]
+    fn mul_with_overflowing_intermediate() {
+        let big = Number128::from_decimal(10i128.pow(18), 0);
+        #[cfg(not(feature = "binary-number128"))]
+        let small = Number128::from_decimal(1, -9);
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Number128::from_decimal(10i128.pow(9), 0), big * small);
+        assert_eq!(Number128::MAX, Number128::MAX * Number128::ONE);
+        assert_eq!(Number128::MIN, Number128::MIN * Number128::ONE);
+        assert_eq!(-Number128::MAX, Number128::MAX * -Number128::ONE);
+        assert_eq!(
+            Number128::from_i128(i128::MAX / 2),
+            Number128::MAX.mul_floor(Number128::from_decimal(5, -1))
+        );
+        assert_eq!(
+            Some(Number128::MIN),
+            Number128::MIN.checked_mul(&Number128::ONE)
+        );
+        assert_eq!(None, Number128::MIN.checked_mul(&-Number128::ONE));
+        assert_eq!(None, big.checked_mul(&big));
+    }
+
+    #[test]
+    fn assign_ops_match_binary_ops() {
+        let a = Number128::from_decimal(10i128.pow(18), 0);
+        let b = Number128::from_decimal(3, -1);
+
+        let mut product = a;
+        product *= b;
+        assert_eq!(a * b, product);
+
+        let mut quotient = a;
+        quotient /= b;
+        assert_eq!(a / b, quotient);
+    }
+
+    #[test]
+    fn try_assign_ops() {
+        let mut n = Number128::from_decimal(15, -1);
+
+        assert_eq!(Ok(()), n.try_mul_assign(Number128::from_decimal(2, 0)));
+        assert_eq!(Number128::from_decimal(3, 0), n);
+        assert_eq!(Ok(()), n.try_div_assign(Number128::from_decimal(4, 0)));
+        assert_eq!(Number128::from_decimal(75, -2), n);
+
+        assert_eq!(Err(Error::DivideByZero), n.try_div_assign(Number128::ZERO));
+        assert_eq!(Number128::from_decimal(75, -2), n);
+
+        let mut max = Number128::MAX;
+        assert_eq!(
+            Err(Error::OutOfRange),
+            max.try_mul_assign(Number128::from_decimal(2, 0))
+        );
+        assert_eq!(Number128::MAX, max);
+    }
+
+    #[test]
+    fn significant_figures() {
+        let n = Number128::from_decimal(-123_456_789, -6);
+
+        assert_eq!(
+            Ok(Number128::from_decimal(-123_456, -3)),
+            n.round_to_sig_figs(6, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(-123_457, -3)),
+            n.round_to_sig_figs(6, Rounding::Up)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(123_457, -9)),
+            Number128::from_decimal(1_234_567, -10).round_to_sig_figs(6, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(1_000_000, 0)),
+            Number128::from_decimal(9_999_995, -1).round_to_sig_figs(6, Rounding::HalfEven)
+        );
+        assert_eq!(Ok(n), n.round_to_sig_figs(20, Rounding::Up));
+        assert_eq!(
+            Ok(Number128::ZERO),
+            Number128::ZERO.round_to_sig_figs(6, Rounding::Up)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(Number128::from_decimal(-1, 28)),
+            Number128::MIN.round_to_sig_figs(1, Rounding::Down)
+        );
+
+        assert!(n.round_to_sig_figs(0, Rounding::Down).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number128::MAX.round_to_sig_figs(1, Rounding::Up)
+        );
+    }
+
+    #[test]
+    fn errors_with_context() {
+        let two = Number128::from_decimal(2, 0);
+
+        assert_eq!(Ok(Number128::from_decimal(4, 0)), two.try_add(&two));
+        assert_eq!(Ok(Number128::ZERO), two.try_sub(&two));
+        assert_eq!(
+            Error::OutOfRange.context("Number128::try_mul", &Number128::MIN, &two),
+            Number128::MIN.try_mul(&two).unwrap_err()
+        );
+        assert_eq!(
+            Error::DivideByZero.context("Number128::try_div", &two, &Number128::ZERO),
+            two.try_div(&Number128::ZERO).unwrap_err()
+        );
+        assert!(Number128::MIN.try_sub(&two).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_assign_panics_on_overflow() {
+        let mut n = Number128::MAX;
+        n *= Number128::from_decimal(2, 0);
+    }
+
+    #[test]
+    fn div_with_overflowing_intermediate() {
+        let big = Number128::from_decimal(10i128.pow(18), 0);
+
+        assert_eq!(
+            Number128::from_decimal(10i128.pow(9), 0),
+            big / Number128::from_decimal(10i128.pow(9), 0)
+        );
+        assert_eq!(Number128::MAX, Number128::MAX / Number128::ONE);
+        assert_eq!(None, big.checked_div(&Number128::ZERO));
+        assert_eq!(
+            None,
+            (big * 10).checked_div(&Number128::from_decimal(1, -10))
+        );
+    }
+
+    #[test]
+    fn rescale() {
+        let amount = Number128::from_decimal(-1_234_567, 0);
+        let rescaled = amount.rescale(6, 9, Rounding::Down).unwrap();
+        assert_eq!(Number128::from_decimal(-1_234_567_000i64, 0), rescaled);
+        assert_eq!(Ok(amount), rescaled.rescale(9, 6, Rounding::Down));
+
+        let smallest = Number128::from_i128(-15);
+        assert_eq!(
+            Ok(Number128::from_i128(-1)),
+            smallest.rescale(1, 0, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number128::from_i128(-2)),
+            smallest.rescale(1, 0, Rounding::Up)
+        );
+        assert_eq!(
+            Ok(Number128::from_i128(-2)),
+            smallest.rescale(1, 0, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(Number128::from_i128(-2)),
+            smallest.rescale(1, 0, Rounding::HalfEven)
+        );
+        assert_eq!(
+            Ok(Number128::from_i128(-1)),
+            smallest.rescale(255, 0, Rounding::Up)
+        );
+        assert_eq!(
+            Ok(Number128::ZERO),
+            Number128::MAX.rescale(255, 0, Rounding::HalfUp)
+        );
+
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number128::MIN.rescale(0, 1, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(Number128::ZERO),
+            Number128::ZERO.rescale(0, 255, Rounding::Down)
+        );
+
+        let prices = [
+            (Number128::from_decimal(25, 0), 0),
+            (Number128::from_decimal(25_000_000_000i64, 0), 9),
+        ];
+        assert_eq!(
+            Ok(vec![Number128::from_decimal(25_000_000, 0); 2]),
+            Number128::normalize_to(&prices, 6, Rounding::Down)
+        );
     }
 
     #[test]
-    fn ten_div_100_equals_point_1() {
+    fn ten_pow() {
+        assert_eq!(Some(1), ten_pow_i128(0));
+        assert_eq!(Some(10i128.pow(38)), ten_pow_i128(38));
+        assert_eq!(None, ten_pow_i128(39));
+    }
+
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    fn from_decimal_large_exponents() {
         assert_eq!(
-            Number128::from_decimal(1, -1),
-            Number128::from_decimal(1, 1) / Number128::from_decimal(100, 0)
+            Number128::from_i128(10i128.pow(37)),
+            Number128::from_decimal(1, 27)
+        );
+        assert_eq!(Number128::from_i128(1), Number128::from_decimal(1, -10));
+        assert_eq!(
+            Number128::from_i128(1),
+            Number128::from_decimal(i128::MAX, -48)
+        );
+        assert_eq!(Number128::ZERO, Number128::from_decimal(i128::MAX, -49));
+        assert_eq!(Number128::MAX, Number128::from_decimal(i128::MAX, -10));
+        assert_eq!(Number128::MIN, Number128::from_decimal(i128::MIN, -10));
+        assert_eq!(
+            Number128::from_i128(-1),
+            Number128::from_decimal(i128::MIN, -48)
         );
     }
 
     #[test]
-    fn comparison() {
-        let a = Number128::from_decimal(1000, -4);
-        let b = Number128::from_decimal(10, -2);
-        assert!(a >= b);
+    fn as_u64_large_exponents() {
+        assert_eq!(10u64.pow(15), Number128::ONE.as_u64(-15));
+        assert_eq!(10u64.pow(18), Number128::ONE.as_u64(-18));
+        assert_eq!(0, Number128::MAX.as_u64(38));
+        assert_eq!(0, Number128::MAX.as_u64(i32::MAX - 10));
+    }
 
-        let c = Number128::from_decimal(1001, -4);
-        assert!(c > a);
-        assert!(c > b);
+    #[test]
+    #[should_panic = "cannot convert to u64 due to overflow"]
+    fn as_u64_panics_on_large_negative_exponents() {
+        Number128::ONE.as_u64(-40);
+    }
 
-        let d = Number128::from_decimal(9999999, -8);
-        assert!(d < a);
-        assert!(d < b);
-        assert!(d < c);
-        assert!(d <= d);
+    #[test]
+    #[cfg(not(feature = "binary-number128"))]
+    fn try_from_decimal_out_of_range() {
+        assert_eq!(
+            Err(Error::ExponentOutOfRange(29)),
+            Number128::try_from_decimal(1, 29)
+        );
+        assert_eq!(Ok(Number128::ZERO), Number128::try_from_decimal(1, -49));
+        assert_eq!(
+            Ok(Number128::ZERO),
+            Number128::try_from_decimal(1, i32::MIN)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            Number128::try_from_decimal(i128::MAX, -9)
+        );
+        assert_eq!(
+            Err(Error::ExponentOutOfRange(i32::MAX)),
+            Number128::try_from_decimal(1, i32::MAX)
+        );
+        assert_eq!(Err(Error::OutOfRange), Number128::try_from_decimal(18, 27));
+    }
 
-        assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
-        assert_eq!(a.cmp(&c), std::cmp::Ordering::Less);
-        assert_eq!(a.cmp(&d), std::cmp::Ordering::Greater);
+    #[test]
+    #[should_panic = "cannot convert from decimal"]
+    fn from_decimal_panics_out_of_range() {
+        Number128::from_decimal(1, 29);
     }
 
     #[test]
-    fn multiply_by_u64() {
+    #[cfg(not(feature = "binary-number128"))]
+    fn string_round_trip() {
+        let values = [
+            Number128::ZERO,
+            Number128::ONE,
+            -Number128::ONE,
+            Number128::from_decimal(1, -10),
+            Number128::from_decimal(-1242, -3),
+            Number128::MAX,
+            Number128::MIN,
+        ];
+
+        for value in values {
+            assert_eq!(Ok(value), value.to_string().parse());
+            assert_eq!(value.to_string(), value.to_exact_string());
+        }
+
+        let mut raw = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..1000 {
+            raw = raw.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let value = Number128::from_i128((raw as i128) * (raw as i64 as i128));
+            assert_eq!(Ok(value), value.to_string().parse());
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Number128::from_decimal(-15, -1)), "-1.50".parse());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Ok(Number128::from_decimal(3, -1)), "3/10".parse());
+        assert!("1/3".parse::<Number128>().is_err());
+        assert!("1.00000000001".parse::<Number128>().is_err());
+        assert!("--1".parse::<Number128>().is_err());
         assert_eq!(
-            Number128::from_decimal(3, 1),
-            Number128::from_decimal(1, 1) * 3u64
-        )
+            Err(Error::OutOfRange),
+            format!("1{}", Number128::MAX).parse::<Number128>()
+        );
     }
 
     #[test]
-    fn test_add_assign_101_2() {
-        let mut a = Number128::from_decimal(101, 0);
-        a += Number128::from_decimal(2, 0);
-        assert_eq!(Number128::from_decimal(103, 0), a);
+    fn debug_string() {
+        let number = Number128::from_decimal(-15, -1);
+
+        assert_eq!("-1.5", format!("{number:?}"));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            "Number128(-1.5; raw=-15000000000)",
+            number.to_debug_string()
+        );
     }
 
     #[test]
-    fn test_sub_assign_101_2() {
-        let mut a = Number128::from_decimal(101, 0);
-        a -= Number128::from_decimal(2, 0);
-        assert_eq!(Number128::from_decimal(99, 0), a);
+    fn into_bits() {
+        let bits = Number128::from_decimal(1242, -3).into_bits();
+        let number = Number128::from_bits(bits);
+
+        assert_eq!(Number128::from_decimal(1242, -3), number);
     }
 
     #[test]
-    fn test_mul_assign_101_2() {
-        let mut a = Number128::from_decimal(101, 0);
-        a *= Number128::from_decimal(2, 0);
-        assert_eq!(Number128::from_decimal(202, 0).0, a.0);
+    fn explicit_endian_bytes() {
+        let number = Number128::from_decimal(-1242, -3);
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!((-12_420_000_000i128).to_le_bytes(), number.to_le_bytes());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!((-12_420_000_000i128).to_be_bytes(), number.to_be_bytes());
+        assert_eq!(number, Number128::from_le_bytes(number.to_le_bytes()));
+        assert_eq!(number, Number128::from_be_bytes(number.to_be_bytes()));
     }
 
     #[test]
-    fn test_div_assign_101_2() {
-        let mut a = Number128::from_decimal(101, 0);
-        a /= Number128::from_decimal(2, 0);
-        assert_eq!(Number128::from_decimal(505, -1), a);
+    #[cfg(feature = "traits")]
+    fn num_traits_checked_mul_div_use_raw_values() {
+        use num_traits::{CheckedDiv, CheckedMul};
+
+        let two = Number128::from_i128(2);
+        let three = Number128::from_i128(3);
+
+        assert_eq!(
+            Some(Number128::from_i128(6)),
+            CheckedMul::checked_mul(&two, &three)
+        );
+        assert_eq!(
+            Some(Number128::from_i128(-1)),
+            CheckedDiv::checked_div(&-three, &two)
+        );
+        assert_eq!(None, CheckedMul::checked_mul(&Number128::MAX, &two));
+        assert_eq!(None, CheckedDiv::checked_div(&two, &Number128::ZERO));
     }
+}
+
+/// Checks that the observable decimal behavior is the same regardless of
+/// the internal scaling, to within one unit of the last decimal place.
+#[cfg(test)]
+mod compat_tests {
+    use super::*;
+    use crate::{CheckedSum, SaturatingSum};
 
     #[test]
-    fn test_div_assign_102_3() {
-        let mut a = Number128::from_decimal(1, 1);
-        a /= Number128::from_decimal(100, 0);
-        assert_eq!(Number128::from_decimal(1, -1).0, a.0);
+    fn decimal_exponent_extremes() {
+        assert_eq!(Number128::ZERO, Number128::from_decimal(i128::MAX, -60));
+        assert_eq!(Number128::ZERO, Number128::from_decimal(-1, i32::MIN));
+        assert_eq!(Number128::ONE, Number128::from_decimal(10i128.pow(15), -15));
+        assert_eq!(10u64.pow(15), Number128::ONE.as_u64(-15));
+        assert_eq!(0, Number128::MAX.as_u64(60));
+        assert!(Number128::try_from_decimal(1, 40).is_err());
     }
 
     #[test]
-    fn div_into_i128() {
-        let a = Number128::from_decimal(1000, 0);
-        let b = a / 500;
-        assert_eq!(Number128::from_decimal(2, 0), b);
+    fn compare_with_integers() {
+        let pnl = Number128::from_decimal(-15, -1);
+
+        assert!(pnl < -1i128);
+        assert!(pnl > -2i128);
+        assert!(-2i128 < pnl);
+        assert!(Number128::ONE == 1i128);
+        assert!(-1i128 == -Number128::ONE);
+        assert!(Number128::from_i128(1) != 0i128);
+        assert!(Number128::MAX < i128::MAX);
+        assert!(Number128::MIN > i128::MIN);
+        assert!(i128::MAX > Number128::MAX);
+    }
 
-        let c = Number128::from_decimal(1000, -3);
-        let d = c / 3;
-        assert_eq!(Number128::from_decimal(3333333333i64, -10).0, d.0);
+    #[test]
+    fn checked_integer_conversions() {
+        let rate = Number128::from_decimal(-15, -1);
+
+        assert_eq!(Ok(-1), rate.try_as_i64(0));
+        assert_eq!(Ok(-1_500_000), rate.try_as_i64(-6));
+        assert_eq!(Ok(-15), rate.try_as_i128(-1));
+        assert_eq!(Err(Error::OutOfRange), rate.try_as_u128(0));
+        assert_eq!(Ok(0), (-rate.ulp()).try_as_u128(0));
+        assert_eq!(Ok(15), (-rate).try_as_u128(-1));
+        assert_eq!(Ok(0), rate.try_as_i64(i32::MAX));
+
+        let large = Number128::from_decimal(i64::MAX, 0);
+        assert_eq!(Ok(i64::MAX), large.try_as_i64(0));
+        assert_eq!(Err(Error::OutOfRange), large.try_as_i64(-1));
+        assert_eq!(Err(Error::OutOfRange), (-large).try_as_i64(-1));
+        assert_eq!(Ok(i64::MAX as i128 * 10), large.try_as_i128(-1));
+        assert_eq!(Err(Error::OutOfRange), Number128::MAX.try_as_i128(-11));
+        assert_eq!(Err(Error::OutOfRange), rate.try_as_i128(i32::MIN));
     }
 
     #[test]
-    fn equality() {
-        let a = Number128::from_decimal(1000, -4);
-        let b = Number128::from_decimal(10, -2);
-        assert_eq!(a, b);
+    fn ulp_steps() {
+        let price = Number128::from_decimal(-125, -2);
+        let ulp = Number128::from_i128(1);
+
+        assert_eq!(ulp, price.ulp());
+        assert!(price.next_up().unwrap() > price);
+        assert_eq!(Some(price + ulp), price.next_up());
+        assert_eq!(Some(price - ulp), price.next_down());
+        assert_eq!(Some(-ulp), Number128::ZERO.next_down());
+        assert_eq!(None, Number128::MAX.next_up());
+        assert_eq!(None, Number128::MIN.next_down());
+    }
 
-        let c = Number128::from_decimal(-1000, -4);
-        assert_ne!(a, c);
-        assert_ne!(b, c);
+    #[test]
+    fn branchless_select() {
+        let (one, two) = (Number128::ONE, Number128::ONE + Number128::ONE);
+
+        assert_eq!(one, Number128::select(true, one, two));
+        assert_eq!(two, Number128::select(false, one, two));
+
+        let values = [
+            Number128::MIN,
+            -two,
+            Number128::from_i128(-1),
+            Number128::ZERO,
+            one,
+            Number128::MAX,
+        ];
+        for a in values {
+            for b in values {
+                assert_eq!(a.max(b), a.branchless_max(b));
+                assert_eq!(a.min(b), a.branchless_min(b));
+            }
+        }
     }
 
     #[test]
-    fn as_u64() {
-        let u64in = 31455;
-        let a = Number128::from_decimal(u64in, -3);
-        let b = a.as_u64(-3);
-        assert_eq!(b, u64in);
+    fn negation() {
+        let min = Number128::MIN;
+
+        assert_eq!(None, min.checked_neg());
+        assert_eq!(min, min.wrapping_neg());
+        assert_eq!(
+            Some(Number128::MAX),
+            Number128::from_i128(-i128::MAX).checked_neg()
+        );
+        assert_eq!(-Number128::ONE, Number128::ONE.wrapping_neg());
+        assert_eq!(Number128::ZERO, -Number128::ZERO);
     }
 
     #[test]
-    #[should_panic = "cannot convert to u64 because value < 0"]
-    fn as_u64_panic_neg() {
-        let a = Number128::from_decimal(-10000, -3);
-        a.as_u64(-3);
+    fn wrapping_arithmetic() {
+        let (max, min, one) = (Number128::MAX, Number128::MIN, Number128::ONE);
+        let unit = Number128::from_i128(1);
+
+        assert_eq!(min, max.wrapping_add(&unit));
+        assert_eq!(max, min.wrapping_sub(&unit));
+        assert_eq!(one + one, one.wrapping_add(&one));
+
+        // a cumulative index that wraps still gives the right difference
+        let start = max - one;
+        let end = start.wrapping_add(&(one + one + one));
+        assert!(end < start);
+        assert_eq!(one + one + one, end.wrapping_sub(&start));
+
+        let two = one + one;
+        assert_eq!(max.wrapping_add(&max), max.wrapping_mul(&two));
+        assert_eq!(-two, (-one).wrapping_mul(&two));
+        assert_eq!(one, (-one).wrapping_mul(&-one));
+        assert_eq!(Number128::ZERO, min.wrapping_mul(&Number128::ZERO));
+        assert_eq!(min, min.wrapping_mul(&one));
+        assert_eq!(min, min.wrapping_mul(&-one));
     }
 
     #[test]
-    #[should_panic = "cannot convert to u64 due to overflow"]
-    fn as_u64_panic_big() {
-        let a = Number128::from_decimal(u64::MAX as i128 + 1, -3);
-        a.as_u64(-3);
+    #[should_panic]
+    fn neg_min_panics() {
+        let _ = -Number128::MIN;
     }
 
     #[test]
-    fn as_f64() {
-        let n = Number128::from_bps(15000);
-        assert_eq!(1.5, n.as_f64());
+    fn si_string() {
+        assert_eq!(
+            "-987.2K",
+            Number128::from_decimal(-987_225, 0).to_si_string(4)
+        );
+        assert_eq!(
+            "-1.5M",
+            Number128::from_decimal(-1_500_000, 0).to_si_string(3)
+        );
+        assert_eq!("0.5", Number128::from_decimal(5, -1).to_si_string(3));
+    }
 
-        // Test that conversion is within bounds and doesn't lose precision for min
-        let n = Number128::MIN; // -170141183460469231731687303715884105728
-        assert_eq!(-17014118346046923173168730371.5884105728, n.as_f64());
+    #[test]
+    fn dust() {
+        let threshold = Number128::DUST_EPSILON;
+        let tiny = Number128::from_decimal(5, -9);
+
+        assert!(threshold > Number128::ZERO);
+        assert!(Number128::ZERO.is_dust(threshold));
+        assert!(tiny.is_dust(threshold));
+        assert!((-tiny).is_dust(threshold));
+        assert!(!Number128::ONE.is_dust(threshold));
+        assert!(!(-Number128::ONE).is_dust(threshold));
+        assert!(!Number128::ZERO.is_dust(-threshold));
+        assert_eq!(Number128::ZERO, (-tiny).to_zero_if_dust(threshold));
+        assert_eq!(Number128::ONE, Number128::ONE.to_zero_if_dust(threshold));
+    }
 
-        // Test that conversion is within bounds and doesn't lose precision for max
-        let n = Number128::MAX; // 170141183460469231731687303715884105727
-        assert_eq!(17014118346046923173168730371.5884105727, n.as_f64());
+    #[test]
+    fn div_by_int() {
+        let div = |n: i128, d: i128| {
+            let n = Number128(n);
+            (n / d, n.div_by_int_ceil(d), n.div_by_int_rounded(d))
+        };
 
-        // More cases
-        let n = Number128::from_bps(0) - Number128::from_bps(15000);
-        assert_eq!(-1.5, n.as_f64());
+        assert_eq!((Number128(3), Number128(4), Number128(3)), div(10, 3));
+        assert_eq!((Number128(-3), Number128(-3), Number128(-3)), div(-10, 3));
+        assert_eq!((Number128(-3), Number128(-3), Number128(-3)), div(10, -3));
+        assert_eq!((Number128(2), Number128(3), Number128(3)), div(10, 4));
+        assert_eq!((Number128(-2), Number128(-2), Number128(-3)), div(-10, 4));
+        assert_eq!((Number128(2), Number128(2), Number128(2)), div(10, 5));
+        assert_eq!(Number128::MIN, Number128::MIN.div_by_int_ceil(1));
+    }
 
-        let n = Number128::from_decimal(12345678901i128, -10);
-        assert_eq!(1.2345678901, n.as_f64());
+    #[test]
+    fn overflow_checked_sums() {
+        let values = [Number128::MIN, -Number128::ONE, Number128::MAX];
 
-        let n = Number128::from_decimal(-12345678901i128, -10);
-        assert_eq!(-1.2345678901, n.as_f64());
+        assert_eq!(None, values.into_iter().checked_sum());
+        assert_eq!(
+            -Number128::from_i128(1),
+            values.into_iter().saturating_sum()
+        );
+        assert_eq!(
+            Some(Number128::ONE + Number128::ONE),
+            [Number128::ONE, Number128::ONE].into_iter().checked_sum()
+        );
+    }
 
-        let n = Number128::from_decimal(-12345678901i128, -9);
-        assert_eq!(-12.345678901, n.as_f64());
+    #[test]
+    fn midpoint() {
+        let midpoint = |a: i128, b: i128| Number128(a).midpoint(Number128(b)).0;
+
+        assert_eq!(2, midpoint(1, 3));
+        assert_eq!(2, midpoint(2, 3));
+        assert_eq!(-2, midpoint(-2, -3));
+        assert_eq!(0, midpoint(-2, 1));
+        assert_eq!(0, midpoint(i128::MIN, i128::MAX));
+        assert_eq!(i128::MAX, midpoint(i128::MAX, i128::MAX));
+        assert_eq!(i128::MIN, midpoint(i128::MIN, i128::MIN));
+    }
 
-        let n = Number128::from_decimal(12345678901i128, -9);
-        assert_eq!(12.345678901, n.as_f64());
+    #[test]
+    fn full_mul() {
+        let full_mul = |a: i128, b: i128| Number128(a).full_mul(&Number128(b));
+
+        assert_eq!((0, 6), full_mul(2, 3));
+        assert_eq!((-1, -6i128 as u128), full_mul(-2, 3));
+        assert_eq!((0, 0), full_mul(-2, 0));
+        assert_eq!((0, 1 << 127), full_mul(i128::MIN, -1));
+        assert_eq!((i128::MAX >> 1, 1), full_mul(i128::MAX, i128::MAX));
+        assert_eq!((1 << 126, 0), full_mul(i128::MIN, i128::MIN));
+        assert_eq!((-1, 0), full_mul(i128::MIN, 2));
+    }
 
-        let n = Number128::from_decimal(ONE - 1, 1);
-        assert_eq!(99999999990.0, n.as_f64());
+    fn assert_within_ulp(expected: f64, actual: Number128) {
+        let ulp = 1.0 / POWERS_OF_TEN[PRECISION as usize] as f64;
+        let difference = (expected - actual.as_f64()).abs();
 
-        let n = Number128::from_decimal(12345678901i128, -13);
-        assert_eq!(0.0012345678, n.as_f64());
+        assert!(
+            difference <= ulp * expected.abs().max(1.0),
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
 
-        let n = Number128::from_decimal(-12345678901i128, -13);
-        assert_eq!(-0.0012345678, n.as_f64());
+    #[test]
+    fn decimal_str_consts() {
+        number_consts! {
+            MAX_UTIL = ~0.95,
+            LIQ_BONUS = ~0.05,
+            /// A negative value
+            pub(crate) OFFSET = -12.500,
+            WHOLE = 3,
+            SEPARATED = ~1_000.000_1,
+            TRUNCATED = ~0.123456789012,
+        }
+
+        assert_eq!(Number128::from_decimal(95, -2), MAX_UTIL);
+        assert_eq!(Number128::from_decimal(5, -2), LIQ_BONUS);
+        assert_eq!(Number128::from_decimal(-125, -1), OFFSET);
+        assert_eq!(Number128::from_decimal(3, 0), WHOLE);
+        assert_eq!(Number128::from_decimal(10_000_001, -4), SEPARATED);
+        assert_eq!(Number128::from_decimal(1_234_567_890, -10), TRUNCATED);
     }
 
     #[test]
-    fn display() {
-        let a = Number128::from_bps(15000);
-        assert_eq!("1.5", a.to_string().as_str());
+    #[cfg(feature = "binary-number128")]
+    #[should_panic = "decimal number cannot be represented exactly"]
+    fn decimal_str_rejects_inexact_binary() {
+        Number128::from_decimal_str("0.1");
+    }
 
-        let a = Number128::from_bps(0) - Number128::from_bps(15000);
-        assert_eq!("-1.5", a.to_string().as_str());
+    #[test]
+    #[should_panic]
+    fn decimal_str_rejects_lost_precision() {
+        Number128::from_decimal_str("0.00000000001");
+    }
 
-        let b = Number128::from_decimal(12345678901i128, -10);
-        assert_eq!("1.2345678901", b.to_string().as_str());
+    #[test]
+    fn decimal_literal_macro() {
+        const RATE: Number128 = number128!(~0.0525);
 
-        let b = Number128::from_decimal(-12345678901i128, -10);
-        assert_eq!("-1.2345678901", b.to_string().as_str());
+        assert_eq!(Number128::from_decimal(525, -4), RATE);
+        assert_eq!(Number128::from_decimal(-125, -2), number128!(-1.25));
+        assert_eq!(Number128::from_decimal(125, -2), number128!("1.25"));
+        assert_eq!(
+            Number128::from_decimal(1_234_567_890, -10),
+            number128!(~0.123456789012345)
+        );
+        assert_eq!(
+            Number128::from_decimal(-1, 0),
+            number128!(~"-1.000000000099")
+        );
+        assert_eq!(Number128::ONE, number128!(1.000000000000));
 
-        let c = Number128::from_decimal(-12345678901i128, -9);
-        assert_eq!("-12.345678901", c.to_string().as_str());
+        // exact with either scaling
+        assert_eq!(Number128::from_decimal(-5, -1), number128!(-0.5));
+        assert_eq!(Number128::from_decimal(625, -4), number128!("0.0625"));
+    }
 
-        let c = Number128::from_decimal(12345678901i128, -9);
-        assert_eq!("12.345678901", c.to_string().as_str());
+    #[test]
+    #[should_panic]
+    fn decimal_str_rejects_float_syntax() {
+        Number128::from_decimal_str("1e-5");
+    }
 
-        let d = Number128::from_decimal(ONE - 1, 1);
-        assert_eq!("99999999990.0", d.to_string().as_str());
+    #[test]
+    fn logarithms() {
+        assert_eq!(Some(0), Number128::ONE.log2_floor());
+        assert_eq!(Some(0), Number128::ONE.log10_floor());
+        assert_eq!(Some(3), Number128::from_decimal(15, 0).log2_floor());
+        assert_eq!(Some(4), Number128::from_decimal(16, 0).log2_floor());
+        assert_eq!(Some(-1), Number128::from_decimal(5, -1).log2_floor());
+        assert_eq!(Some(-2), Number128::from_decimal(49, -2).log2_floor());
+        assert_eq!(Some(1), Number128::from_decimal(99, 0).log10_floor());
+        assert_eq!(Some(2), Number128::from_decimal(100, 0).log10_floor());
+        assert_eq!(Some(-2), Number128::from_decimal(5, -2).log10_floor());
+        assert_eq!(Some(-1), Number128::from_decimal(5, -1).log10_floor());
+        assert_eq!(
+            Some(20),
+            Number128::from_decimal(10i128.pow(20), 0).log10_floor()
+        );
+        assert_eq!(None, Number128::ZERO.log2_floor());
+        assert_eq!(None, (-Number128::ONE).log10_floor());
+        assert_eq!(1, Number128::MAX.leading_zeros());
+    }
 
-        let e = Number128::from_decimal(12345678901i128, -13);
-        assert_eq!("0.0012345678", e.to_string().as_str());
+    #[test]
+    fn reference_ops() {
+        let a = Number128::from_decimal(15, -1);
+        let b = Number128::from_decimal(-5, -1);
+
+        let (ra, rb) = (&a, &b);
+
+        assert_eq!(a + b, ra + rb);
+        assert_eq!(a - b, ra - b);
+        assert_eq!(a * b, a * rb);
+        assert_eq!(a / b, ra / rb);
+        assert_eq!(-a, -ra);
+
+        let mut n = a;
+        n += rb;
+        n -= rb;
+        n *= rb;
+        n /= rb;
+        assert_eq!(a, n);
+
+        let total = [a, b].iter().fold(Number128::ZERO, |acc, n| acc + n);
+        assert_eq!(Number128::ONE, total);
+    }
 
-        let e = Number128::from_decimal(-12345678901i128, -13);
-        assert_eq!("-0.0012345678", e.to_string().as_str());
+    #[test]
+    fn rounded_ties() {
+        let tie = Number128::from_decimal(25, -1);
+
+        assert_eq!(3, tie.as_u64_rounded(0));
+        assert_eq!(2, tie.as_u64_rounded_with(0, Rounding::HalfEven));
+        assert_eq!(
+            4,
+            Number128::from_decimal(35, -1).as_u64_rounded_with(0, Rounding::HalfEven)
+        );
+        assert_eq!(
+            3,
+            Number128::from_decimal(2500001, -6).as_u64_rounded_with(0, Rounding::HalfEven)
+        );
+        assert_eq!(250, tie.as_u64_rounded_with(-2, Rounding::HalfEven));
+        assert_eq!(0, Number128::MAX.as_u64_rounded(30));
     }
 
     #[test]
-    fn into_bits() {
-        let bits = Number128::from_decimal(1242, -3).into_bits();
-        let number = Number128::from_bits(bits);
+    fn matches_number_rounding() {
+        for (value, exponent) in [(15, -1), (25, -1), (1249, -3), (1250, -3), (1251, -3)] {
+            for rounding in [
+                Rounding::Down,
+                Rounding::Up,
+                Rounding::HalfUp,
+                Rounding::HalfEven,
+            ] {
+                assert_eq!(
+                    crate::Number::from_decimal(value as u64, exponent)
+                        .as_u64_rounded_with(-1, rounding),
+                    Number128::from_decimal(value, exponent).as_u64_rounded_with(-1, rounding)
+                );
+            }
+        }
+    }
 
-        assert_eq!(Number128::from_decimal(1242, -3), number);
+    #[test]
+    fn exact_string_round_trip() {
+        for value in [
+            Number128::from_decimal(-1242, -3),
+            Number128::from_i128(1),
+            Number128::from_i128(-7),
+            Number128::MAX,
+            Number128::MIN,
+        ] {
+            assert_eq!(Ok(value), value.to_exact_string().parse());
+        }
+    }
+
+    #[test]
+    fn zeroed_and_default_equal_zero() {
+        #[derive(Pod, Zeroable, Default, Copy, Clone)]
+        #[repr(C)]
+        struct Account {
+            value: Number128,
+        }
+
+        let bytes = [0u8; std::mem::size_of::<Account>()];
+
+        assert_eq!(
+            Number128::ZERO,
+            bytemuck::pod_read_unaligned::<Account>(&bytes).value
+        );
+        assert_eq!(Number128::ZERO, Number128::zeroed());
+        assert_eq!(Number128::ZERO, Account::default().value);
+    }
+
+    #[test]
+    fn from_decimal() {
+        assert_within_ulp(0.1, Number128::from_decimal(1, -1));
+        assert_within_ulp(1.2345678901, Number128::from_decimal(12345678901i64, -10));
+        assert_within_ulp(-31.455, Number128::from_decimal(-31455, -3));
+        assert_within_ulp(100.0, Number128::from_decimal(1, 2));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Number128::from_decimal(15, -1);
+        let b = Number128::from_decimal(-25, -1);
+
+        assert_within_ulp(-1.0, a + b);
+        assert_within_ulp(4.0, a - b);
+        assert_within_ulp(-3.75, a * b);
+        assert_within_ulp(-0.6, a / b);
+        assert_within_ulp(4.5, a * 3);
+        assert_within_ulp(0.5, a / 3);
+    }
+
+    #[test]
+    fn conversion() {
+        assert_eq!(3, Number128::from_decimal(35, -1).as_u64(0));
+        assert_eq!(1500, Number128::from_decimal(15, -1).as_u64(-3));
+        assert_eq!(123, Number128::from_decimal(12345, 0).as_u64(2));
+        assert_eq!(1.5, Number128::from_decimal(15, -1).as_f64());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("1.5", Number128::from_decimal(15, -1).to_string());
+        assert_eq!("-2.25", Number128::from_decimal(-225, -2).to_string());
+        assert_eq!("1000.0", Number128::from_decimal(1000, 0).to_string());
     }
 }
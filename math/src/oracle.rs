@@ -0,0 +1,364 @@
+//! A common interface over price oracles.
+//!
+//! Each supported oracle format is normalized into `Number128` values, so
+//! that switching oracles does not require changes to the math using the
+//! prices. Support for each oracle crate is behind its own feature flag:
+//! `pyth` and `switchboard`.
+//!
+//! How fresh a price must be is configured with `StalenessConfig`, which
+//! every read takes, and `PriceWindow` for averages over a window. Both can
+//! be stored in accounts.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{Error, Number128};
+
+/// A price reported by an oracle
+///
+/// Every read takes the current unix timestamp, and fails with
+/// `Error::Freshness` if the oracle's timestamp doesn't pass the
+/// `StalenessConfig`. Prices that are not positive are rejected too.
+pub trait OraclePrice {
+    /// The current price
+    fn price(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error>;
+
+    /// The uncertainty in the current price
+    fn confidence(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error>;
+
+    /// The time-weighted average price
+    fn twap(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error>;
+}
+
+/// A constant price, e.g. for stablecoins or tests
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FixedPrice(pub Number128);
+
+/// A fixed price is never stale.
+impl OraclePrice for FixedPrice {
+    fn price(&self, _now: i64, _staleness: &StalenessConfig) -> Result<Number128, Error> {
+        positive(self.0)
+    }
+
+    fn confidence(&self, _now: i64, _staleness: &StalenessConfig) -> Result<Number128, Error> {
+        Ok(Number128::ZERO)
+    }
+
+    fn twap(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        self.price(now, staleness)
+    }
+}
+
+/// Reject a price that is zero or negative
+fn positive(price: Number128) -> Result<Number128, Error> {
+    match price > Number128::ZERO {
+        true => Ok(price),
+        false => Err(Error::InvalidInput("price is not positive")),
+    }
+}
+
+/// Why a price failed a freshness check
+#[derive(thiserror::Error, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FreshnessError {
+    #[error("The price is {age}s old, more than the maximum of {max_age}s")]
+    Stale { age: u64, max_age: u64 },
+
+    #[error("The price is {ahead}s in the future, more than the maximum of {max_drift}s")]
+    FromFuture { ahead: u64, max_drift: u64 },
+
+    #[error("The price window spans {span}s, less than the minimum of {min}s")]
+    WindowTooShort { span: u64, min: u64 },
+
+    #[error("The price window spans {span}s, more than the maximum of {max}s")]
+    WindowTooLong { span: u64, max: u64 },
+
+    #[error("The price window starts at {start}, after it ends at {end}")]
+    ReversedWindow { start: i64, end: i64 },
+}
+
+/// How old a price can be before it is rejected
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct StalenessConfig {
+    /// The largest age of a price, in seconds
+    pub max_age: u64,
+    /// How far ahead of the current time a price may be timestamped, in
+    /// seconds, to allow for clock drift between the oracle and the cluster
+    pub max_drift: u64,
+}
+
+impl StalenessConfig {
+    /// Check the timestamp of a price against the current time
+    pub fn validate(&self, timestamp: i64, now: i64) -> Result<(), FreshnessError> {
+        match now >= timestamp {
+            true if now.abs_diff(timestamp) > self.max_age => Err(FreshnessError::Stale {
+                age: now.abs_diff(timestamp),
+                max_age: self.max_age,
+            }),
+            false if now.abs_diff(timestamp) > self.max_drift => Err(FreshnessError::FromFuture {
+                ahead: now.abs_diff(timestamp),
+                max_drift: self.max_drift,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The range of lengths allowed for the window of a time-weighted average
+/// price
+///
+/// None of the oracle adapters report when their average starts, so this
+/// is for averages computed elsewhere, e.g. from two snapshots of a
+/// cumulative price.
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct PriceWindow {
+    /// The shortest window, in seconds
+    pub min_duration: u64,
+    /// The longest window, in seconds
+    pub max_duration: u64,
+}
+
+impl PriceWindow {
+    /// Check the window of an average that started at `timestamp` and ends
+    /// at the current time
+    pub fn validate(&self, timestamp: i64, now: i64) -> Result<(), FreshnessError> {
+        if timestamp > now {
+            return Err(FreshnessError::ReversedWindow {
+                start: timestamp,
+                end: now,
+            });
+        }
+
+        let span = now.abs_diff(timestamp);
+        if span < self.min_duration {
+            return Err(FreshnessError::WindowTooShort {
+                span,
+                min: self.min_duration,
+            });
+        }
+        if span > self.max_duration {
+            return Err(FreshnessError::WindowTooLong {
+                span,
+                max: self.max_duration,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Pyth's exponential moving average is used as the TWAP.
+#[cfg(feature = "pyth")]
+impl OraclePrice for pyth_sdk::PriceFeed {
+    fn price(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        let price = self.get_price_unchecked();
+        staleness.validate(price.publish_time, now)?;
+
+        positive(Number128::try_from_decimal(price.price, price.expo)?)
+    }
+
+    fn confidence(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        let price = self.get_price_unchecked();
+        staleness.validate(price.publish_time, now)?;
+
+        Number128::try_from_decimal(price.conf, price.expo)
+    }
+
+    fn twap(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        let price = self.get_ema_price_unchecked();
+        staleness.validate(price.publish_time, now)?;
+
+        positive(Number128::try_from_decimal(price.price, price.expo)?)
+    }
+}
+
+/// Switchboard aggregators don't publish an average, so the latest
+/// confirmed result is used as the TWAP, and its standard deviation as the
+/// confidence.
+#[cfg(feature = "switchboard")]
+impl OraclePrice for switchboard_v2::AggregatorAccountData {
+    fn price(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        let round = fresh_switchboard_round(self, now, staleness)?;
+
+        positive(from_switchboard_decimal(&round.result)?)
+    }
+
+    fn confidence(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        let round = fresh_switchboard_round(self, now, staleness)?;
+
+        from_switchboard_decimal(&round.std_deviation)
+    }
+
+    fn twap(&self, now: i64, staleness: &StalenessConfig) -> Result<Number128, Error> {
+        self.price(now, staleness)
+    }
+}
+
+#[cfg(feature = "switchboard")]
+fn fresh_switchboard_round<'a>(
+    aggregator: &'a switchboard_v2::AggregatorAccountData,
+    now: i64,
+    staleness: &StalenessConfig,
+) -> Result<&'a switchboard_v2::AggregatorRound, Error> {
+    let round = &aggregator.latest_confirmed_round;
+    staleness.validate(round.round_open_timestamp, now)?;
+
+    Ok(round)
+}
+
+#[cfg(feature = "switchboard")]
+fn from_switchboard_decimal(
+    value: &switchboard_v2::SwitchboardDecimal,
+) -> Result<Number128, Error> {
+    let scale = i32::try_from(value.scale).unwrap_or(i32::MAX);
+
+    Number128::try_from_decimal(value.mantissa, -scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_price() {
+        let oracle = FixedPrice(Number128::from_decimal(1, 0));
+        let config = StalenessConfig::default();
+
+        assert_eq!(Ok(Number128::ONE), oracle.price(i64::MAX, &config));
+        assert_eq!(Ok(Number128::ZERO), oracle.confidence(i64::MAX, &config));
+        assert_eq!(Ok(Number128::ONE), oracle.twap(i64::MAX, &config));
+
+        assert_eq!(
+            Err(Error::InvalidInput("price is not positive")),
+            FixedPrice(Number128::ZERO).price(0, &config)
+        );
+        assert_eq!(
+            Err(Error::InvalidInput("price is not positive")),
+            FixedPrice(-Number128::ONE).twap(0, &config)
+        );
+    }
+
+    #[test]
+    fn staleness() {
+        let config = StalenessConfig {
+            max_age: 60,
+            max_drift: 5,
+        };
+
+        assert_eq!(Ok(()), config.validate(1_000, 1_000));
+        assert_eq!(Ok(()), config.validate(1_000, 1_060));
+        assert_eq!(Ok(()), config.validate(1_005, 1_000));
+        assert_eq!(
+            Err(FreshnessError::Stale {
+                age: 61,
+                max_age: 60
+            }),
+            config.validate(1_000, 1_061)
+        );
+        assert_eq!(
+            Err(FreshnessError::FromFuture {
+                ahead: 6,
+                max_drift: 5
+            }),
+            config.validate(1_006, 1_000)
+        );
+        assert!(config.validate(i64::MIN, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn price_window() {
+        let window = PriceWindow {
+            min_duration: 300,
+            max_duration: 3_600,
+        };
+
+        assert_eq!(Ok(()), window.validate(0, 300));
+        assert_eq!(Ok(()), window.validate(0, 3_600));
+        assert_eq!(
+            Err(FreshnessError::WindowTooShort {
+                span: 299,
+                min: 300
+            }),
+            window.validate(1, 300)
+        );
+        assert_eq!(
+            Err(FreshnessError::WindowTooLong {
+                span: 3_601,
+                max: 3_600
+            }),
+            window.validate(0, 3_601)
+        );
+        assert_eq!(
+            Err(FreshnessError::ReversedWindow {
+                start: 301,
+                end: 300
+            }),
+            window.validate(301, 300)
+        );
+    }
+
+    #[cfg(feature = "pyth")]
+    #[test]
+    fn pyth_price_feed() {
+        use pyth_sdk::{Identifier, Price, PriceFeed};
+
+        let feed = |price: i64| {
+            PriceFeed::new(
+                Identifier::new([0; 32]),
+                Price {
+                    price,
+                    conf: 1_500_000,
+                    expo: -8,
+                    publish_time: 1_000,
+                },
+                Price {
+                    price: 2_500_000_000,
+                    conf: 0,
+                    expo: -8,
+                    publish_time: 970,
+                },
+            )
+        };
+        let fresh = feed(2_512_345_678);
+        let config = StalenessConfig {
+            max_age: 60,
+            max_drift: 5,
+        };
+
+        assert_eq!(
+            Ok(Number128::from_decimal(2_512_345_678i64, -8)),
+            fresh.price(1_030, &config)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(15, -3)),
+            fresh.confidence(1_030, &config)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(25, 0)),
+            fresh.twap(1_030, &config)
+        );
+        assert!(fresh.price(995, &config).is_ok());
+
+        let stale = |age| Err(Error::Freshness(FreshnessError::Stale { age, max_age: 60 }));
+        assert_eq!(stale(61), fresh.price(1_061, &config));
+        assert_eq!(stale(61), fresh.confidence(1_061, &config));
+        assert_eq!(stale(61), fresh.twap(1_031, &config));
+        assert_eq!(
+            Err(Error::Freshness(FreshnessError::FromFuture {
+                ahead: 6,
+                max_drift: 5
+            })),
+            fresh.price(994, &config)
+        );
+
+        let negative = feed(-1);
+        assert_eq!(
+            Err(Error::InvalidInput("price is not positive")),
+            negative.price(1_000, &config)
+        );
+        assert_eq!(
+            Err(Error::InvalidInput("price is not positive")),
+            feed(0).price(1_000, &config)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Price helpers for order books.
+//!
+//! Order prices must be a whole number of ticks. Which way to round a price
+//! onto the tick grid depends on the side of the order, so the rounding is
+//! always chosen by the caller.
+
+use crate::{div_rounded, Error, Number128, Rounding};
+
+/// Round a price to a whole number of ticks
+///
+/// Returns an error if the tick is not positive, or the result overflows.
+pub fn round_to_tick(
+    price: Number128,
+    tick: Number128,
+    rounding: Rounding,
+) -> Result<Number128, Error> {
+    let (price, tick) = (price.to_i128(), tick.to_i128());
+
+    if tick == 0 {
+        return Err(Error::DivideByZero);
+    }
+    if tick < 0 {
+        return Err(Error::InvalidInput("tick size is negative"));
+    }
+
+    // round the magnitude, so that rounding is symmetric around zero
+    let ticks = div_rounded(price.unsigned_abs(), tick.unsigned_abs(), rounding);
+    let ticks = match price < 0 {
+        true => 0i128.checked_sub_unsigned(ticks),
+        false => i128::try_from(ticks).ok(),
+    };
+
+    ticks
+        .and_then(|ticks| ticks.checked_mul(tick))
+        .map(Number128::from_i128)
+        .ok_or(Error::OutOfRange)
+}
+
+/// The difference between the best ask and the best bid, which is negative
+/// if the book is crossed
+///
+/// Returns an error if the result overflows.
+pub fn spread(bid: Number128, ask: Number128) -> Result<Number128, Error> {
+    ask.checked_sub(&bid).ok_or(Error::OutOfRange)
+}
+
+/// The price halfway between the best bid and the best ask, rounded toward
+/// zero when it falls between two representable values
+pub fn mid(bid: Number128, ask: Number128) -> Number128 {
+    bid.midpoint(ask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(bps: u16) -> Number128 {
+        Number128::from_bps(bps)
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn rounds_to_tick() {
+        let tick = price(25);
+
+        assert_eq!(
+            Ok(price(9_975)),
+            round_to_tick(price(9_990), tick, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(price(10_000)),
+            round_to_tick(price(9_990), tick, Rounding::Up)
+        );
+        assert_eq!(
+            Ok(price(10_000)),
+            round_to_tick(price(9_990), tick, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(price(9_975)),
+            round_to_tick(price(9_980), tick, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(price(10_000)),
+            round_to_tick(price(10_000), tick, Rounding::Up)
+        );
+    }
+
+    #[test]
+    fn rounds_ties() {
+        let tick = price(10);
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(price(20)),
+            round_to_tick(price(15), tick, Rounding::HalfUp)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(price(20)),
+            round_to_tick(price(15), tick, Rounding::HalfEven)
+        );
+        assert_eq!(
+            Ok(price(20)),
+            round_to_tick(price(25), tick, Rounding::HalfEven)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(-price(20)),
+            round_to_tick(-price(15), tick, Rounding::HalfUp)
+        );
+        assert_eq!(
+            Ok(-price(10)),
+            round_to_tick(-price(15), tick, Rounding::Down)
+        );
+    }
+
+    #[test]
+    fn invalid_tick() {
+        assert_eq!(
+            Err(Error::DivideByZero),
+            round_to_tick(price(1), Number128::ZERO, Rounding::Down)
+        );
+        assert!(round_to_tick(price(1), -price(1), Rounding::Down).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            round_to_tick(Number128::MAX, price(10_000), Rounding::Up)
+        );
+    }
+
+    #[test]
+    fn spread_and_mid() {
+        assert_eq!(Ok(price(50)), spread(price(9_975), price(10_025)));
+        assert_eq!(Ok(-price(50)), spread(price(10_025), price(9_975)));
+        assert_eq!(
+            Err(Error::OutOfRange),
+            spread(Number128::MIN, Number128::MAX)
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(price(10_000), mid(price(9_975), price(10_025)));
+        assert_eq!(Number128::MAX, mid(Number128::MAX, Number128::MAX));
+        assert_eq!(
+            Number128::ZERO,
+            mid(Number128::from_i128(-2), Number128::from_i128(1))
+        );
+        assert_eq!(
+            Number128::from_i128(-1),
+            mid(Number128::from_i128(-2), Number128::from_i128(-1))
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Aggregations over large slices of numbers, computed in parallel with
+//! rayon.
+//!
+//! Partial results are kept in widened integers, so that results are exact
+//! (up to the final truncation) and don't depend on how the work was split
+//! between threads.
+
+use std::ops::{Add, Mul, Sub};
+
+use rayon::prelude::*;
+
+use crate::bigint::U384;
+use crate::{lerp, Error, Number, Number128, U192};
+
+static_assertions::assert_impl_all!(Number: Send, Sync);
+static_assertions::assert_impl_all!(Number128: Send, Sync);
+
+/// A number type that can be aggregated in parallel
+pub trait Aggregate:
+    Copy + Ord + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The underlying integer, as a sign (`true` if negative) and magnitude
+    fn to_parts(self) -> (bool, U192);
+
+    /// The number for an underlying integer, or `None` if it is out of range
+    fn from_parts(negative: bool, magnitude: U192) -> Option<Self>;
+}
+
+impl Aggregate for Number {
+    const ZERO: Self = Number::ZERO;
+    const ONE: Self = Number::ONE;
+
+    fn to_parts(self) -> (bool, U192) {
+        (false, self.to_u192())
+    }
+
+    fn from_parts(negative: bool, magnitude: U192) -> Option<Self> {
+        match negative && !magnitude.is_zero() {
+            true => None,
+            false => Some(Number::from_u192(magnitude)),
+        }
+    }
+}
+
+impl Aggregate for Number128 {
+    const ZERO: Self = Number128::ZERO;
+    const ONE: Self = Number128::ONE;
+
+    fn to_parts(self) -> (bool, U192) {
+        let raw = self.to_i128();
+        (raw < 0, U192::from(raw.unsigned_abs()))
+    }
+
+    fn from_parts(negative: bool, magnitude: U192) -> Option<Self> {
+        if magnitude.bits() > 128 {
+            return None;
+        }
+
+        let magnitude = magnitude.low_u128();
+        let raw = match negative {
+            true => 0i128.checked_sub_unsigned(magnitude),
+            false => i128::try_from(magnitude).ok(),
+        };
+
+        raw.map(Number128::from_i128)
+    }
+}
+
+/// The sum of the values
+pub fn par_sum<T: Aggregate>(values: &[T]) -> Result<T, Error> {
+    values
+        .par_iter()
+        .map(|n| {
+            let (negative, magnitude) = n.to_parts();
+            Total::new(negative, U384::from(magnitude))
+        })
+        .reduce(Total::default, Total::add)
+        .to_number(U384::one())
+}
+
+/// The sum of each value multiplied by its weight, truncated once at the
+/// end rather than after each product
+pub fn par_weighted_sum<T: Aggregate>(values: &[T], weights: &[T]) -> Result<T, Error> {
+    if values.len() != weights.len() {
+        return Err(Error::InvalidInput("values and weights differ in length"));
+    }
+
+    values
+        .par_iter()
+        .zip(weights)
+        .map(|(value, weight)| {
+            let (value_negative, value) = value.to_parts();
+            let (weight_negative, weight) = weight.to_parts();
+
+            let product = U384::from(value) * U384::from(weight);
+            Total::new(value_negative ^ weight_negative, product)
+        })
+        .reduce(Total::default, Total::add)
+        .to_number(U384::from(T::ONE.to_parts().1))
+}
+
+/// The `p`th percentile of the values, where `p` is between 0 and 1,
+/// linearly interpolating between the closest ranks. The slice is sorted
+/// in place, in parallel.
+pub fn par_percentile<T: Aggregate>(values: &mut [T], p: T) -> Result<T, Error> {
+    if values.is_empty() {
+        return Err(Error::InvalidInput("no values"));
+    }
+    if p < T::ZERO || p > T::ONE {
+        return Err(Error::InvalidInput("percentile must be between 0 and 1"));
+    }
+
+    values.par_sort_unstable();
+
+    let one = U384::from(T::ONE.to_parts().1);
+    let rank = U384::from(p.to_parts().1) * U384::from(values.len() - 1);
+    let (index, fraction) = rank.div_mod(one);
+    let index = index.as_usize();
+    // the fraction is less than one, so it's always representable
+    let fraction = T::from_parts(false, fraction.checked_as_u192().unwrap()).unwrap();
+
+    match values.get(index + 1) {
+        Some(next) => Ok(lerp(values[index], *next, fraction)),
+        None => Ok(values[index]),
+    }
+}
+
+/// A running total, with the positive and negative terms summed separately
+/// so that neither can overflow before the final result is known
+#[derive(Default, Clone, Copy)]
+struct Total {
+    positive: U384,
+    negative: U384,
+}
+
+impl Total {
+    fn new(negative: bool, magnitude: U384) -> Self {
+        match negative {
+            true => Self {
+                positive: U384::zero(),
+                negative: magnitude,
+            },
+            false => Self {
+                positive: magnitude,
+                negative: U384::zero(),
+            },
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            positive: self.positive.saturating_add(other.positive),
+            negative: self.negative.saturating_add(other.negative),
+        }
+    }
+
+    /// The total divided by `scale`, truncated toward zero
+    fn to_number<T: Aggregate>(self, scale: U384) -> Result<T, Error> {
+        // a saturated total is far beyond the range of any number
+        if self.positive == U384::MAX || self.negative == U384::MAX {
+            return Err(Error::OutOfRange);
+        }
+
+        let (negative, magnitude) = match self.positive >= self.negative {
+            true => (false, self.positive - self.negative),
+            false => (true, self.negative - self.positive),
+        };
+
+        (magnitude / scale)
+            .checked_as_u192()
+            .and_then(|magnitude| T::from_parts(negative, magnitude))
+            .ok_or(Error::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::percentile;
+
+    fn numbers_128(count: i64) -> Vec<Number128> {
+        (0..count)
+            .map(|i| Number128::from_decimal((i * 7_919) % 1_000 - 500, -2))
+            .collect()
+    }
+
+    #[test]
+    fn sums_match_sequential() {
+        let values: Vec<_> = (0..10_000u64)
+            .map(|i| Number::from_decimal(i, -3))
+            .collect();
+        assert_eq!(Ok(values.iter().sum::<Number>()), par_sum(&values));
+
+        let values = numbers_128(10_000);
+        let expected = values.iter().fold(Number128::ZERO, |acc, n| acc + *n);
+        assert_eq!(Ok(expected), par_sum(&values));
+        assert_eq!(Ok(Number128::ZERO), par_sum::<Number128>(&[]));
+    }
+
+    #[test]
+    fn weighted_sums_truncate_once() {
+        let third = Number::ONE / Number::from(3u64);
+        let values = vec![third; 3];
+        let weights = vec![Number::from_decimal(5, -1); 3];
+
+        // each product truncates to 0.1666..., but the sum keeps the digit
+        assert_eq!(
+            Ok(Number::from_decimal(499_999_999_999_999u64, -15)),
+            par_weighted_sum(&values, &weights)
+        );
+
+        // binary fractions round each product differently
+        #[cfg(not(feature = "binary-number128"))]
+        {
+            let values = numbers_128(1_000);
+            let weights: Vec<_> = values.iter().map(|n| -*n).collect();
+            let expected = values.iter().fold(Number128::ZERO, |acc, n| acc - *n * *n);
+            assert_eq!(Ok(expected), par_weighted_sum(&values, &weights));
+        }
+    }
+
+    #[test]
+    fn percentiles_match_sequential() {
+        let mut values = numbers_128(1_001);
+        let mut sorted = values.clone();
+
+        for bps in [0, 1, 2_500, 5_000, 9_999, 10_000] {
+            let p = Number128::from_bps(bps);
+            assert_eq!(percentile(&mut sorted, p), par_percentile(&mut values, p));
+        }
+
+        let mut values: Vec<_> = (1..=4u64).rev().map(Number::from).collect();
+        assert_eq!(
+            Ok(Number::from_decimal(25, -1)),
+            par_percentile(&mut values, Number::from_decimal(5, -1))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(Error::OutOfRange), par_sum(&[Number::MAX, Number::ONE]));
+        assert_eq!(
+            Ok(Number128::MAX),
+            par_sum(&[Number128::MAX, Number128::ONE, -Number128::ONE])
+        );
+        assert!(par_weighted_sum(&[Number::ONE], &[]).is_err());
+        assert!(par_percentile::<Number>(&mut [], Number::ONE).is_err());
+        assert!(par_percentile(&mut [Number::ONE], Number::from(2u64)).is_err());
+    }
+}
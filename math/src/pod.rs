@@ -0,0 +1,90 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{Number, Number128};
+
+/// A `Number` with a layout that doesn't depend on the toolchain, for
+/// storing in `#[account(zero_copy)]` structs.
+///
+/// The value is stored as 24 little-endian bytes, aligned to 8 bytes.
+/// Convert to a `Number` with `From` to do arithmetic.
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C, align(8))]
+pub struct PodNumber([u8; 24]);
+
+static_assertions::const_assert_eq!(24, std::mem::size_of::<PodNumber>());
+static_assertions::const_assert_eq!(8, std::mem::align_of::<PodNumber>());
+
+/// A `Number128` with a layout that doesn't depend on the toolchain, for
+/// storing in `#[account(zero_copy)]` structs.
+///
+/// The alignment of `i128` has changed between compiler versions, so a
+/// `Number128` embedded in an account can move between builds. This type
+/// stores the value as 16 little-endian bytes, aligned to 8 bytes.
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C, align(8))]
+pub struct PodNumber128([u8; 16]);
+
+static_assertions::const_assert_eq!(16, std::mem::size_of::<PodNumber128>());
+static_assertions::const_assert_eq!(8, std::mem::align_of::<PodNumber128>());
+
+impl From<Number> for PodNumber {
+    fn from(n: Number) -> Self {
+        Self(n.to_le_bytes())
+    }
+}
+
+impl From<PodNumber> for Number {
+    fn from(n: PodNumber) -> Self {
+        Number::from_le_bytes(n.0)
+    }
+}
+
+impl From<Number128> for PodNumber128 {
+    fn from(n: Number128) -> Self {
+        Self(n.to_le_bytes())
+    }
+}
+
+impl From<PodNumber128> for Number128 {
+    fn from(n: PodNumber128) -> Self {
+        Number128::from_le_bytes(n.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let number = Number::from_decimal(1242, -3);
+        let number_128 = Number128::from_decimal(-1242, -3);
+
+        assert_eq!(number, Number::from(PodNumber::from(number)));
+        assert_eq!(number_128, Number128::from(PodNumber128::from(number_128)));
+        assert_eq!(Number::ZERO, Number::from(PodNumber::default()));
+        assert_eq!(Number128::ZERO, Number128::from(PodNumber128::default()));
+    }
+
+    #[test]
+    fn stable_account_layout() {
+        #[derive(Pod, Zeroable, Clone, Copy)]
+        #[repr(C)]
+        struct Account {
+            flag: u64,
+            number_128: PodNumber128,
+            number: PodNumber,
+        }
+
+        let account = Account {
+            flag: 1,
+            number_128: Number128::from_decimal(1, 0).into(),
+            number: Number::from_decimal(1, 0).into(),
+        };
+        let bytes = bytemuck::bytes_of(&account);
+
+        assert_eq!(48, bytes.len());
+        assert_eq!(&Number128::ONE.to_le_bytes(), &bytes[8..24]);
+        assert_eq!(&Number::ONE.to_le_bytes(), &bytes[24..48]);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{Error, Number, Number128, Rounding};
+
+/// How every multiplication and division in a calculation is rounded, so
+/// that a whole instruction can round consistently (e.g. in favor of the
+/// protocol) without choosing a method at each call site.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RoundingPolicy {
+    pub rounding: Rounding,
+}
+
+impl RoundingPolicy {
+    /// Round towards zero, like the arithmetic operators
+    pub const TRUNCATE: Self = Self::new(Rounding::Down);
+
+    /// Round ties to even (banker's rounding), so that rounding errors
+    /// cancel out on average instead of accumulating
+    pub const BANKERS: Self = Self::new(Rounding::HalfEven);
+
+    pub const fn new(rounding: Rounding) -> Self {
+        Self { rounding }
+    }
+
+    /// Multiply two numbers
+    pub fn mul<T: RoundedOps>(&self, a: T, b: T) -> Result<T, Error> {
+        a.checked_mul_div_rounded(&b, &T::ONE, self.rounding)
+            .ok_or(Error::OutOfRange)
+    }
+
+    /// Divide two numbers
+    pub fn div<T: RoundedOps>(&self, a: T, b: T) -> Result<T, Error> {
+        if b == T::ZERO {
+            return Err(Error::DivideByZero);
+        }
+
+        a.checked_mul_div_rounded(&T::ONE, &b, self.rounding)
+            .ok_or(Error::OutOfRange)
+    }
+
+    /// Compute `a * b / c`, rounding only once
+    pub fn mul_div<T: RoundedOps>(&self, a: T, b: T, c: T) -> Result<T, Error> {
+        if c == T::ZERO {
+            return Err(Error::DivideByZero);
+        }
+
+        a.checked_mul_div_rounded(&b, &c, self.rounding)
+            .ok_or(Error::OutOfRange)
+    }
+}
+
+/// A number type whose multiplication and division can be rounded by a
+/// `RoundingPolicy`
+pub trait RoundedOps: Copy + Eq {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Compute `self * mul / div`, rounding as specified, returning `None`
+    /// on overflow or division by zero
+    fn checked_mul_div_rounded(&self, mul: &Self, div: &Self, rounding: Rounding) -> Option<Self>;
+}
+
+impl RoundedOps for Number {
+    const ZERO: Self = Number::ZERO;
+    const ONE: Self = Number::ONE;
+
+    fn checked_mul_div_rounded(&self, mul: &Self, div: &Self, rounding: Rounding) -> Option<Self> {
+        Number::checked_mul_div_rounded(self, mul, div, rounding)
+    }
+}
+
+impl RoundedOps for Number128 {
+    const ZERO: Self = Number128::ZERO;
+    const ONE: Self = Number128::ONE;
+
+    fn checked_mul_div_rounded(&self, mul: &Self, div: &Self, rounding: Rounding) -> Option<Self> {
+        Number128::checked_mul_div_rounded(self, mul, div, rounding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_round_every_operation() {
+        let third = Number::ONE / Number::from(3u64);
+
+        let up = RoundingPolicy::new(Rounding::Up);
+        assert_eq!(
+            Ok(third),
+            RoundingPolicy::TRUNCATE.div(Number::ONE, Number::from(3u64))
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(333_333_333_333_334u64, -15)),
+            up.div(Number::ONE, Number::from(3u64))
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(111_111_111_111_111u64, -15)),
+            up.mul(third, third)
+        );
+        assert_eq!(
+            Ok(Number::from_decimal(111_111_111_111_110u64, -15)),
+            RoundingPolicy::TRUNCATE.mul(third, third)
+        );
+
+        // 0.5 and 1.5 units round to the nearest even unit
+        let unit = Number128::from_decimal(1, -10);
+        let half = Number128::from_decimal(5, -1);
+        let bankers = RoundingPolicy::BANKERS;
+        assert_eq!(Ok(Number128::ZERO), bankers.mul(unit, half));
+        assert_eq!(Ok(unit * 2i128), bankers.mul(unit * 3i128, half));
+        assert_eq!(Ok(-unit * 2i128), bankers.mul(-unit * 3i128, half));
+        assert_eq!(
+            Ok(-unit),
+            RoundingPolicy::new(Rounding::Up).mul(-unit, half)
+        );
+    }
+
+    #[test]
+    fn mul_div_rounds_once() {
+        let policy = RoundingPolicy::new(Rounding::HalfUp);
+        let n = Number128::from_decimal(2, 0);
+
+        assert_eq!(
+            Ok(Number128::from_decimal(6_666_666_667i64, -10)),
+            policy.mul_div(n, Number128::ONE, Number128::from_decimal(3, 0))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let policy = RoundingPolicy::BANKERS;
+
+        assert_eq!(
+            Err(Error::DivideByZero),
+            policy.div(Number::ONE, Number::ZERO)
+        );
+        assert_eq!(
+            Err(Error::DivideByZero),
+            policy.mul_div(Number128::ONE, Number128::ONE, Number128::ZERO)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            policy.mul(Number::MAX, Number::from(2u64))
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            policy.mul(Number128::MIN, Number128::from_decimal(2, 0))
+        );
+    }
+}
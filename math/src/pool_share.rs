@@ -0,0 +1,144 @@
+//! Exchanging pool tokens (notes) for the value they represent.
+//!
+//! A pool holding `total_value` tokens against `total_notes` outstanding
+//! notes values each note at `total_value / total_notes`. Every conversion
+//! rounds in favor of the depositors remaining in the pool: deposits mint
+//! fewer notes and withdrawals burn more, so no one can extract value from
+//! the others through rounding.
+//!
+//! A pool with no notes outstanding exchanges notes one for one.
+
+use crate::{Error, Number};
+
+/// Notes to mint for a deposit of `amount`, rounded down
+pub fn deposit_notes_to_mint(
+    amount: u64,
+    total_notes: u64,
+    total_value: u64,
+) -> Result<u64, Error> {
+    if total_notes == 0 {
+        return Ok(amount);
+    }
+    if total_value == 0 {
+        return Err(Error::DivideByZero);
+    }
+
+    mul_div(amount, total_notes, total_value, false)
+}
+
+/// Notes to burn for a withdrawal of `amount`, rounded up
+pub fn withdraw_notes_to_burn(
+    amount: u64,
+    total_notes: u64,
+    total_value: u64,
+) -> Result<u64, Error> {
+    if amount > total_value {
+        return Err(Error::InvalidInput("withdrawal exceeds pool value"));
+    }
+    if total_notes == 0 {
+        return Ok(amount);
+    }
+    if total_value == 0 {
+        return Err(Error::DivideByZero);
+    }
+
+    mul_div(amount, total_notes, total_value, true)
+}
+
+/// The value redeemed by burning `notes`, rounded down
+pub fn notes_value(notes: u64, total_notes: u64, total_value: u64) -> Result<u64, Error> {
+    if notes > total_notes {
+        return Err(Error::InvalidInput("notes exceed total notes"));
+    }
+    if total_notes == 0 {
+        return Ok(0);
+    }
+
+    mul_div(notes, total_value, total_notes, false)
+}
+
+/// The value of one note, rounded down
+pub fn exchange_rate(total_notes: u64, total_value: u64) -> Number {
+    match total_notes {
+        0 => Number::ONE,
+        _ => Number::from(total_value) / total_notes,
+    }
+}
+
+/// The notes minted per unit of value deposited, rounded down
+///
+/// Returns an error if notes are outstanding but the pool has no value.
+pub fn note_rate(total_notes: u64, total_value: u64) -> Result<Number, Error> {
+    match (total_notes, total_value) {
+        (0, _) => Ok(Number::ONE),
+        (_, 0) => Err(Error::DivideByZero),
+        _ => Ok(Number::from(total_notes) / total_value),
+    }
+}
+
+fn mul_div(a: u64, b: u64, c: u64, round_up: bool) -> Result<u64, Error> {
+    let (product, divisor) = (a as u128 * b as u128, c as u128);
+    let (quotient, remainder) = (product / divisor, product % divisor);
+    let quotient = match round_up && remainder != 0 {
+        true => quotient + 1,
+        false => quotient,
+    };
+
+    u64::try_from(quotient).map_err(|_| Error::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_pool_is_one_to_one() {
+        assert_eq!(Ok(1_000), deposit_notes_to_mint(1_000, 0, 0));
+        assert_eq!(Ok(0), withdraw_notes_to_burn(0, 0, 0));
+        assert_eq!(Number::ONE, exchange_rate(0, 0));
+        assert_eq!(Ok(Number::ONE), note_rate(0, 0));
+    }
+
+    #[test]
+    fn rounds_in_favor_of_the_pool() {
+        // each note is worth 1.5 tokens
+        let (notes, value) = (2_000, 3_000);
+
+        assert_eq!(Ok(666), deposit_notes_to_mint(1_000, notes, value));
+        assert_eq!(Ok(667), withdraw_notes_to_burn(1_000, notes, value));
+        assert_eq!(Ok(1), notes_value(1, notes, value));
+        assert_eq!(Ok(3_000), notes_value(2_000, notes, value));
+        assert_eq!(Number::from_decimal(15u64, -1), exchange_rate(notes, value));
+        assert_eq!(
+            Ok(Number::from_decimal(666_666_666_666_666u64, -15)),
+            note_rate(notes, value)
+        );
+    }
+
+    #[test]
+    fn round_trips_never_profit() {
+        let (notes, value) = (7_777, 10_001);
+
+        for amount in [1, 3, 999, 5_000] {
+            let minted = deposit_notes_to_mint(amount, notes, value).unwrap();
+            let redeemed = notes_value(minted, notes + minted, value + amount).unwrap();
+            assert!(redeemed <= amount);
+
+            let burned = withdraw_notes_to_burn(amount, notes, value).unwrap();
+            assert!(notes_value(burned, notes, value).unwrap() >= amount);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(Error::DivideByZero), deposit_notes_to_mint(1, 10, 0));
+        assert_eq!(Err(Error::DivideByZero), note_rate(10, 0));
+        assert_eq!(Err(Error::DivideByZero), withdraw_notes_to_burn(0, 10, 0));
+        assert!(withdraw_notes_to_burn(11, 10, 10).is_err());
+        assert!(notes_value(11, 10, 10).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            deposit_notes_to_mint(u64::MAX, u64::MAX, 1)
+        );
+    }
+}
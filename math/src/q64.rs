@@ -0,0 +1,233 @@
+//! Conversions between `Number128` and the binary fixed-point encodings
+//! used by concentrated liquidity AMMs.
+//!
+//! Prices are encoded as Q64.64 (a `u128` with 64 fractional bits), and
+//! Uniswap v3 style pools store the square root of the price as Q64.96 (160
+//! bits, held in a `U256`). Every conversion takes the rounding to apply,
+//! so that callers can always round in the pool's favor.
+
+use crate::bigint::{U256, U384};
+use crate::{div_rounded, Error, Number128, Rounding};
+
+const Q64_FRACTION_BITS: usize = 64;
+const X96_FRACTION_BITS: usize = 96;
+const X96_BITS: usize = 160;
+
+/// Encode a price as Q64.64
+pub fn to_q64_64(price: Number128, rounding: Rounding) -> Result<u128, Error> {
+    to_u128(to_fixed(price, Q64_FRACTION_BITS, rounding)?)
+}
+
+/// Decode a Q64.64 price
+pub fn from_q64_64(value: u128, rounding: Rounding) -> Result<Number128, Error> {
+    from_fixed(U384::from(value), Q64_FRACTION_BITS, rounding)
+}
+
+/// Encode a price as Q64.96
+pub fn to_q64_96(price: Number128, rounding: Rounding) -> Result<U256, Error> {
+    to_u160(to_fixed(price, X96_FRACTION_BITS, rounding)?)
+}
+
+/// Decode a Q64.96 price
+pub fn from_q64_96(value: U256, rounding: Rounding) -> Result<Number128, Error> {
+    from_fixed(U384::from(value), X96_FRACTION_BITS, rounding)
+}
+
+/// The price for a square root price encoded as Q64.64
+pub fn sqrt_price_to_price(sqrt_price: u128, rounding: Rounding) -> Result<Number128, Error> {
+    square(U384::from(sqrt_price), Q64_FRACTION_BITS, rounding)
+}
+
+/// The square root of a price, encoded as Q64.64
+pub fn price_to_sqrt_price(price: Number128, rounding: Rounding) -> Result<u128, Error> {
+    to_u128(sqrt(price, Q64_FRACTION_BITS, rounding)?)
+}
+
+/// The price for a square root price encoded as Q64.96, like Uniswap v3's
+/// `sqrtPriceX96`
+///
+/// Returns an error if the value does not fit in 160 bits.
+pub fn sqrt_price_x96_to_price(sqrt_price: U256, rounding: Rounding) -> Result<Number128, Error> {
+    if sqrt_price.bits() > X96_BITS {
+        return Err(Error::OutOfRange);
+    }
+
+    square(U384::from(sqrt_price), X96_FRACTION_BITS, rounding)
+}
+
+/// The square root of a price, encoded as Q64.96
+pub fn price_to_sqrt_price_x96(price: Number128, rounding: Rounding) -> Result<U256, Error> {
+    to_u160(sqrt(price, X96_FRACTION_BITS, rounding)?)
+}
+
+fn to_fixed(price: Number128, fraction_bits: usize, rounding: Rounding) -> Result<U384, Error> {
+    let (raw, one) = unsigned_parts(price)?;
+
+    Ok(div_rounded(raw << fraction_bits, one, rounding))
+}
+
+fn from_fixed(value: U384, fraction_bits: usize, rounding: Rounding) -> Result<Number128, Error> {
+    let one = U384::from(Number128::ONE.to_i128() as u128);
+
+    to_number(div_rounded(
+        value * one,
+        U384::one() << fraction_bits,
+        rounding,
+    ))
+}
+
+fn square(sqrt_price: U384, fraction_bits: usize, rounding: Rounding) -> Result<Number128, Error> {
+    let one = U384::from(Number128::ONE.to_i128() as u128);
+    let squared = sqrt_price * sqrt_price;
+
+    to_number(div_rounded(
+        squared * one,
+        U384::one() << (2 * fraction_bits),
+        rounding,
+    ))
+}
+
+fn sqrt(price: Number128, fraction_bits: usize, rounding: Rounding) -> Result<U384, Error> {
+    let (raw, one) = unsigned_parts(price)?;
+    let (numerator, denominator) = (raw << (2 * fraction_bits), one);
+
+    // the floor of the root of the floor is the floor of the exact root
+    let root = (numerator / denominator).integer_sqrt();
+    let exact = root * root * denominator == numerator;
+
+    let round_up = !exact
+        && match rounding {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp | Rounding::HalfEven => {
+                // compare the exact root with root + 1/2
+                let doubled = root * 2 + 1;
+                let lhs = numerator * 4;
+                let rhs = doubled * doubled * denominator;
+
+                lhs > rhs || (lhs == rhs && (rounding == Rounding::HalfUp || root.bit(0)))
+            }
+        };
+
+    Ok(match round_up {
+        true => root + 1,
+        false => root,
+    })
+}
+
+fn unsigned_parts(price: Number128) -> Result<(U384, U384), Error> {
+    if price < Number128::ZERO {
+        return Err(Error::InvalidInput("negative price"));
+    }
+
+    Ok((
+        U384::from(price.to_i128() as u128),
+        U384::from(Number128::ONE.to_i128() as u128),
+    ))
+}
+
+fn to_number(value: U384) -> Result<Number128, Error> {
+    match value > U384::from(i128::MAX as u128) {
+        true => Err(Error::OutOfRange),
+        false => Ok(Number128::from_i128(value.low_u128() as i128)),
+    }
+}
+
+fn to_u128(value: U384) -> Result<u128, Error> {
+    match value.bits() > 128 {
+        true => Err(Error::OutOfRange),
+        false => Ok(value.low_u128()),
+    }
+}
+
+fn to_u160(value: U384) -> Result<U256, Error> {
+    match value.bits() > X96_BITS {
+        true => Err(Error::OutOfRange),
+        false => Ok(value.checked_as_u256().unwrap()),
+    }
+}
+
+#[cfg(test)]
+mod compat_tests {
+    use super::*;
+
+    const X96_ONE: U256 = U256([0, 1 << 32, 0, 0]);
+
+    fn number(value: i128, exponent: i32) -> Number128 {
+        Number128::from_decimal(value, exponent)
+    }
+
+    #[test]
+    fn q64_round_trip() {
+        assert_eq!(Ok(3 << 63), to_q64_64(number(15, -1), Rounding::Down));
+        assert_eq!(Ok(number(15, -1)), from_q64_64(3 << 63, Rounding::Down));
+        assert_eq!(Ok(X96_ONE), to_q64_96(Number128::ONE, Rounding::Up));
+        assert_eq!(Ok(number(25, -2)), from_q64_96(X96_ONE >> 2, Rounding::Up));
+    }
+
+    #[test]
+    fn sqrt_prices() {
+        assert_eq!(
+            Ok(2 << 64),
+            price_to_sqrt_price(number(4, 0), Rounding::Down)
+        );
+        assert_eq!(
+            Ok(number(4, 0)),
+            sqrt_price_to_price(2 << 64, Rounding::Down)
+        );
+        assert_eq!(
+            Ok(X96_ONE),
+            price_to_sqrt_price_x96(Number128::ONE, Rounding::Up)
+        );
+        assert_eq!(
+            Ok(number(25, -2)),
+            sqrt_price_x96_to_price(X96_ONE >> 1, Rounding::Up)
+        );
+    }
+
+    #[test]
+    fn rounding_directions() {
+        let two = number(2, 0);
+        let down = price_to_sqrt_price(two, Rounding::Down).unwrap();
+        let up = price_to_sqrt_price(two, Rounding::Up).unwrap();
+        let nearest = price_to_sqrt_price(two, Rounding::HalfUp).unwrap();
+
+        // sqrt(2) * 2^64 = 26087635650665564424.699...
+        assert_eq!(26_087_635_650_665_564_424, down);
+        assert_eq!(down + 1, up);
+        assert_eq!(up, nearest);
+
+        assert_eq!(Ok(Number128::ZERO), from_q64_64(1, Rounding::Down));
+        assert_eq!(Ok(Number128::from_i128(1)), from_q64_64(1, Rounding::Up));
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(to_q64_64(number(-1, 0), Rounding::Down).is_err());
+        assert!(price_to_sqrt_price(number(-1, 0), Rounding::Down).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            to_q64_64(Number128::MAX, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            sqrt_price_to_price(u128::MAX, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            sqrt_price_x96_to_price(U256::one() << X96_BITS, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            sqrt_price_x96_to_price(U256::one() << 200, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            sqrt_price_x96_to_price(U256::MAX, Rounding::Down)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            sqrt_price_x96_to_price((U256::one() << X96_BITS) - 1, Rounding::Up)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Swap quotes for constant product pools.
+//!
+//! A quote charges the pool's fee schedule on the input, then swaps the
+//! rest along `x * y = k`. Every amount is rounded in the pool's favor, so
+//! a program can check a swap against `simulate_swap` and a frontend can
+//! show the same numbers before the swap is sent.
+
+use crate::fees::FeeSchedule;
+use crate::{slippage, Error, Number, Rounding};
+
+/// The state of a pool needed to quote a swap in one direction
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PoolState {
+    /// The pool's balance of the token being swapped in
+    pub reserve_in: u64,
+    /// The pool's balance of the token being swapped out
+    pub reserve_out: u64,
+    /// The fees charged on the input amount
+    pub fees: FeeSchedule,
+}
+
+/// The outcome of a simulated swap
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Quote {
+    /// The amount received
+    pub out: u64,
+    /// The fee charged, out of the input amount
+    pub fee: u64,
+    /// How far the execution price falls short of the spot price, as a
+    /// fraction of the spot price, not counting the fee
+    pub price_impact: Number,
+}
+
+impl Quote {
+    /// The smallest amount out to accept for this quote
+    pub fn min_out(&self, max_slippage_bps: u16) -> Result<u64, Error> {
+        slippage::min_out(self.out, max_slippage_bps)
+    }
+}
+
+/// Quote a swap of `amount_in` against a pool
+pub fn simulate_swap(pool: &PoolState, amount_in: u64) -> Result<Quote, Error> {
+    if pool.reserve_in == 0 || pool.reserve_out == 0 {
+        return Err(Error::InvalidInput("empty pool"));
+    }
+
+    let (fee, net_in) = pool.fees.apply(amount_in);
+    if net_in == 0 {
+        return Ok(Quote {
+            out: 0,
+            fee,
+            price_impact: Number::ZERO,
+        });
+    }
+
+    let reserve_in = Number::from(pool.reserve_in);
+    let reserve_out = Number::from(pool.reserve_out);
+    let net_in = Number::from(net_in);
+
+    let out = reserve_out
+        .checked_mul_div_rounded(&net_in, &(reserve_in + net_in), Rounding::Down)
+        .ok_or(Error::OutOfRange)?
+        .to_token_amount(0, Rounding::Down)?;
+
+    // the execution price relative to the spot price, rounded down so that
+    // the impact is never understated
+    let execution = Number::from(out)
+        .checked_mul_div_rounded(&reserve_in, &(net_in * reserve_out), Rounding::Down)
+        .ok_or(Error::OutOfRange)?;
+
+    Ok(Quote {
+        out,
+        fee,
+        price_impact: Number::ONE.saturating_sub(execution),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fees::FeeTier;
+
+    fn pool(reserve_in: u64, reserve_out: u64, fee_bps: u16) -> PoolState {
+        PoolState {
+            reserve_in,
+            reserve_out,
+            fees: FeeSchedule::new(&[FeeTier::new(0, fee_bps)]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn swaps_along_the_curve() {
+        let quote = simulate_swap(&pool(1_000_000, 2_000_000, 30), 10_000).unwrap();
+
+        // 9_970 in after the fee, for 2_000_000 * 9_970 / 1_009_970
+        assert_eq!(30, quote.fee);
+        assert_eq!(19_743, quote.out);
+        assert_eq!(
+            Number::from_decimal(9_879_638_916_751u64, -15),
+            quote.price_impact
+        );
+        assert_eq!(Ok(19_644), quote.min_out(50));
+    }
+
+    #[test]
+    fn price_impact_grows_with_size() {
+        let pool = pool(1_000_000, 1_000_000, 0);
+
+        let small = simulate_swap(&pool, 1_000).unwrap();
+        let large = simulate_swap(&pool, 1_000_000).unwrap();
+
+        assert_eq!(999, small.out);
+        assert_eq!(500_000, large.out);
+        assert_eq!(Number::from_decimal(5u64, -1), large.price_impact);
+        assert!(small.price_impact < large.price_impact);
+    }
+
+    #[test]
+    fn rounds_in_favor_of_the_pool() {
+        let quote = simulate_swap(&pool(3, 10, 0), 1).unwrap();
+
+        // 10 * 1 / 4 = 2.5
+        assert_eq!(2, quote.out);
+        assert_eq!(Number::from_decimal(4u64, -1), quote.price_impact);
+
+        let quote = simulate_swap(&pool(1_000, 1_000, 100), 1).unwrap();
+        assert_eq!((0, 1), (quote.out, quote.fee));
+    }
+
+    #[test]
+    fn extreme_reserves() {
+        let quote = simulate_swap(&pool(u64::MAX, u64::MAX, 0), u64::MAX).unwrap();
+        assert_eq!(u64::MAX / 2, quote.out);
+
+        assert_eq!(
+            Err(Error::InvalidInput("empty pool")),
+            simulate_swap(&pool(0, 1_000, 0), 1)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Exchange rates with a type-checked direction.
+//!
+//! A `Rate<Base, Quote>` is the amount of `Quote` that one unit of `Base` is
+//! worth, e.g. `Rate<Sol, Usd>` is the price of SOL in USD. The marker types
+//! are only used at compile time, so inverting or composing rates in the
+//! wrong direction fails to build instead of silently producing a bad price.
+
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::Mul;
+
+use crate::{Error, Number128};
+
+/// The price of one unit of `Base`, denominated in `Quote`
+pub struct Rate<Base, Quote> {
+    value: Number128,
+    direction: PhantomData<fn() -> (Base, Quote)>,
+}
+
+impl<Base, Quote> Rate<Base, Quote> {
+    /// Create a rate from the price of one `Base` in `Quote`
+    pub const fn new(value: Number128) -> Self {
+        Self {
+            value,
+            direction: PhantomData,
+        }
+    }
+
+    /// The price of one `Base` in `Quote`
+    pub fn value(&self) -> Number128 {
+        self.value
+    }
+
+    /// Get the rate in the opposite direction
+    pub fn invert(self) -> Result<Rate<Quote, Base>, Error> {
+        if self.value == Number128::ZERO {
+            return Err(Error::DivideByZero);
+        }
+
+        Number128::ONE
+            .checked_div(&self.value)
+            .map(Rate::new)
+            .ok_or(Error::OutOfRange)
+    }
+
+    /// Chain this rate with one from `Quote` to `Other`
+    pub fn compose<Other>(self, rhs: Rate<Quote, Other>) -> Result<Rate<Base, Other>, Error> {
+        self.value
+            .checked_mul(&rhs.value)
+            .map(Rate::new)
+            .ok_or(Error::OutOfRange)
+    }
+
+    /// Convert an amount of `Base` into the equivalent amount of `Quote`
+    pub fn convert(&self, amount: Number128) -> Number128 {
+        amount * self.value
+    }
+}
+
+impl<Base, Quote, Other> Mul<Rate<Quote, Other>> for Rate<Base, Quote> {
+    type Output = Rate<Base, Other>;
+
+    fn mul(self, rhs: Rate<Quote, Other>) -> Self::Output {
+        self.compose(rhs).unwrap()
+    }
+}
+
+impl<Base, Quote> From<Rate<Base, Quote>> for Number128 {
+    fn from(rate: Rate<Base, Quote>) -> Self {
+        rate.value
+    }
+}
+
+impl<Base, Quote> Clone for Rate<Base, Quote> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Base, Quote> Copy for Rate<Base, Quote> {}
+
+impl<Base, Quote> PartialEq for Rate<Base, Quote> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<Base, Quote> Eq for Rate<Base, Quote> {}
+
+impl<Base, Quote> Debug for Rate<Base, Quote> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Rate<{}, {}>({})",
+            std::any::type_name::<Base>(),
+            std::any::type_name::<Quote>(),
+            self.value
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sol;
+    struct Usd;
+    struct Usdc;
+
+    #[test]
+    fn compose_rates() {
+        let sol_usd = Rate::<Sol, Usd>::new(Number128::from_decimal(25, 0));
+        let usd_usdc = Rate::<Usd, Usdc>::new(Number128::from_decimal(99, -2));
+        let sol_usdc: Rate<Sol, Usdc> = sol_usd * usd_usdc;
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(Number128::from_decimal(2475, -2), sol_usdc.value());
+        assert_eq!(Ok(sol_usdc), sol_usd.compose(usd_usdc));
+    }
+
+    #[test]
+    fn invert_rate() {
+        let sol_usd = Rate::<Sol, Usd>::new(Number128::from_decimal(25, 0));
+        let usd_sol: Rate<Usd, Sol> = sol_usd.invert().unwrap();
+
+        assert_eq!(Number128::from_decimal(4, -2), usd_sol.value());
+        assert_eq!(
+            Err(Error::DivideByZero),
+            Rate::<Sol, Usd>::new(Number128::ZERO).invert()
+        );
+    }
+
+    #[test]
+    fn convert_amount() {
+        let sol_usd = Rate::<Sol, Usd>::new(Number128::from_decimal(25, 0));
+
+        assert_eq!(
+            Number128::from_decimal(50, 0),
+            sol_usd.convert(Number128::from_decimal(2, 0))
+        );
+        assert_eq!(Number128::from_decimal(25, 0), Number128::from(sol_usd));
+    }
+}
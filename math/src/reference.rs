@@ -0,0 +1,260 @@
+//! An arbitrary-precision reference implementation of the number types,
+//! for certifying their precision off chain.
+//!
+//! A [`Reference`] stores the exact value of a number as a `BigDecimal`,
+//! and has the same checked operations as the fixed-point type it mirrors.
+//! Each operation computes the exact result before rounding it once, the
+//! same way the fixed-point type specifies (toward zero), and fails if the
+//! rounded result is out of the type's range.
+//!
+//! [`check_number`] and [`check_number_128`] run every operation on
+//! randomized inputs against the reference. An empty list of mismatches
+//! certifies that each result is within one unit in the last place of the
+//! exact value, and that overflow is detected exactly.
+
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::BigDecimal;
+
+use crate::bigint::U192;
+use crate::test_vectors::Rng;
+use crate::{Number, Number128};
+
+/// A fixed-point type that can be checked against a `Reference`
+pub trait FixedPoint: Copy {
+    /// The underlying integer, i.e. the value scaled by `one`
+    fn raw(&self) -> BigInt;
+
+    /// The number for an underlying integer, or `None` if it is out of range
+    fn from_raw(raw: &BigInt) -> Option<Self>;
+
+    /// The underlying integer for one
+    fn one() -> BigInt;
+}
+
+impl FixedPoint for Number {
+    fn raw(&self) -> BigInt {
+        BigInt::from_str(&self.to_u192().to_string()).unwrap()
+    }
+
+    fn from_raw(raw: &BigInt) -> Option<Self> {
+        U192::from_dec_str(&raw.to_string())
+            .ok()
+            .map(Number::from_u192)
+    }
+
+    fn one() -> BigInt {
+        Number::ONE.raw()
+    }
+}
+
+impl FixedPoint for Number128 {
+    fn raw(&self) -> BigInt {
+        BigInt::from(self.to_i128())
+    }
+
+    fn from_raw(raw: &BigInt) -> Option<Self> {
+        i128::try_from(raw).ok().map(Number128::from_i128)
+    }
+
+    fn one() -> BigInt {
+        Number128::ONE.raw()
+    }
+}
+
+/// The exact value of a fixed-point number
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reference<T> {
+    value: BigDecimal,
+    number: PhantomData<T>,
+}
+
+impl<T: FixedPoint> Reference<T> {
+    pub fn new(number: &T) -> Self {
+        Self::from_raw(number.raw())
+    }
+
+    /// The exact value
+    pub fn value(&self) -> &BigDecimal {
+        &self.value
+    }
+
+    /// The fixed-point number with this value
+    pub fn to_fixed(&self) -> T {
+        T::from_raw(&self.raw()).unwrap()
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Self::round(&self.value + &rhs.value)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Self::round(&self.value - &rhs.value)
+    }
+
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Self::round(&self.value * &rhs.value)
+    }
+
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        let divisor = rhs.raw();
+        if divisor == BigInt::from(0) {
+            return None;
+        }
+
+        // `BigDecimal` division has limited precision, so divide the
+        // underlying integers instead, which truncates toward zero
+        Self::checked_from_raw(self.raw() * T::one() / divisor)
+    }
+
+    /// Round an exact value toward zero to a representable number
+    fn round(value: BigDecimal) -> Option<Self> {
+        let (raw, _) = (value * BigDecimal::from(T::one()))
+            .with_scale(0)
+            .into_bigint_and_exponent();
+
+        Self::checked_from_raw(raw)
+    }
+
+    fn checked_from_raw(raw: BigInt) -> Option<Self> {
+        T::from_raw(&raw).map(|_| Self::from_raw(raw))
+    }
+
+    fn from_raw(raw: BigInt) -> Self {
+        Self {
+            value: BigDecimal::from(raw) / BigDecimal::from(T::one()),
+            number: PhantomData,
+        }
+    }
+
+    fn raw(&self) -> BigInt {
+        let (raw, _) = (&self.value * BigDecimal::from(T::one()))
+            .with_scale(0)
+            .into_bigint_and_exponent();
+
+        raw
+    }
+}
+
+/// An operation where the fixed-point result differs from the reference
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub op: &'static str,
+    pub inputs: Vec<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+type FixedOp<T> = fn(&T, &T) -> Option<T>;
+type ReferenceOp<T> = fn(&Reference<T>, &Reference<T>) -> Option<Reference<T>>;
+
+/// Check `count` random inputs for each operation on `Number` against the
+/// reference, returning every mismatch
+pub fn check_number(seed: u64, count: usize) -> Vec<Mismatch> {
+    let ops: [(&str, FixedOp<Number>, ReferenceOp<Number>); 4] = [
+        ("add", Number::checked_add, Reference::checked_add),
+        ("sub", Number::checked_sub, Reference::checked_sub),
+        ("mul", Number::checked_mul, Reference::checked_mul),
+        ("div", Number::checked_div, Reference::checked_div),
+    ];
+
+    check(ops, count, || Rng::new(seed), Rng::number)
+}
+
+/// Check `count` random inputs for each operation on `Number128` against
+/// the reference, returning every mismatch
+pub fn check_number_128(seed: u64, count: usize) -> Vec<Mismatch> {
+    let ops: [(&str, FixedOp<Number128>, ReferenceOp<Number128>); 4] = [
+        ("add", Number128::checked_add, Reference::checked_add),
+        ("sub", Number128::checked_sub, Reference::checked_sub),
+        ("mul", Number128::checked_mul, Reference::checked_mul),
+        ("div", Number128::checked_div, Reference::checked_div),
+    ];
+
+    check(ops, count, || Rng::new(seed), Rng::number_128)
+}
+
+fn check<T: FixedPoint>(
+    ops: [(&'static str, FixedOp<T>, ReferenceOp<T>); 4],
+    count: usize,
+    rng: impl Fn() -> Rng,
+    random: fn(&mut Rng) -> T,
+) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+
+    for (op, fixed, reference) in ops {
+        let mut rng = rng();
+
+        for _ in 0..count {
+            let (a, b) = (random(&mut rng), random(&mut rng));
+            let actual = fixed(&a, &b).map(|n| n.raw());
+            let expected = reference(&Reference::new(&a), &Reference::new(&b)).map(|r| r.raw());
+
+            if actual != expected {
+                mismatches.push(Mismatch {
+                    op,
+                    inputs: vec![a.raw().to_string(), b.raw().to_string()],
+                    expected: expected.map(|r| r.to_string()),
+                    actual: actual.map(|r| r.to_string()),
+                });
+            }
+        }
+    }
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_rounds_toward_zero() {
+        let three = Reference::new(&Number128::from_decimal(3, 0));
+        let third = Reference::new(&Number128::ONE).checked_div(&three).unwrap();
+        let negative = Reference::new(&Number128::from_decimal(-1, 0))
+            .checked_div(&three)
+            .unwrap();
+
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Number128::from_decimal(3_333_333_333i64, -10),
+            third.to_fixed()
+        );
+        assert_eq!(-third.to_fixed(), negative.to_fixed());
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Number128::from_decimal(9_999_999_999i64, -10),
+            third.checked_mul(&three).unwrap().to_fixed()
+        );
+    }
+
+    #[test]
+    fn reference_detects_overflow() {
+        let max = Reference::new(&Number::MAX);
+
+        assert_eq!(None, max.checked_add(&Reference::new(&Number::ONE)));
+        assert_eq!(None, max.checked_div(&Reference::new(&Number::ZERO)));
+        assert_eq!(
+            None,
+            Reference::new(&Number::ZERO).checked_sub(&Reference::new(&Number::ONE))
+        );
+        assert_eq!(
+            Some(Number::MAX),
+            max.checked_mul(&Reference::new(&Number::ONE))
+                .map(|r| r.to_fixed())
+        );
+    }
+
+    #[test]
+    fn number_matches_reference() {
+        assert_eq!(Vec::<Mismatch>::new(), check_number(0, 1_000));
+    }
+
+    #[test]
+    fn number_128_matches_reference() {
+        assert_eq!(Vec::<Mismatch>::new(), check_number_128(0, 1_000));
+    }
+}
@@ -0,0 +1,230 @@
+use std::ops::{Add, AddAssign};
+
+use crate::bigint::{U192, U256, U384};
+use crate::{Number, Number128};
+
+/// The part of a result that was discarded by rounding toward zero,
+/// measured in units of the smallest representable value, so a single
+/// remainder is always less than one unit.
+///
+/// Remainders can be summed, so that the dust lost over many operations
+/// is accounted for explicitly, and then credited somewhere (e.g. to a
+/// treasury) with `take_units` once it adds up to whole units.
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Remainder<T>(T);
+
+impl<T: Copy> Remainder<T> {
+    /// The discarded amount, as a number of the smallest units, e.g. 0.5
+    /// for half of one unit
+    pub fn units(&self) -> T {
+        self.0
+    }
+}
+
+impl Remainder<Number> {
+    pub const ZERO: Self = Self(Number::ZERO);
+
+    /// Remove the whole units from the accumulated remainder, returning them
+    /// as a `Number`
+    pub fn take_units(&mut self) -> Number {
+        let (units, fraction) = self.0.to_u192().div_rem(Number::ONE.to_u192());
+        self.0 = Number::from_u192(fraction);
+
+        Number::from_u192(units)
+    }
+}
+
+impl Remainder<Number128> {
+    pub const ZERO: Self = Self(Number128::ZERO);
+
+    /// Remove the whole units from the accumulated remainder, returning them
+    /// as a `Number128`. Any units left over have the same sign as the
+    /// units taken.
+    pub fn take_units(&mut self) -> Number128 {
+        let (value, one) = (self.0.to_i128(), Number128::ONE.to_i128());
+        self.0 = Number128::from_i128(value % one);
+
+        Number128::from_i128(value / one)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Remainder<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Remainder<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Number {
+    /// Multiply, returning the result rounded down along with the part
+    /// that was discarded
+    ///
+    /// Panics on overflow.
+    pub fn mul_with_remainder(&self, rhs: &Number) -> (Number, Remainder<Number>) {
+        mul_div_with_remainder(self.to_u192(), rhs.to_u192(), Number::ONE.to_u192())
+    }
+
+    /// Divide, returning the result rounded down along with the part that
+    /// was discarded
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_with_remainder(&self, rhs: &Number) -> (Number, Remainder<Number>) {
+        mul_div_with_remainder(self.to_u192(), Number::ONE.to_u192(), rhs.to_u192())
+    }
+}
+
+impl Number128 {
+    /// Multiply, returning the result rounded toward zero along with the
+    /// part that was discarded, which has the same sign as the result
+    ///
+    /// Panics on overflow.
+    pub fn mul_with_remainder(&self, rhs: &Number128) -> (Number128, Remainder<Number128>) {
+        mul_div_with_remainder_128(self.to_i128(), rhs.to_i128(), Number128::ONE.to_i128())
+    }
+
+    /// Divide, returning the result rounded toward zero along with the
+    /// part that was discarded, which has the same sign as the result
+    ///
+    /// Panics on overflow or division by zero.
+    pub fn div_with_remainder(&self, rhs: &Number128) -> (Number128, Remainder<Number128>) {
+        mul_div_with_remainder_128(self.to_i128(), Number128::ONE.to_i128(), rhs.to_i128())
+    }
+}
+
+/// Compute `a * b / c`, and the fraction of a unit that was discarded,
+/// scaled by `ONE`
+fn mul_div_with_remainder(a: U192, b: U192, c: U192) -> (Number, Remainder<Number>) {
+    assert!(!c.is_zero(), "division by zero");
+
+    let c = U384::from(c);
+    let (quotient, remainder) = (U384::from(a) * U384::from(b)).div_mod(c);
+    let quotient = quotient.checked_as_u192().expect("overflow");
+
+    // the remainder is less than `c`, so this fits in a U192
+    let fraction = remainder * U384::from(Number::ONE.to_u192()) / c;
+    let fraction = fraction.checked_as_u192().unwrap();
+
+    (
+        Number::from_u192(quotient),
+        Remainder(Number::from_u192(fraction)),
+    )
+}
+
+/// Compute `a * b / c`, and the fraction of a unit that was discarded,
+/// scaled by `ONE`
+fn mul_div_with_remainder_128(a: i128, b: i128, c: i128) -> (Number128, Remainder<Number128>) {
+    assert!(c != 0, "division by zero");
+
+    let negative = (a < 0) ^ (b < 0) ^ (c < 0);
+    let c = U256::from(c.unsigned_abs());
+    let product = U256::from(a.unsigned_abs()) * U256::from(b.unsigned_abs());
+    let (quotient, remainder) = product.div_rem(c);
+
+    // the remainder is less than `c`, so this fits in a u128
+    let fraction = remainder * U256::from(Number128::ONE.to_i128()) / c;
+    let fraction = fraction.as_u128() as i128;
+
+    assert!(quotient.bits() <= 128, "overflow");
+    let quotient = quotient.as_u128();
+    let (quotient, fraction) = match negative {
+        true => (0i128.checked_sub_unsigned(quotient), -fraction),
+        false => (i128::try_from(quotient).ok(), fraction),
+    };
+
+    (
+        Number128::from_i128(quotient.expect("overflow")),
+        Remainder(Number128::from_i128(fraction)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_remainders() {
+        let third = Number::ONE / Number::from(3u64);
+        let (result, remainder) = Number::ONE.div_with_remainder(&Number::from(3u64));
+
+        assert_eq!(third, result);
+        assert_eq!(
+            Number::from_decimal(333_333_333_333_333u64, -15),
+            remainder.units()
+        );
+
+        let (result, remainder) = third.mul_with_remainder(&Number::from_decimal(5, -1));
+        assert_eq!(Number::from_decimal(166_666_666_666_666u64, -15), result);
+        assert_eq!(Number::from_decimal(5, -1), remainder.units());
+
+        let (result, remainder) = Number::ONE.mul_with_remainder(&Number::from(3u64));
+        assert_eq!(Number::from(3u64), result);
+        assert_eq!(Remainder::<Number>::ZERO, remainder);
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn number_128_remainders() {
+        let three = Number128::from_decimal(3, 0);
+        let (result, remainder) = Number128::ONE.div_with_remainder(&three);
+
+        assert_eq!(Number128::from_decimal(3_333_333_333i64, -10), result);
+        assert_eq!(
+            Number128::from_decimal(3_333_333_333i64, -10),
+            remainder.units()
+        );
+
+        let (result, remainder) = (-Number128::ONE).div_with_remainder(&three);
+        assert_eq!(Number128::from_decimal(-3_333_333_333i64, -10), result);
+        assert_eq!(
+            Number128::from_decimal(-3_333_333_333i64, -10),
+            remainder.units()
+        );
+
+        let half = Number128::from_decimal(5, -1);
+        let (result, remainder) = Number128::from_decimal(-1, -10).mul_with_remainder(&half);
+        assert_eq!(Number128::ZERO, result);
+        assert_eq!(-half, remainder.units());
+    }
+
+    #[test]
+    fn accumulates_dust() {
+        let amount = Number::from_decimal(1, -15);
+        let half = Number::from_decimal(5, -1);
+        let mut dust = Remainder::<Number>::ZERO;
+        let mut total = Number::ZERO;
+
+        for _ in 0..5 {
+            let (result, remainder) = amount.mul_with_remainder(&half);
+            total += result;
+            dust += remainder;
+        }
+
+        // each product is half of one unit, so five of them lose 2.5 units
+        assert_eq!(Number::ZERO, total);
+        assert_eq!(Number::from_decimal(2, -15), dust.take_units());
+        assert_eq!(Number::from_decimal(5, -1), dust.units());
+        assert_eq!(Number::ZERO, dust.take_units());
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn negative_dust() {
+        let mut dust = Remainder(Number128::from_decimal(-25, -1));
+
+        assert_eq!(Number128::from_decimal(-2, -10), dust.take_units());
+        assert_eq!(Number128::from_decimal(-5, -1), dust.units());
+    }
+
+    #[test]
+    #[should_panic]
+    fn overflow_panics() {
+        Number::MAX.mul_with_remainder(&Number::from(2u64));
+    }
+}
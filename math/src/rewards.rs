@@ -0,0 +1,102 @@
+//! The "accumulated reward per share" pattern for distributing rewards
+//! to a changing set of shareholders.
+//!
+//! Every time rewards are funded, the reward per share grows by
+//! `rewards / total_shares`. A user records a debt of
+//! `reward_per_share * shares` whenever their shares change, and is owed
+//! the difference between that and the current value.
+//!
+//! The per-share increment and every user payout are rounded down, so the
+//! sum of all payouts can never exceed the funded amount.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::number::*;
+
+#[derive(Pod, Zeroable, Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct RewardAccumulator {
+    /// The total rewards distributed per share since the beginning
+    pub reward_per_share: Number,
+}
+
+impl RewardAccumulator {
+    /// Distribute newly funded rewards across all shares
+    ///
+    /// Returns an error if there are no shares to distribute to.
+    pub fn update_accumulator(
+        &mut self,
+        total_rewards: u64,
+        total_shares: u64,
+    ) -> Result<(), Error> {
+        if total_shares == 0 {
+            return Err(Error::DivideByZero);
+        }
+
+        let increment = Number::from(total_rewards) / total_shares;
+        self.reward_per_share = self
+            .reward_per_share
+            .checked_add(&increment)
+            .ok_or(Error::Overflow(self.reward_per_share))?;
+
+        Ok(())
+    }
+
+    /// The debt to record for a user holding some number of shares
+    pub fn debt(&self, user_shares: u64) -> Number {
+        self.reward_per_share * user_shares
+    }
+
+    /// The rewards owed to a user, given their shares and recorded debt
+    pub fn pending(&self, user_shares: u64, user_debt: Number) -> u64 {
+        self.debt(user_shares).saturating_sub(user_debt).as_u64(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_shares() {
+        let mut acc = RewardAccumulator::default();
+        assert_eq!(Err(Error::DivideByZero), acc.update_accumulator(100, 0));
+    }
+
+    #[test]
+    fn single_user_gets_everything() {
+        let mut acc = RewardAccumulator::default();
+        acc.update_accumulator(1_000, 50).unwrap();
+
+        assert_eq!(1_000, acc.pending(50, Number::ZERO));
+    }
+
+    #[test]
+    fn later_depositor_only_earns_later_rewards() {
+        let mut acc = RewardAccumulator::default();
+        acc.update_accumulator(1_000, 100).unwrap();
+
+        let late_debt = acc.debt(100);
+        acc.update_accumulator(1_000, 200).unwrap();
+
+        assert_eq!(1_500, acc.pending(100, Number::ZERO));
+        assert_eq!(500, acc.pending(100, late_debt));
+    }
+
+    #[test]
+    fn payouts_never_exceed_funding() {
+        let shares = [1u64, 7, 13, 333, 1_000_003];
+        let total_shares = shares.iter().sum();
+
+        let mut acc = RewardAccumulator::default();
+        let mut funded = 0;
+        for rewards in [1u64, 999_999, 7, 123_456_789] {
+            acc.update_accumulator(rewards, total_shares).unwrap();
+            funded += rewards;
+        }
+
+        let paid: u64 = shares.iter().map(|s| acc.pending(*s, Number::ZERO)).sum();
+        assert!(paid <= funded);
+        assert!(funded - paid < shares.len() as u64);
+    }
+}
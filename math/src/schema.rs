@@ -0,0 +1,281 @@
+//! JSON Schema and TypeScript definitions for the JSON forms of the number
+//! types, so that consumers of our APIs (e.g. the indexer's) can validate
+//! payloads.
+//!
+//! The wire format of `Number` and `Number128` is their decimal string:
+//! write values with `Display` (i.e. `to_string`) and read them with
+//! `FromStr` (i.e. `parse`), since their range exceeds what JSON numbers
+//! can hold exactly. The `pattern` in the schema matches every string that
+//! `Display` produces, and `FromStr` accepts every string it matches.
+//! `DecimalValue` is serialized as an object with its mantissa and exponent.
+//!
+//! `Number128Json` is the object written by `json::number128`,
+//! whose mantissa is a string so that JavaScript can hold it exactly.
+//!
+//! Regenerate the files in `schema/` with:
+//!
+//! `cargo run --example gen_schema --features schema -- schema`
+
+use crate::{Number, Number128};
+
+const NUMBER_PRECISION: usize = 15;
+const NUMBER128_PRECISION: usize = 10;
+
+/// A type's name, with its JSON Schema definition and TypeScript type
+struct Definition {
+    name: &'static str,
+    description: String,
+    schema: Vec<String>,
+    typescript: &'static str,
+}
+
+fn definitions() -> [Definition; 4] {
+    [
+        Definition {
+            name: "Number",
+            description: format!(
+                "A non-negative decimal string with up to {NUMBER_PRECISION} fractional digits, at most {}",
+                Number::MAX
+            ),
+            schema: decimal_schema(false, NUMBER_PRECISION),
+            typescript: "string",
+        },
+        Definition {
+            name: "Number128",
+            description: format!(
+                "A decimal string with up to {NUMBER128_PRECISION} fractional digits, from {} to {}",
+                Number128::MIN,
+                Number128::MAX
+            ),
+            schema: decimal_schema(true, NUMBER128_PRECISION),
+            typescript: "string",
+        },
+        Definition {
+            name: "DecimalValue",
+            description: "The value mantissa * 10^exponent".to_string(),
+            schema: vec![
+                "\"type\": \"object\"".to_string(),
+                format!(
+                    "\"properties\": {{ \"mantissa\": {}, \"exponent\": {} }}",
+                    integer_schema(i64::MIN as i128, i64::MAX as i128),
+                    integer_schema(i32::MIN as i128, i32::MAX as i128)
+                ),
+                "\"required\": [\"mantissa\", \"exponent\"]".to_string(),
+                "\"additionalProperties\": false".to_string(),
+            ],
+            typescript: "{ mantissa: number | bigint; exponent: number }",
+        },
+        Definition {
+            name: "Number128Json",
+            description: "A Number128 with the value mantissa * 10^exponent, as written by json::number128, which also reads a Number128 string".to_string(),
+            schema: vec![
+                "\"type\": \"object\"".to_string(),
+                format!(
+                    "\"properties\": {{ \"mantissa\": {}, \"exponent\": {} }}",
+                    "{ \"type\": \"string\", \"pattern\": \"^-?[0-9]+$\" }",
+                    integer_schema(i32::MIN as i128, i32::MAX as i128)
+                ),
+                "\"required\": [\"mantissa\", \"exponent\"]".to_string(),
+                "\"additionalProperties\": false".to_string(),
+            ],
+            typescript: "{ mantissa: string; exponent: number }",
+        },
+    ]
+}
+
+fn decimal_schema(signed: bool, fraction_digits: usize) -> Vec<String> {
+    let sign = if signed { "-?" } else { "" };
+
+    vec![
+        "\"type\": \"string\"".to_string(),
+        format!("\"pattern\": \"^{sign}(0|[1-9][0-9]*)(\\\\.[0-9]{{1,{fraction_digits}}})?$\""),
+    ]
+}
+
+fn integer_schema(minimum: i128, maximum: i128) -> String {
+    format!("{{ \"type\": \"integer\", \"minimum\": {minimum}, \"maximum\": {maximum} }}")
+}
+
+/// A JSON Schema document defining each type under `$defs`
+pub fn json_schema() -> String {
+    let definitions = definitions()
+        .iter()
+        .map(|d| {
+            let mut properties = vec![format!("\"description\": \"{}\"", d.description)];
+            properties.extend(d.schema.iter().cloned());
+
+            format!(
+                "    \"{}\": {{\n      {}\n    }}",
+                d.name,
+                properties.join(",\n      ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        "{{\n  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\",\n  \"$defs\": {{\n{}\n  }}\n}}\n",
+        definitions
+    )
+}
+
+/// TypeScript declarations of each type
+pub fn typescript() -> String {
+    definitions()
+        .iter()
+        .map(|d| {
+            format!(
+                "/** {} */\nexport type {} = {};\n",
+                d.description, d.name, d.typescript
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// A type's definition in the generated schema
+    fn definition(name: &str) -> Value {
+        let schema: Value = serde_json::from_str(&json_schema()).unwrap();
+
+        schema["$defs"][name].clone()
+    }
+
+    /// The `pattern` of a type's definition in the generated schema
+    fn pattern(name: &str) -> Regex {
+        Regex::new(definition(name)["pattern"].as_str().unwrap()).unwrap()
+    }
+
+    /// Whether a value is valid under a schema, supporting only the keywords
+    /// that the generated schema uses
+    fn is_valid(schema: &Value, value: &Value) -> bool {
+        let type_matches = match schema["type"].as_str() {
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("object") => value.is_object(),
+            _ => false,
+        };
+        let pattern_matches = match (schema["pattern"].as_str(), value.as_str()) {
+            (Some(pattern), Some(s)) => Regex::new(pattern).unwrap().is_match(s),
+            _ => true,
+        };
+        let minimum = schema["minimum"].as_i64().unwrap_or(i64::MIN);
+        let maximum = schema["maximum"].as_i64().unwrap_or(i64::MAX);
+        let in_range =
+            !value.is_number() || value.as_i64().is_some_and(|n| minimum <= n && n <= maximum);
+        let properties_match = match value.as_object() {
+            Some(object) => {
+                let properties = schema["properties"].as_object().unwrap();
+                let required = schema["required"].as_array().unwrap();
+
+                required
+                    .iter()
+                    .all(|key| object.contains_key(key.as_str().unwrap()))
+                    && object
+                        .iter()
+                        .all(|(key, value)| properties.get(key).is_some_and(|p| is_valid(p, value)))
+            }
+            None => true,
+        };
+
+        type_matches && pattern_matches && in_range && properties_match
+    }
+
+    #[test]
+    fn strings_match_schema() {
+        let number = pattern("Number");
+        let numbers = [
+            Number::ZERO,
+            Number::ONE,
+            Number::MAX,
+            Number::from_decimal(1, -15),
+        ];
+        for n in numbers {
+            let s = n.to_string();
+            assert!(number.is_match(&s), "{s}");
+            assert_eq!(Ok(n), s.parse(), "{s}");
+        }
+        for s in ["-1.0", "01.0", "1.", ".5", "1e3", "0.0000000000000001"] {
+            assert!(!number.is_match(s), "{s}");
+        }
+
+        let number128 = pattern("Number128");
+        let numbers = [
+            Number128::ZERO,
+            Number128::MIN,
+            Number128::MAX,
+            Number128::from_decimal(-5, -1),
+            Number128::from_i128(1),
+        ];
+        for n in numbers {
+            let s = n.to_string();
+            assert!(number128.is_match(&s), "{s}");
+            // see `Number128::to_exact_string`
+            #[cfg(not(feature = "binary-number128"))]
+            assert_eq!(Ok(n), s.parse(), "{s}");
+        }
+        for s in ["01.0", "--1", "+1", "1.", "0.00000000001"] {
+            assert!(!number128.is_match(s), "{s}");
+        }
+    }
+
+    #[test]
+    fn parses_strings_matching_schema() {
+        for s in ["0", "1.5", "10.000000000000001", "0.000000000000001"] {
+            assert!(pattern("Number").is_match(s), "{s}");
+            assert!(s.parse::<Number>().is_ok(), "{s}");
+        }
+        for s in ["0", "-1.5", "-0.0000000001", "1234567890.0123456789"] {
+            assert!(pattern("Number128").is_match(s), "{s}");
+            assert!(s.parse::<Number128>().is_ok(), "{s}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_matches_schema() {
+        let schema = definition("Number128Json");
+        let to_json = |n: Number128| {
+            crate::json::number128::serialize(&n, serde_json::value::Serializer).unwrap()
+        };
+
+        let numbers = [
+            Number128::ZERO,
+            Number128::MIN,
+            Number128::MAX,
+            Number128::from_decimal(-5, -1),
+            Number128::from_decimal(-25_000, 0),
+            Number128::from_i128(1),
+        ];
+        for n in numbers {
+            let json = to_json(n);
+            assert!(is_valid(&schema, &json), "{json}");
+        }
+
+        let invalid = [
+            serde_json::json!({ "mantissa": 1, "exponent": 0 }),
+            serde_json::json!({ "mantissa": "1.5", "exponent": 0 }),
+            serde_json::json!({ "mantissa": "1" }),
+            serde_json::json!({ "mantissa": "1", "exponent": 0, "scale": 2 }),
+            serde_json::json!({ "mantissa": "1", "exponent": 2_147_483_648i64 }),
+            serde_json::json!("1.5"),
+        ];
+        for json in invalid {
+            assert!(!is_valid(&schema, &json), "{json}");
+        }
+    }
+
+    // the range of `Number128` in the descriptions depends on its scale
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn schema_is_stable() {
+        assert_eq!(include_str!("../schema/numbers.schema.json"), json_schema());
+        assert_eq!(include_str!("../schema/numbers.d.ts"), typescript());
+    }
+}
@@ -0,0 +1,75 @@
+//! Slippage bounds on token amounts.
+//!
+//! A slippage bound must always round against the user, so the rounding
+//! direction is chosen by the caller through `Direction` rather than
+//! implied by the operation.
+
+use crate::{Error, Number128};
+
+/// Which way to adjust an amount by the slippage tolerance
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// Reduce the amount, rounding down, e.g. for a minimum amount out
+    Down,
+    /// Increase the amount, rounding up, e.g. for a maximum amount in
+    Up,
+}
+
+/// Adjust an amount by some basis points in the given direction
+pub fn apply_bps(amount: u64, bps: u16, direction: Direction) -> Result<u64, Error> {
+    let amount = Number128::from_decimal(amount, 0);
+    let bps = Number128::from_bps(bps);
+
+    match direction {
+        Direction::Down => {
+            if bps > Number128::ONE {
+                return Err(Error::InvalidInput("slippage exceeds 10000 bps"));
+            }
+
+            Ok(amount.mul_floor(Number128::ONE - bps).as_u64(0))
+        }
+        Direction::Up => {
+            let adjusted = amount.mul_ceil(Number128::ONE + bps).to_i128() as u128;
+            let one = Number128::ONE.to_i128() as u128;
+
+            u64::try_from(adjusted.div_ceil(one)).map_err(|_| Error::OutOfRange)
+        }
+    }
+}
+
+/// The smallest acceptable output amount for a trade quoted at `amount_out`
+pub fn min_out(amount_out: u64, max_slippage_bps: u16) -> Result<u64, Error> {
+    apply_bps(amount_out, max_slippage_bps, Direction::Down)
+}
+
+/// The largest acceptable input amount for a trade quoted at `amount_in`
+pub fn max_in(amount_in: u64, max_slippage_bps: u16) -> Result<u64, Error> {
+    apply_bps(amount_in, max_slippage_bps, Direction::Up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_against_the_user() {
+        assert_eq!(Ok(9_949), min_out(10_000, 51));
+        assert_eq!(Ok(98), min_out(99, 50));
+        assert_eq!(Ok(100), max_in(99, 50));
+        assert_eq!(Ok(10_051), max_in(10_000, 51));
+    }
+
+    #[test]
+    fn exact_amounts_are_not_rounded() {
+        assert_eq!(Ok(9_950), min_out(10_000, 50));
+        assert_eq!(Ok(10_050), max_in(10_000, 50));
+        assert_eq!(Ok(0), min_out(10_000, 10_000));
+        assert_eq!(Ok(u64::MAX), max_in(u64::MAX, 0));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert!(min_out(10_000, 10_001).is_err());
+        assert_eq!(Err(Error::OutOfRange), max_in(u64::MAX, 1));
+    }
+}
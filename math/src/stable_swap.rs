@@ -0,0 +1,92 @@
+//! Math for stable-swap pools.
+//!
+//! The amplification coefficient of a stable-swap pool is changed
+//! gradually, by ramping it linearly between two timestamps like Curve
+//! does, so that a change can't be used to move the pool's prices at once.
+
+use crate::{Error, Number};
+
+/// The amplification coefficient at `time`, during a ramp from `amp_start`
+/// at `ramp_start` to `amp_end` at `ramp_end`
+///
+/// The coefficient is `amp_start` before the ramp, and `amp_end` from the
+/// end of the ramp.
+/// During the ramp, the change is rounded towards `amp_start`.
+pub fn amp_at(
+    time: i64,
+    ramp_start: i64,
+    ramp_end: i64,
+    amp_start: Number,
+    amp_end: Number,
+) -> Result<Number, Error> {
+    if ramp_end < ramp_start {
+        return Err(Error::InvalidInput("ramp ends before it starts"));
+    }
+    if time >= ramp_end {
+        return Ok(amp_end);
+    }
+    if time <= ramp_start {
+        return Ok(amp_start);
+    }
+
+    // the timestamps are ordered, so the differences fit in a u64
+    let elapsed = Number::from(time.abs_diff(ramp_start));
+    let duration = Number::from(ramp_end.abs_diff(ramp_start));
+
+    let change = |from: Number, to: Number| {
+        (to - from)
+            .checked_mul_div(&elapsed, &duration)
+            .ok_or(Error::OutOfRange)
+    };
+
+    match amp_end >= amp_start {
+        true => Ok(amp_start + change(amp_start, amp_end)?),
+        false => Ok(amp_start - change(amp_end, amp_start)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramps_linearly() {
+        let (low, high) = (Number::from(100u64), Number::from(200u64));
+
+        assert_eq!(Ok(low), amp_at(0, 1_000, 2_000, low, high));
+        assert_eq!(Ok(low), amp_at(1_000, 1_000, 2_000, low, high));
+        assert_eq!(
+            Ok(Number::from(125u64)),
+            amp_at(1_250, 1_000, 2_000, low, high)
+        );
+        assert_eq!(Ok(high), amp_at(2_000, 1_000, 2_000, low, high));
+        assert_eq!(Ok(high), amp_at(i64::MAX, 1_000, 2_000, low, high));
+
+        assert_eq!(
+            Ok(Number::from(175u64)),
+            amp_at(1_250, 1_000, 2_000, high, low)
+        );
+        assert_eq!(Ok(low), amp_at(3_000, 1_000, 2_000, high, low));
+    }
+
+    #[test]
+    fn rounds_towards_the_start() {
+        let (low, high) = (Number::from(1u64), Number::from(2u64));
+        let third = Number::from_decimal(333_333_333_333_333u64, -15);
+
+        assert_eq!(Ok(low + third), amp_at(1, 0, 3, low, high));
+        assert_eq!(Ok(high - third), amp_at(1, 0, 3, high, low));
+    }
+
+    #[test]
+    fn extreme_timestamps() {
+        let (low, high) = (Number::from(1u64), Number::from(3u64));
+
+        assert_eq!(
+            Ok(Number::from(2u64)),
+            amp_at(0, i64::MIN, i64::MAX, low, high)
+        );
+        assert_eq!(Ok(high), amp_at(5, 5, 5, low, high));
+        assert!(amp_at(0, 10, 5, low, high).is_err());
+    }
+}
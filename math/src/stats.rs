@@ -0,0 +1,270 @@
+//! Summary statistics over slices of `Number128`, computed with widened
+//! intermediates so that results are exact (up to the final truncation)
+//! and deterministic.
+
+use crate::bigint::{I192, U256};
+use crate::{lerp, Error, Number128};
+
+/// The median of the values, averaging the two middle values when there
+/// is an even number of them. The slice is sorted in place.
+pub fn median(values: &mut [Number128]) -> Result<Number128, Error> {
+    if values.is_empty() {
+        return Err(Error::InvalidInput("no values"));
+    }
+
+    values.sort_unstable();
+    let middle = values.len() / 2;
+
+    if values.len() % 2 == 1 {
+        return Ok(values[middle]);
+    }
+
+    let sum = I192::from(values[middle - 1].to_i128()) + I192::from(values[middle].to_i128());
+    to_number(sum / I192::from(2i64))
+}
+
+/// The `p`th percentile of the values, where `p` is between 0 and 1,
+/// linearly interpolating between the closest ranks. The slice is sorted
+/// in place.
+pub fn percentile(values: &mut [Number128], p: Number128) -> Result<Number128, Error> {
+    if values.is_empty() {
+        return Err(Error::InvalidInput("no values"));
+    }
+    if p < Number128::ZERO || p > Number128::ONE {
+        return Err(Error::InvalidInput("percentile must be between 0 and 1"));
+    }
+
+    values.sort_unstable();
+
+    let rank = p * (values.len() as i128 - 1);
+    let index = rank.as_u64(0) as usize;
+    let fraction = rank - Number128::from_decimal(index as i128, 0);
+
+    match values.get(index + 1) {
+        Some(next) => Ok(lerp(values[index], *next, fraction)),
+        None => Ok(values[index]),
+    }
+}
+
+/// The arithmetic mean of the values
+pub fn mean(values: &[Number128]) -> Result<Number128, Error> {
+    if values.is_empty() {
+        return Err(Error::InvalidInput("no values"));
+    }
+
+    to_number(sum(values)? / I192::from(values.len() as u64))
+}
+
+/// The population variance of the values
+pub fn variance(values: &[Number128]) -> Result<Number128, Error> {
+    let raw = sum_of_squared_deviations(values)? / U256::from(values.len());
+    let scaled = raw / U256::from(Number128::ONE.to_i128() as u128);
+
+    match scaled > U256::from(i128::MAX as u128) {
+        true => Err(Error::OutOfRange),
+        false => Ok(Number128::from_i128(scaled.as_u128() as i128)),
+    }
+}
+
+/// The population standard deviation of the values
+pub fn stddev(values: &[Number128]) -> Result<Number128, Error> {
+    let raw = sum_of_squared_deviations(values)? / U256::from(values.len());
+
+    // sqrt of the squared raw values is already in the raw scale, and
+    // cannot exceed the range of the inputs
+    Ok(Number128::from_i128(raw.integer_sqrt().as_u128() as i128))
+}
+
+/// The change from `old` to `new` as a fraction of the magnitude of `old`,
+/// e.g. 0.1 for a 10% gain, rounded toward zero
+///
+/// A change towards positive infinity is positive even when `old` is
+/// negative, so a PnL going from -100 to -50 is a change of 0.5.
+pub fn pct_change(old: Number128, new: Number128) -> Result<Number128, Error> {
+    if old == Number128::ZERO {
+        return Err(Error::DivideByZero);
+    }
+
+    let change = new.checked_sub(&old).ok_or(Error::OutOfRange)?;
+    let base = match old < Number128::ZERO {
+        true => old.checked_neg().ok_or(Error::OutOfRange)?,
+        false => old,
+    };
+
+    change.checked_div(&base).ok_or(Error::OutOfRange)
+}
+
+/// The natural log of the ratio of `new` to `old`, which can be summed over
+/// consecutive periods, unlike `pct_change`
+///
+/// Both values must be positive. The result is accurate to about 1e-9.
+pub fn log_return(old: Number128, new: Number128) -> Result<Number128, Error> {
+    if old == Number128::ZERO {
+        return Err(Error::DivideByZero);
+    }
+    if old < Number128::ZERO || new <= Number128::ZERO {
+        return Err(Error::InvalidInput("log return of a non-positive value"));
+    }
+
+    match new.checked_div(&old) {
+        Some(ratio) if ratio > Number128::ZERO => Ok(crate::functions::ln(ratio)),
+        _ => Err(Error::OutOfRange),
+    }
+}
+
+fn sum(values: &[Number128]) -> Result<I192, Error> {
+    values.iter().try_fold(I192::ZERO, |acc, n| {
+        acc.checked_add(I192::from(n.to_i128()))
+            .ok_or(Error::OutOfRange)
+    })
+}
+
+fn sum_of_squared_deviations(values: &[Number128]) -> Result<U256, Error> {
+    let mean = I192::from(mean(values)?.to_i128());
+
+    values.iter().try_fold(U256::zero(), |acc, n| {
+        let deviation = U256::from((I192::from(n.to_i128()) - mean).unsigned_abs());
+        acc.checked_add(deviation * deviation)
+            .ok_or(Error::OutOfRange)
+    })
+}
+
+fn to_number(value: I192) -> Result<Number128, Error> {
+    i128::try_from(value)
+        .map(Number128::from_i128)
+        .map_err(|_| Error::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(values: &[i64]) -> Vec<Number128> {
+        values
+            .iter()
+            .map(|v| Number128::from_decimal(*v, 0))
+            .collect()
+    }
+
+    #[test]
+    fn median_odd_and_even() {
+        assert_eq!(
+            Ok(Number128::from_decimal(3, 0)),
+            median(&mut numbers(&[5, 1, 3]))
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(25, -1)),
+            median(&mut numbers(&[4, 1, 3, 2]))
+        );
+        assert!(median(&mut []).is_err());
+    }
+
+    #[test]
+    fn median_does_not_overflow() {
+        let mut values = [Number128::MAX, Number128::MAX];
+        assert_eq!(Ok(Number128::MAX), median(&mut values));
+    }
+
+    #[test]
+    fn percentiles() {
+        let mut values = numbers(&[40, 10, 30, 20, 50]);
+
+        assert_eq!(
+            Ok(Number128::from_decimal(10, 0)),
+            percentile(&mut values, Number128::ZERO)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(50, 0)),
+            percentile(&mut values, Number128::ONE)
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(30, 0)),
+            percentile(&mut values, Number128::from_bps(5000))
+        );
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(
+            Ok(Number128::from_decimal(46, 0)),
+            percentile(&mut values, Number128::from_bps(9000))
+        );
+        assert!(percentile(&mut values, Number128::from_bps(10001)).is_err());
+    }
+
+    #[test]
+    fn mean_variance_stddev() {
+        let values = numbers(&[2, 4, 4, 4, 5, 5, 7, 9]);
+
+        assert_eq!(Ok(Number128::from_decimal(5, 0)), mean(&values));
+        assert_eq!(Ok(Number128::from_decimal(4, 0)), variance(&values));
+        assert_eq!(Ok(Number128::from_decimal(2, 0)), stddev(&values));
+    }
+
+    #[test]
+    fn mean_of_extremes() {
+        assert_eq!(
+            Ok(Number128::MAX),
+            mean(&[Number128::MAX, Number128::MAX, Number128::MAX])
+        );
+        assert_eq!(Ok(Number128::ZERO), mean(&[Number128::MIN, Number128::MAX]));
+    }
+
+    #[test]
+    fn percentage_changes() {
+        let n = |v: i64| Number128::from_decimal(v, 0);
+
+        assert_eq!(
+            Ok(Number128::from_decimal(1, -1)),
+            pct_change(n(100), n(110))
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(-1, -1)),
+            pct_change(n(100), n(90))
+        );
+        assert_eq!(Ok(Number128::ZERO), pct_change(n(-7), n(-7)));
+
+        // the sign follows the direction of the change, not the values
+        assert_eq!(
+            Ok(Number128::from_decimal(5, -1)),
+            pct_change(n(-100), n(-50))
+        );
+        assert_eq!(
+            Ok(Number128::from_decimal(-5, -1)),
+            pct_change(n(-100), n(-150))
+        );
+        assert_eq!(Ok(n(2)), pct_change(n(-100), n(100)));
+        assert_eq!(Ok(n(-2)), pct_change(n(100), n(-100)));
+        assert_eq!(Ok(n(-1)), pct_change(n(100), Number128::ZERO));
+
+        assert_eq!(Err(Error::DivideByZero), pct_change(Number128::ZERO, n(1)));
+        assert_eq!(
+            Err(Error::OutOfRange),
+            pct_change(Number128::MIN, Number128::MAX)
+        );
+    }
+
+    #[test]
+    fn log_returns() {
+        let n = |v: i64| Number128::from_decimal(v, 0);
+        let ln_1_1 = Number128::from_decimal(953_101_798i64, -10);
+        let close = |a: Number128, b: Number128| {
+            assert!((a - b).to_i128().abs() <= 10, "{a} != {b}");
+        };
+
+        close(ln_1_1, log_return(n(100), n(110)).unwrap());
+        close(-ln_1_1, log_return(n(110), n(100)).unwrap());
+        assert_eq!(Ok(Number128::ZERO), log_return(n(3), n(3)));
+
+        // log returns over consecutive periods add up
+        let total = log_return(n(100), n(250)).unwrap();
+        let steps = log_return(n(100), n(125)).unwrap() + log_return(n(125), n(250)).unwrap();
+        close(total, steps);
+
+        assert_eq!(Err(Error::DivideByZero), log_return(Number128::ZERO, n(1)));
+        assert!(log_return(n(-1), n(1)).is_err());
+        assert!(log_return(n(1), n(-1)).is_err());
+        assert!(log_return(n(1), Number128::ZERO).is_err());
+        assert_eq!(
+            Err(Error::OutOfRange),
+            log_return(Number128::MAX, Number128::from_i128(1))
+        );
+    }
+}
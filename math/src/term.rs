@@ -0,0 +1,146 @@
+//! Conversions between the price and the yield of fixed-term loans.
+//!
+//! A price is the fraction of the face value paid at the start of the
+//! term, e.g. a price of 0.95 repays 1.0 at maturity. Yields are simple
+//! annualized rates, using the chosen day count convention to convert the
+//! tenor into years.
+
+use crate::{Error, Number128};
+
+/// The convention used to count the length of a year
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DayCount {
+    /// Actual days over a 365 day year
+    Act365,
+    /// Actual days over a 360 day year
+    Act360,
+}
+
+impl DayCount {
+    /// The number of seconds in a year under this convention
+    pub fn seconds_per_year(&self) -> u64 {
+        match self {
+            DayCount::Act365 => 365 * 24 * 60 * 60,
+            DayCount::Act360 => 360 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// Convert a price into the annualized yield it implies over the tenor
+pub fn price_to_yield(
+    price: Number128,
+    tenor_seconds: u64,
+    day_count: DayCount,
+) -> Result<Number128, Error> {
+    if price <= Number128::ZERO {
+        return Err(Error::InvalidInput("price is not positive"));
+    }
+    if tenor_seconds == 0 {
+        return Err(Error::DivideByZero);
+    }
+
+    let interest = Number128::ONE / price - Number128::ONE;
+
+    Ok(interest * day_count.seconds_per_year() / tenor_seconds)
+}
+
+/// Convert an annualized yield into the price it implies over the tenor,
+/// returning an error if the growth over the tenor overflows
+pub fn yield_to_price(
+    rate: Number128,
+    tenor_seconds: u64,
+    day_count: DayCount,
+) -> Result<Number128, Error> {
+    let tenor = Number128::from_i128(tenor_seconds as i128);
+    let year = Number128::from_i128(day_count.seconds_per_year() as i128);
+
+    let growth = rate
+        .checked_mul_div(&tenor, &year)
+        .and_then(|interest| Number128::ONE.checked_add(&interest))
+        .ok_or(Error::OutOfRange)?;
+
+    if growth == Number128::ZERO {
+        return Err(Error::DivideByZero);
+    }
+    if growth < Number128::ZERO {
+        return Err(Error::InvalidInput("yield implies a negative price"));
+    }
+
+    Ok(Number128::ONE / growth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn one_year_yield() {
+        let rate = price_to_yield(Number128::from_bps(9500), 365 * DAY, DayCount::Act365);
+        assert_eq!(Ok(Number128::from_decimal(526315789, -10)), rate);
+
+        let price = yield_to_price(Number128::from_bps(500), 365 * DAY, DayCount::Act365);
+        assert_eq!(Ok(Number128::from_decimal(9523809523i64, -10)), price);
+    }
+
+    #[test]
+    fn day_count_conventions() {
+        let price = Number128::from_bps(9900);
+        let act365 = price_to_yield(price, 90 * DAY, DayCount::Act365).unwrap();
+        let act360 = price_to_yield(price, 90 * DAY, DayCount::Act360).unwrap();
+
+        assert!(act365 > act360);
+        assert!((act365 * 360u64 / 365u64 - act360).as_f64().abs() < 1e-9);
+    }
+
+    #[test]
+    fn round_trip() {
+        let rate = Number128::from_bps(735);
+        let price = yield_to_price(rate, 30 * DAY, DayCount::Act360).unwrap();
+        let round_tripped = price_to_yield(price, 30 * DAY, DayCount::Act360).unwrap();
+
+        assert!((round_tripped - rate).as_f64().abs() < 1e-7);
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        assert_eq!(
+            Err(Error::InvalidInput("price is not positive")),
+            price_to_yield(Number128::ZERO, DAY, DayCount::Act365)
+        );
+        assert_eq!(
+            Err(Error::InvalidInput("price is not positive")),
+            price_to_yield(-Number128::from_bps(9500), DAY, DayCount::Act365)
+        );
+        assert_eq!(
+            Err(Error::DivideByZero),
+            price_to_yield(Number128::ONE, 0, DayCount::Act365)
+        );
+        assert_eq!(
+            Err(Error::DivideByZero),
+            yield_to_price(-Number128::ONE, 365 * DAY, DayCount::Act365)
+        );
+        assert_eq!(
+            Err(Error::InvalidInput("yield implies a negative price")),
+            yield_to_price(-Number128::from_decimal(2, 0), 365 * DAY, DayCount::Act365)
+        );
+    }
+
+    #[test]
+    fn overflowing_yields() {
+        assert_eq!(
+            Err(Error::OutOfRange),
+            yield_to_price(Number128::MAX, 365 * DAY, DayCount::Act365)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            yield_to_price(Number128::MAX, 730 * DAY, DayCount::Act365)
+        );
+        assert_eq!(
+            Err(Error::OutOfRange),
+            yield_to_price(Number128::MIN, 730 * DAY, DayCount::Act365)
+        );
+    }
+}
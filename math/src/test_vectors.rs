@@ -0,0 +1,254 @@
+//! Deterministic test vectors for checking other implementations of the
+//! number types (e.g. the typescript client) against this one bit for bit.
+//!
+//! Values are generated by a seeded PRNG, so the same seed always produces
+//! the same vectors. All inputs and outputs are written as the raw scaled
+//! integers in decimal, and operations that fail (overflow or division by
+//! zero) have a `null` result.
+
+use crate::bigint::U192;
+use crate::{Number, Number128};
+
+/// A small, fast, deterministic PRNG (splitmix64)
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u128(&mut self) -> u128 {
+        (self.next_u64() as u128) << 64 | self.next_u64() as u128
+    }
+
+    /// A random `Number`, with the bit length chosen uniformly so that
+    /// small and large magnitudes are equally likely
+    pub fn number(&mut self) -> Number {
+        let raw = U192([self.next_u64(), self.next_u64(), self.next_u64()]);
+        let shift = self.next_u64() % Number::BITS as u64;
+
+        Number::from_u192(raw >> shift as usize)
+    }
+
+    /// A random `Number128`, with the bit length chosen uniformly so that
+    /// small and large magnitudes are equally likely
+    pub fn number_128(&mut self) -> Number128 {
+        let shift = self.next_u64() % Number128::BITS as u64;
+        let raw = (self.next_u128() >> shift) as i128;
+
+        match self.next_u64() % 2 {
+            0 => Number128::from_i128(raw),
+            _ => Number128::from_i128(raw.wrapping_neg()),
+        }
+    }
+}
+
+type BinaryOp<T> = fn(&T, &T) -> Option<T>;
+
+/// A single operation with its inputs and expected output
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestVector {
+    pub op: &'static str,
+    pub inputs: Vec<String>,
+    pub output: Option<String>,
+}
+
+impl TestVector {
+    pub fn to_json(&self) -> String {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|i| format!("\"{}\"", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = match &self.output {
+            Some(o) => format!("\"{}\"", o),
+            None => "null".to_string(),
+        };
+
+        format!(
+            "{{\"op\":\"{}\",\"inputs\":[{}],\"output\":{}}}",
+            self.op, inputs, output
+        )
+    }
+}
+
+/// Render a list of vectors as a JSON array, one vector per line
+pub fn to_json(vectors: &[TestVector]) -> String {
+    let lines = vectors
+        .iter()
+        .map(|v| format!("  {}", v.to_json()))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!("[\n{}\n]\n", lines)
+}
+
+/// Generate `count` vectors for each binary operation on `Number`
+pub fn number_vectors(seed: u64, count: usize) -> Vec<TestVector> {
+    let mut rng = Rng::new(seed);
+    let ops: [(&str, BinaryOp<Number>); 4] = [
+        ("add", Number::checked_add),
+        ("sub", Number::checked_sub),
+        ("mul", Number::checked_mul),
+        ("div", Number::checked_div),
+    ];
+
+    let mut vectors = vec![];
+    for (op, f) in ops {
+        for _ in 0..count {
+            let (a, b) = (rng.number(), rng.number());
+            vectors.push(TestVector {
+                op,
+                inputs: vec![a.to_u192().to_string(), b.to_u192().to_string()],
+                output: f(&a, &b).map(|n| n.to_u192().to_string()),
+            });
+        }
+    }
+
+    vectors
+}
+
+/// Generate `count` vectors for each binary operation on `Number128`
+pub fn number_128_vectors(seed: u64, count: usize) -> Vec<TestVector> {
+    let mut rng = Rng::new(seed);
+    let ops: [(&str, BinaryOp<Number128>); 4] = [
+        ("add", Number128::checked_add),
+        ("sub", Number128::checked_sub),
+        ("mul", Number128::checked_mul),
+        ("div", Number128::checked_div),
+    ];
+
+    let mut vectors = vec![];
+    for (op, f) in ops {
+        for _ in 0..count {
+            let (a, b) = (rng.number_128(), rng.number_128());
+            vectors.push(TestVector {
+                op,
+                inputs: vec![a.to_i128().to_string(), b.to_i128().to_string()],
+                output: f(&a, &b).map(|n| n.to_i128().to_string()),
+            });
+        }
+    }
+
+    vectors
+}
+
+/// Generate `count` vectors of the underlying byte representation of each
+/// number type, as returned by `into_bits`, in hex
+///
+/// The representation is native endian, so these vectors are for little
+/// endian targets, like the Solana runtime.
+pub fn bits_vectors(seed: u64, count: usize) -> Vec<TestVector> {
+    let mut rng = Rng::new(seed);
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut vectors = vec![];
+    for _ in 0..count {
+        let n = rng.number();
+        vectors.push(TestVector {
+            op: "number_bits",
+            inputs: vec![n.to_u192().to_string()],
+            output: Some(hex(&n.into_bits())),
+        });
+    }
+    for _ in 0..count {
+        let n = rng.number_128();
+        vectors.push(TestVector {
+            op: "number_128_bits",
+            inputs: vec![n.to_i128().to_string()],
+            output: Some(hex(&n.into_bits())),
+        });
+    }
+
+    vectors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(number_vectors(7, 10), number_vectors(7, 10));
+        assert_eq!(number_128_vectors(7, 10), number_128_vectors(7, 10));
+        assert_ne!(number_128_vectors(7, 10), number_128_vectors(8, 10));
+    }
+
+    #[test]
+    fn json_format() {
+        let vector = TestVector {
+            op: "div",
+            inputs: vec!["1".to_string(), "0".to_string()],
+            output: None,
+        };
+        assert_eq!(
+            r#"{"op":"div","inputs":["1","0"],"output":null}"#,
+            vector.to_json()
+        );
+        assert_eq!(
+            "[\n  {\"op\":\"div\",\"inputs\":[\"1\",\"0\"],\"output\":null}\n]\n",
+            to_json(&[vector])
+        );
+    }
+
+    #[test]
+    fn outputs_match_operators() {
+        for vector in number_128_vectors(1, 100) {
+            let a = Number128::from_i128(vector.inputs[0].parse().unwrap());
+            let b = Number128::from_i128(vector.inputs[1].parse().unwrap());
+
+            if let (Some(output), "add") = (&vector.output, vector.op) {
+                assert_eq!(output, &(a + b).to_i128().to_string());
+            }
+        }
+    }
+}
+
+/// Checks the vectors against golden copies generated by a previous version
+/// of the crate, so that any change to the representation or the results of
+/// the arithmetic is caught. Regenerate the golden files with:
+///
+/// `cargo run --example gen_vectors --features test-vectors -- test-vectors 0 32`
+#[cfg(all(test, target_endian = "little"))]
+mod golden_tests {
+    use super::*;
+
+    const SEED: u64 = 0;
+    const COUNT: usize = 32;
+
+    #[test]
+    fn number() {
+        assert_eq!(
+            include_str!("../test-vectors/number.json"),
+            to_json(&number_vectors(SEED, COUNT))
+        );
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn number_128() {
+        assert_eq!(
+            include_str!("../test-vectors/number_128.json"),
+            to_json(&number_128_vectors(SEED, COUNT))
+        );
+    }
+
+    #[cfg(not(feature = "binary-number128"))]
+    #[test]
+    fn bits() {
+        assert_eq!(
+            include_str!("../test-vectors/bits.json"),
+            to_json(&bits_vectors(SEED, COUNT))
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Conversions between `Number` and SPL token amounts, using the decimals
+//! of the token's mint.
+
+use anchor_spl::token::Mint;
+
+use crate::{Error, Number, Rounding};
+
+impl Number {
+    /// Convert a raw amount of the mint's token into a `Number` of whole tokens
+    pub fn from_mint_amount(amount: u64, mint: &Mint) -> Self {
+        Self::from_token_amount(amount, mint.decimals)
+    }
+
+    /// Convert a `Number` of whole tokens into a raw amount of the mint's token
+    pub fn to_mint_amount(&self, mint: &Mint, rounding: Rounding) -> Result<u64, Error> {
+        self.to_token_amount(mint.decimals, rounding)
+    }
+}
@@ -1,7 +1,10 @@
+use std::ops::{Add, Div, Mul, Sub};
+
 use anchor_lang::{error, error_code, prelude::Result};
 use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
 
-use crate::{Number, U192};
+use crate::bps::bps_mul;
+use crate::{Number, Number128, Rounding, U192};
 
 #[error_code]
 pub enum NumericalError {
@@ -100,3 +103,162 @@ impl<T: CheckedAdd> SafeAdd for T {}
 impl<T: CheckedDiv> SafeDiv for T {}
 impl<T: CheckedMul> SafeMul for T {}
 impl<T: CheckedSub> SafeSub for T {}
+
+/// Math on raw token amounts, widening to `u128` so that intermediate
+/// products can't overflow. Results are rounded down.
+pub trait SafeTokenMath: Sized {
+    /// Multiply by some basis points
+    fn safe_mul_bps(&self, bps: u16) -> Result<Self>;
+
+    /// Compute `self * num / denom`
+    fn safe_mul_div(&self, num: Self, denom: Self) -> Result<Self>;
+}
+
+impl SafeTokenMath for u64 {
+    fn safe_mul_bps(&self, bps: u16) -> Result<Self> {
+        bps_mul(*self, bps, Rounding::Down)
+            .ok_or_else(|| error!(NumericalError::MultiplicationOverflow))
+    }
+
+    fn safe_mul_div(&self, num: Self, denom: Self) -> Result<Self> {
+        if denom == 0 {
+            return Err(error!(NumericalError::ZeroDivision));
+        }
+
+        u64::try_from(*self as u128 * num as u128 / denom as u128)
+            .map_err(|_| error!(NumericalError::MultiplicationOverflow))
+    }
+}
+
+/// The part of `total` that `share` is entitled to out of `whole`, e.g. the
+/// tokens owed for some of a pool's shares, rounded down
+pub fn proportion_of(total: u64, share: u64, whole: u64) -> Result<u64> {
+    total.safe_mul_div(share, whole)
+}
+
+/// Operations shared by the fixed-point number types, so that generic code
+/// can be written once for whichever width a program uses.
+pub trait FixedPointOps:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn from_decimal(value: u64, exponent: i32) -> Self;
+
+    fn as_u64(&self, exponent: i32) -> u64;
+
+    /// Compute `self * mul / div` without losing precision in the
+    /// intermediate product
+    fn checked_mul_div(&self, mul: &Self, div: &Self) -> Option<Self>;
+
+    fn mul_div(&self, mul: &Self, div: &Self) -> Result<Self> {
+        self.checked_mul_div(mul, div)
+            .ok_or_else(|| error!(NumericalError::MultiplicationOverflow))
+    }
+}
+
+impl FixedPointOps for Number {
+    fn zero() -> Self {
+        Number::ZERO
+    }
+
+    fn one() -> Self {
+        Number::ONE
+    }
+
+    fn from_decimal(value: u64, exponent: i32) -> Self {
+        Number::from_decimal(value, exponent)
+    }
+
+    fn as_u64(&self, exponent: i32) -> u64 {
+        Number::as_u64(self, exponent)
+    }
+
+    fn checked_mul_div(&self, mul: &Self, div: &Self) -> Option<Self> {
+        Number::checked_mul_div(self, mul, div)
+    }
+}
+
+impl FixedPointOps for Number128 {
+    fn zero() -> Self {
+        Number128::ZERO
+    }
+
+    fn one() -> Self {
+        Number128::ONE
+    }
+
+    fn from_decimal(value: u64, exponent: i32) -> Self {
+        Number128::from_decimal(value, exponent)
+    }
+
+    fn as_u64(&self, exponent: i32) -> u64 {
+        Number128::as_u64(self, exponent)
+    }
+
+    fn checked_mul_div(&self, mul: &Self, div: &Self) -> Option<Self> {
+        Number128::checked_mul_div(self, mul, div)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accrue<T: FixedPointOps>(principal: u64, rate_bps: u64, elapsed: u64, year: u64) -> u64 {
+        let principal = T::from_decimal(principal, 0);
+        let rate = T::from_decimal(rate_bps, -4);
+        let interest = (principal * rate)
+            .mul_div(&T::from_decimal(elapsed, 0), &T::from_decimal(year, 0))
+            .unwrap();
+
+        (principal + interest).as_u64(0)
+    }
+
+    #[test]
+    fn generic_over_width() {
+        assert_eq!(1_050, accrue::<Number>(1_000, 500, 365, 365));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(1_050, accrue::<Number128>(1_000, 500, 365, 365));
+        #[cfg(not(feature = "binary-number128"))]
+        assert_eq!(1_025, accrue::<Number128>(1_000, 500, 1, 2));
+    }
+
+    #[test]
+    fn mul_div_errors_on_zero_divisor() {
+        assert!(Number::ONE.mul_div(&Number::ONE, &Number::ZERO).is_err());
+        assert!(Number128::ONE
+            .mul_div(&Number128::ONE, &Number128::ZERO)
+            .is_err());
+    }
+
+    #[test]
+    fn token_math() {
+        assert_eq!(30, 10_000u64.safe_mul_bps(30).unwrap());
+        assert_eq!(0, 333u64.safe_mul_bps(30).unwrap());
+        assert_eq!(u64::MAX, u64::MAX.safe_mul_bps(10_000).unwrap());
+        assert!(u64::MAX.safe_mul_bps(10_001).is_err());
+
+        assert_eq!(
+            u64::MAX - 1,
+            u64::MAX.safe_mul_div(u64::MAX - 1, u64::MAX).unwrap()
+        );
+        assert_eq!(1, 3u64.safe_mul_div(1, 2).unwrap());
+        assert!(2u64.safe_mul_div(u64::MAX, 1).is_err());
+        assert!(1u64.safe_mul_div(1, 0).is_err());
+
+        assert_eq!(333, proportion_of(1_000, 1, 3).unwrap());
+        assert_eq!(u64::MAX, proportion_of(u64::MAX, 7, 7).unwrap());
+    }
+}
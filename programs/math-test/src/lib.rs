@@ -0,0 +1,111 @@
+//! A small program that exercises the math types on-chain, so that
+//! regressions in account layout, error mapping and compute usage show up
+//! in the integration tests.
+
+use anchor_lang::prelude::*;
+use jet_proto_math::{
+    interest::RatePeriod,
+    traits::{NumericalError, SafeAdd},
+    Number, Number128,
+};
+
+declare_id!("JPMTest111111111111111111111111111111111111");
+
+#[program]
+pub mod jet_proto_math_test {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let mut state = ctx.accounts.state.load_init()?;
+        state.number = Number::ZERO;
+        state.number_128 = Number128::ZERO;
+
+        Ok(())
+    }
+
+    /// Store a decimal value in both representations
+    pub fn store(ctx: Context<Update>, value: u128, exponent: i32) -> Result<()> {
+        let value_128 = i128::try_from(value).map_err(|_| error!(TestError::ValueOutOfRange))?;
+
+        let mut state = ctx.accounts.state.load_mut()?;
+        state.number = Number::from_u128(value, exponent).map_err(out_of_range)?;
+        state.number_128 =
+            Number128::try_from_decimal(value_128, exponent).map_err(out_of_range)?;
+
+        Ok(())
+    }
+
+    /// Add to the stored values, failing with a program error on overflow
+    pub fn add(ctx: Context<Update>, value: u64, exponent: i32) -> Result<()> {
+        let number = Number::from_u128(value.into(), exponent).map_err(out_of_range)?;
+        let number_128 = Number128::try_from_decimal(value, exponent).map_err(out_of_range)?;
+
+        let mut state = ctx.accounts.state.load_mut()?;
+        state.number = state.number.safe_add(number)?;
+        state.number_128 = state.number_128.safe_add(number_128)?;
+
+        Ok(())
+    }
+
+    /// Compound the stored `Number` at an annual rate over some seconds
+    pub fn accrue(ctx: Context<Update>, apr_bps: u16, elapsed: u64) -> Result<()> {
+        let mut state = ctx.accounts.state.load_mut()?;
+        state.number = RatePeriod::Second
+            .accrue(state.number, Number::from_bps(apr_bps), elapsed)
+            .map_err(|_| error!(NumericalError::MultiplicationOverflow))?;
+
+        Ok(())
+    }
+
+    /// Convert the stored values back into integers at the given exponent
+    pub fn convert(ctx: Context<Update>, exponent: i32) -> Result<()> {
+        let mut state = ctx.accounts.state.load_mut()?;
+        let converted = to_u64(state.number.try_as_u128(exponent))?;
+
+        require!(
+            converted == to_u64(state.number_128.try_as_u128(exponent))?,
+            TestError::ConversionMismatch
+        );
+        state.converted = converted;
+
+        Ok(())
+    }
+}
+
+fn out_of_range(_: jet_proto_math::Error) -> anchor_lang::error::Error {
+    error!(TestError::ValueOutOfRange)
+}
+
+fn to_u64(value: std::result::Result<u128, jet_proto_math::Error>) -> Result<u64> {
+    value
+        .ok()
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or_else(|| error!(TestError::ValueOutOfRange))
+}
+
+#[error_code]
+pub enum TestError {
+    #[msg("the two representations converted to different values")]
+    ConversionMismatch,
+    #[msg("the value is out of range")]
+    ValueOutOfRange,
+}
+
+#[account(zero_copy)]
+pub struct MathState {
+    pub number_128: Number128,
+    pub number: Number,
+    pub converted: u64,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(zero)]
+    pub state: AccountLoader<'info, MathState>,
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub state: AccountLoader<'info, MathState>,
+}
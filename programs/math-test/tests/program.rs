@@ -0,0 +1,186 @@
+//! Runs the test program under `solana-program-test`.
+//!
+//! Build the program first with
+//! `cargo build-bpf --manifest-path programs/math-test/Cargo.toml`, which
+//! writes `jet_proto_math_test.so` to `target/deploy` where the test
+//! runtime can find it. `cargo test-bpf` with the same manifest path does
+//! both steps.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use jet_proto_math::{traits::NumericalError, Number, Number128};
+use jet_proto_math_test::{accounts, instruction, MathState};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+const COMPUTE_BUDGET: u64 = 50_000;
+
+async fn setup() -> (ProgramTestContext, Pubkey) {
+    let mut test = ProgramTest::new("jet_proto_math_test", jet_proto_math_test::id(), None);
+    test.set_compute_max_units(COMPUTE_BUDGET);
+
+    let state = Keypair::new().pubkey();
+    let size = 8 + std::mem::size_of::<MathState>();
+    test.add_account(
+        state,
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![0; size],
+            owner: jet_proto_math_test::id(),
+            ..Account::default()
+        },
+    );
+
+    let mut context = test.start_with_context().await;
+    send(
+        &mut context,
+        instruction::Initialize {}.data(),
+        accounts::Initialize { state }.to_account_metas(None),
+    )
+    .await
+    .unwrap();
+
+    (context, state)
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    data: Vec<u8>,
+    accounts: Vec<AccountMeta>,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let instruction = Instruction {
+        program_id: jet_proto_math_test::id(),
+        accounts,
+        data,
+    };
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        blockhash,
+    );
+
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn load_state(context: &mut ProgramTestContext, state: Pubkey) -> MathState {
+    let account = context
+        .banks_client
+        .get_account(state)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // skip the 8 byte account discriminator
+    bytemuck::pod_read_unaligned(&account.data[8..])
+}
+
+#[tokio::test]
+async fn layout_round_trips_through_account() {
+    let (mut context, state) = setup().await;
+
+    send(
+        &mut context,
+        instruction::Store {
+            value: 1242,
+            exponent: -3,
+        }
+        .data(),
+        accounts::Update { state }.to_account_metas(None),
+    )
+    .await
+    .unwrap();
+
+    let loaded = load_state(&mut context, state).await;
+    assert_eq!(Number::from_decimal(1242, -3), loaded.number);
+    assert_eq!(Number128::from_decimal(1242, -3), loaded.number_128);
+}
+
+#[tokio::test]
+async fn accrual_and_conversion() {
+    let (mut context, state) = setup().await;
+    let update = accounts::Update { state }.to_account_metas(None);
+
+    send(
+        &mut context,
+        instruction::Store {
+            value: 1_000,
+            exponent: 0,
+        }
+        .data(),
+        update.clone(),
+    )
+    .await
+    .unwrap();
+    send(
+        &mut context,
+        instruction::Convert { exponent: -3 }.data(),
+        update.clone(),
+    )
+    .await
+    .unwrap();
+
+    let loaded = load_state(&mut context, state).await;
+    assert_eq!(1_000_000, loaded.converted);
+
+    send(
+        &mut context,
+        instruction::Accrue {
+            apr_bps: 1000,
+            elapsed: 3_600,
+        }
+        .data(),
+        update,
+    )
+    .await
+    .unwrap();
+
+    let loaded = load_state(&mut context, state).await;
+    assert!(loaded.number > Number::from(1_000u64));
+    assert!(loaded.number < Number::from(1_001u64));
+}
+
+#[tokio::test]
+async fn overflow_maps_to_program_error() {
+    let (mut context, state) = setup().await;
+    let update = accounts::Update { state }.to_account_metas(None);
+
+    // close to the largest Number128, but far from the largest Number
+    let store = instruction::Store {
+        value: 17_014_118_346_046_923_173 * 10u128.pow(9),
+        exponent: 0,
+    };
+
+    send(&mut context, store.data(), update.clone())
+        .await
+        .unwrap();
+
+    // fits in a Number, but not in a Number128
+    let error = send(
+        &mut context,
+        instruction::Add {
+            value: 10u64.pow(19),
+            exponent: 0,
+        }
+        .data(),
+        update,
+    )
+    .await
+    .unwrap_err()
+    .unwrap();
+
+    let expected_code = u32::from(anchor_lang::error::Error::from(
+        NumericalError::AdditionOverflow,
+    ));
+    assert_eq!(
+        TransactionError::InstructionError(0, InstructionError::Custom(expected_code)),
+        error
+    );
+}